@@ -0,0 +1,127 @@
+//! Measures repository throughput under concurrent `list`/`get` load against a
+//! real database.
+//!
+//! Run with `cargo bench --bench repository_throughput`. `DATABASE_URL` must
+//! point at a migrated database; the benchmark seeds its own users and removes
+//! them afterwards. `BENCH_DURATION_SECS` (default 5) sets how long each
+//! concurrency level runs and `DATABASE_POOL_MAX_SIZE` sizes the pool as usual.
+//! Everything runs on a single-threaded runtime, so any gain from higher
+//! concurrency comes from queries overlapping rather than from extra threads.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::rt::System;
+use futures_util::future::join_all;
+use uuid::Uuid;
+
+use iron_cms_api::domain::models::user::{CreateUserHashed, Role};
+use iron_cms_api::domain::repositories::user::{UserQueryParams, UserRepository};
+use iron_cms_api::infrastructure::database::postgresql::db_pool;
+use iron_cms_api::infrastructure::repositories::user::UserRepositoryImpl;
+
+const SEEDED_USERS: usize = 200;
+const CONCURRENCY_LEVELS: [usize; 5] = [1, 4, 16, 64, 128];
+
+struct Report {
+    operations: usize,
+    errors: usize,
+    latencies: Vec<Duration>,
+}
+
+async fn seed(repository: &UserRepositoryImpl, run_id: Uuid) -> Vec<Uuid> {
+    let mut ids = Vec::with_capacity(SEEDED_USERS);
+    for index in 0..SEEDED_USERS {
+        let user = repository.create(&CreateUserHashed {
+            role: Some(Role::User),
+            name: format!("Benchmark user {}", index),
+            email: format!("bench-{}-{}@example.com", run_id, index),
+            password_hash: "not-a-real-hash".to_string(),
+            reset_token: None,
+            reset_token_expiry: None,
+        })
+            .await
+            .expect("Failed to seed benchmark user");
+        ids.push(user.id);
+    }
+    ids
+}
+
+/// Alternates between paged listing and lookups by id until `deadline`.
+async fn worker(repository: Arc<UserRepositoryImpl>, ids: Arc<Vec<Uuid>>, worker_index: usize, deadline: Instant) -> Report {
+    let mut report = Report { operations: 0, errors: 0, latencies: Vec::new() };
+    let mut step = worker_index;
+
+    while Instant::now() < deadline {
+        let started = Instant::now();
+        let result = if step.is_multiple_of(2) {
+            repository.list(UserQueryParams { limit: Some(25), offset: Some(((step * 7) % SEEDED_USERS) as i64) })
+                .await
+                .map(|_| ())
+        } else {
+            repository.get(ids[step % ids.len()]).await.map(|_| ())
+        };
+        report.latencies.push(started.elapsed());
+        report.operations += 1;
+        if result.is_err() {
+            report.errors += 1;
+        }
+        step += 1;
+    }
+
+    report
+}
+
+fn percentile(sorted: &[Duration], percentile: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+    sorted[index]
+}
+
+async fn run() {
+    let duration = Duration::from_secs(
+        std::env::var("BENCH_DURATION_SECS").ok().and_then(|value| value.parse().ok()).unwrap_or(5)
+    );
+    let repository = Arc::new(UserRepositoryImpl::new(Arc::new(db_pool().await)));
+    let run_id = Uuid::new_v4();
+    let ids = Arc::new(seed(&repository, run_id).await);
+
+    println!("{:>11} {:>10} {:>12} {:>10} {:>10} {:>7}", "concurrency", "ops", "ops/sec", "p50 ms", "p99 ms", "errors");
+    for concurrency in CONCURRENCY_LEVELS {
+        let deadline = Instant::now() + duration;
+        let started = Instant::now();
+        let reports = join_all((0..concurrency).map(|index| worker(repository.clone(), ids.clone(), index, deadline))).await;
+        let elapsed = started.elapsed();
+
+        let operations: usize = reports.iter().map(|report| report.operations).sum();
+        let errors: usize = reports.iter().map(|report| report.errors).sum();
+        let mut latencies: Vec<Duration> = reports.into_iter().flat_map(|report| report.latencies).collect();
+        latencies.sort();
+
+        println!(
+            "{:>11} {:>10} {:>12.1} {:>10.2} {:>10.2} {:>7}",
+            concurrency,
+            operations,
+            operations as f64 / elapsed.as_secs_f64(),
+            percentile(&latencies, 0.50).as_secs_f64() * 1000.0,
+            percentile(&latencies, 0.99).as_secs_f64() * 1000.0,
+            errors
+        );
+    }
+
+    for id in ids.iter() {
+        repository.delete(*id).await.expect("Failed to remove benchmark user");
+    }
+}
+
+fn main() {
+    dotenv::dotenv().ok();
+    if std::env::var("DATABASE_URL").is_err() {
+        println!("DATABASE_URL is not set, skipping the repository throughput benchmark");
+        return;
+    }
+
+    System::new().block_on(run());
+}
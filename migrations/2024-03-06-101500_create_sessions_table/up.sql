@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+CREATE TABLE sessions (
+    id              UUID PRIMARY KEY,
+    user_id         UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+    token           VARCHAR(255) NOT NULL UNIQUE,
+    expiry          TIMESTAMP WITH TIME ZONE NOT NULL,
+    user_agent      VARCHAR(255),
+    ip_address      VARCHAR(64),
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at      TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX sessions_user_id_idx ON sessions (user_id);
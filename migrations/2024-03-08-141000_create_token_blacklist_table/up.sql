@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+CREATE TABLE token_blacklist (
+    id              UUID PRIMARY KEY,
+    user_id         UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+    token           VARCHAR(255) NOT NULL UNIQUE,
+    expiry          TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX token_blacklist_expiry_idx ON token_blacklist (expiry);
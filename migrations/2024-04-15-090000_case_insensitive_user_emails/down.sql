@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+-- Normalized emails are left as they are; they are still valid and unique.
+DROP INDEX users_email_lower_key;
+ALTER TABLE users ADD CONSTRAINT users_email_key UNIQUE (email);
@@ -0,0 +1,38 @@
+-- Your SQL goes here
+
+-- Emails that only differ in case, Unicode composition or surrounding whitespace
+-- were accepted as separate accounts. Which of them to keep can't be decided
+-- here, so the migration stops and lists every group until they have been
+-- merged or renamed. `normalize` needs PostgreSQL 13 and a UTF8 database.
+DO $$
+DECLARE
+    collisions text;
+BEGIN
+    SELECT string_agg(collision, E'\n') INTO collisions
+    FROM (
+        SELECT lower(normalize(btrim(email), NFC)) || ': ' || string_agg(id::text || ' <' || email || '>', ', ' ORDER BY created_at) AS collision
+        FROM users
+        GROUP BY lower(normalize(btrim(email), NFC))
+        HAVING count(*) > 1
+    ) AS groups;
+
+    IF collisions IS NOT NULL THEN
+        RAISE EXCEPTION E'Users share an email that only differs in case, composition or whitespace:\n%', collisions
+            USING HINT = 'Merge or rename these accounts, then run the migration again.';
+    END IF;
+END $$;
+
+-- Existing emails get the shape `normalize_email` stores: trimmed, in Unicode
+-- NFC, with a lowercase domain.
+UPDATE users
+SET email = normalized.email
+FROM (
+    SELECT id, substring(trimmed from '^(.*@)') || lower(substring(trimmed from '@([^@]*)$')) AS email
+    FROM (SELECT id, normalize(btrim(email), NFC) AS trimmed FROM users) AS composed
+    WHERE trimmed LIKE '%@%'
+) AS normalized
+WHERE users.id = normalized.id
+  AND users.email <> normalized.email;
+
+ALTER TABLE users DROP CONSTRAINT users_email_key;
+CREATE UNIQUE INDEX users_email_lower_key ON users (lower(email));
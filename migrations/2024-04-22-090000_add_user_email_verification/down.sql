@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+ALTER TABLE users
+    DROP COLUMN email_verified_at,
+    DROP COLUMN email_verification_sent_at;
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+
+ALTER TABLE users
+    ADD COLUMN email_verified_at           TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN email_verification_sent_at  TIMESTAMP WITH TIME ZONE;
+
+-- Accounts created before verification existed are trusted as they are, or
+-- requiring a verified email would lock every one of them out.
+UPDATE users SET email_verified_at = created_at;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+
+DROP TABLE mfa_recovery_codes;
+
+ALTER TABLE users
+    DROP COLUMN totp_secret,
+    DROP COLUMN totp_enabled_at,
+    DROP COLUMN totp_last_used_step;
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+ALTER TABLE users
+    ADD COLUMN totp_secret          VARCHAR(255),
+    ADD COLUMN totp_enabled_at      TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN totp_last_used_step  BIGINT;
+
+CREATE TABLE mfa_recovery_codes (
+    id              UUID PRIMARY KEY,
+    user_id         UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+    code            VARCHAR(255) NOT NULL,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+    UNIQUE (user_id, code)
+);
@@ -0,0 +1,189 @@
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+
+use crate::api::dto::auth::{
+    AuthForgotPasswordDto,
+    AuthLoginDto,
+    AuthMfaChallengeDto,
+    AuthMfaCodeDto,
+    AuthMfaVerifyDto,
+    AuthRefreshDto,
+    AuthRegisterDto,
+    AuthResendVerificationDto,
+    AuthResetPasswordDto,
+    AuthSuccessfulResponseDto,
+    AuthVerifyEmailDto,
+    MfaEnrollmentDto,
+    MfaRecoveryCodesDto
+};
+use crate::api::dto::session::SessionDto;
+use crate::api::dto::user::UserDto;
+use crate::api::validation::ValidatedJson;
+use crate::domain::error::ApiError;
+use crate::domain::models::auth::{AuthLoginOutcome, AuthenticatedUser};
+use crate::domain::models::common::{MessageResponse, MessageWithPaginationResponse};
+use crate::domain::models::session::SessionClient;
+use crate::domain::repositories::repository::ResultPaging;
+use crate::domain::repositories::session::SessionQueryParams;
+use crate::domain::services::auth::AuthService;
+use crate::domain::services::email_verification::EmailVerificationService;
+use crate::domain::services::mfa::MfaService;
+use crate::domain::services::session::SessionService;
+
+const MAX_USER_AGENT_LEN: usize = 255;
+const MAX_IP_ADDRESS_LEN: usize = 64;
+
+/// Collects the client details stored alongside a session. Both values are
+/// client controlled, so they are only truncated to fit and never trusted.
+fn session_client(req: &HttpRequest) -> SessionClient {
+    SessionClient {
+        user_agent: req.headers()
+            .get(actix_web::http::header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LEN).collect()),
+        ip_address: req.connection_info()
+            .realip_remote_addr()
+            .map(|value| value.chars().take(MAX_IP_ADDRESS_LEN).collect()),
+    }
+}
+
+pub async fn login_handler(
+    req: HttpRequest,
+    auth_service: web::Data<dyn AuthService>,
+    post_data: ValidatedJson<AuthLoginDto>,
+) -> Result<HttpResponse, ApiError> {
+    let outcome = auth_service.login(post_data.into_inner().into(), session_client(&req)).await?;
+    Ok(match outcome {
+        AuthLoginOutcome::Authenticated(response) => HttpResponse::Ok().json(AuthSuccessfulResponseDto::from(response)),
+        AuthLoginOutcome::MfaRequired(challenge) => HttpResponse::Accepted().json(AuthMfaChallengeDto::from(challenge)),
+    })
+}
+
+pub async fn verify_mfa_handler(
+    req: HttpRequest,
+    auth_service: web::Data<dyn AuthService>,
+    post_data: ValidatedJson<AuthMfaVerifyDto>,
+) -> Result<web::Json<AuthSuccessfulResponseDto>, ApiError> {
+    let response = auth_service.verify_mfa(post_data.into_inner().into(), session_client(&req)).await?;
+    Ok(web::Json(response.into()))
+}
+
+pub async fn register_handler(
+    req: HttpRequest,
+    auth_service: web::Data<dyn AuthService>,
+    post_data: ValidatedJson<AuthRegisterDto>,
+) -> Result<HttpResponse, ApiError> {
+    let response = auth_service.register(post_data.into_inner().into(), session_client(&req)).await?;
+    Ok(match response {
+        Some(response) => HttpResponse::Ok().json(AuthSuccessfulResponseDto::from(response)),
+        None => HttpResponse::Accepted().json(MessageResponse {
+            message: "Account created, verify your email address to sign in".to_string(),
+        }),
+    })
+}
+
+pub async fn refresh_handler(
+    req: HttpRequest,
+    auth_service: web::Data<dyn AuthService>,
+    post_data: ValidatedJson<AuthRefreshDto>,
+) -> Result<web::Json<AuthSuccessfulResponseDto>, ApiError> {
+    let response = auth_service.refresh(post_data.into_inner().into(), session_client(&req)).await?;
+    Ok(web::Json(response.into()))
+}
+
+pub async fn logout_handler(
+    auth_service: web::Data<dyn AuthService>,
+    authenticated_user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.logout(&authenticated_user.claims).await?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn logout_all_handler(
+    auth_service: web::Data<dyn AuthService>,
+    authenticated_user: AuthenticatedUser,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.logout_all(&authenticated_user.claims).await?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn forgot_password_handler(
+    auth_service: web::Data<dyn AuthService>,
+    post_data: ValidatedJson<AuthForgotPasswordDto>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.forgot_password(post_data.into_inner().into()).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+pub async fn reset_password_handler(
+    auth_service: web::Data<dyn AuthService>,
+    post_data: ValidatedJson<AuthResetPasswordDto>,
+) -> Result<HttpResponse, ApiError> {
+    auth_service.reset_password(post_data.into_inner().into()).await?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn verify_email_handler(
+    email_verification_service: web::Data<dyn EmailVerificationService>,
+    post_data: ValidatedJson<AuthVerifyEmailDto>,
+) -> Result<web::Json<UserDto>, ApiError> {
+    let user = email_verification_service.verify_email(post_data.into_inner().into()).await?;
+    Ok(web::Json(user.into()))
+}
+
+pub async fn resend_verification_handler(
+    email_verification_service: web::Data<dyn EmailVerificationService>,
+    post_data: ValidatedJson<AuthResendVerificationDto>,
+) -> Result<HttpResponse, ApiError> {
+    email_verification_service.resend_verification(post_data.into_inner().into()).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+pub async fn enroll_mfa_handler(
+    mfa_service: web::Data<dyn MfaService>,
+    authenticated_user: AuthenticatedUser,
+) -> Result<web::Json<MfaEnrollmentDto>, ApiError> {
+    let enrollment = mfa_service.enroll(&authenticated_user.user).await?;
+    Ok(web::Json(enrollment.into()))
+}
+
+pub async fn confirm_mfa_handler(
+    mfa_service: web::Data<dyn MfaService>,
+    authenticated_user: AuthenticatedUser,
+    post_data: ValidatedJson<AuthMfaCodeDto>,
+) -> Result<web::Json<MfaRecoveryCodesDto>, ApiError> {
+    let recovery_codes = mfa_service.confirm_enrollment(&authenticated_user.user, post_data.into_inner().into()).await?;
+    Ok(web::Json(recovery_codes.into()))
+}
+
+pub async fn disable_mfa_handler(
+    mfa_service: web::Data<dyn MfaService>,
+    authenticated_user: AuthenticatedUser,
+    post_data: ValidatedJson<AuthMfaCodeDto>,
+) -> Result<HttpResponse, ApiError> {
+    mfa_service.disable(&authenticated_user.user, post_data.into_inner().into()).await?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub async fn regenerate_recovery_codes_handler(
+    mfa_service: web::Data<dyn MfaService>,
+    authenticated_user: AuthenticatedUser,
+    post_data: ValidatedJson<AuthMfaCodeDto>,
+) -> Result<web::Json<MfaRecoveryCodesDto>, ApiError> {
+    let recovery_codes = mfa_service.regenerate_recovery_codes(&authenticated_user.user, post_data.into_inner().into()).await?;
+    Ok(web::Json(recovery_codes.into()))
+}
+
+pub async fn me_handler(
+    authenticated_user: AuthenticatedUser,
+) -> Result<web::Json<UserDto>, ApiError> {
+    Ok(web::Json(authenticated_user.user.into()))
+}
+
+pub async fn list_own_session_handler(
+    session_service: web::Data<dyn SessionService>,
+    authenticated_user: AuthenticatedUser,
+    params: web::Query<SessionQueryParams>,
+) -> Result<web::Json<MessageWithPaginationResponse<SessionDto>>, ApiError> {
+    let sessions: ResultPaging<SessionDto> = session_service.list_by_user(authenticated_user.user.id, params.into_inner()).await?.into();
+    Ok(web::Json(sessions.into_response("Sessions retrieved successfully")))
+}
@@ -0,0 +1,13 @@
+use actix_web::{web, Result};
+
+use crate::api::dto::error::ErrorCodeDto;
+use crate::domain::error::ApiError;
+use crate::domain::models::common::MessageWithListOfObjectsResponse;
+use crate::error_codes::ERROR_CATALOG;
+
+pub async fn list_error_code_handler() -> Result<web::Json<MessageWithListOfObjectsResponse<ErrorCodeDto>>, ApiError> {
+    Ok(web::Json(MessageWithListOfObjectsResponse {
+        message: "Error codes retrieved successfully".to_string(),
+        objects: ERROR_CATALOG.iter().map(ErrorCodeDto::from).collect(),
+    }))
+}
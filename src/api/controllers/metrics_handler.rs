@@ -0,0 +1,11 @@
+use actix_web::{web, Result};
+
+use crate::api::dto::metrics::HashingMetricsDto;
+use crate::domain::error::ApiError;
+use crate::services::concrete::hashing_pool::HashingPool;
+
+pub async fn get_hashing_metrics_handler(
+    hashing_pool: web::Data<HashingPool>,
+) -> Result<web::Json<HashingMetricsDto>, ApiError> {
+    Ok(web::Json(hashing_pool.metrics().into()))
+}
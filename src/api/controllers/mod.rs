@@ -1,2 +1,6 @@
 pub mod admin_permission_handler;
-pub mod user_handler;
+pub mod auth_handler;
+pub mod error_handler;
+pub mod metrics_handler;
+pub mod session_handler;
+pub mod user_handler;
\ No newline at end of file
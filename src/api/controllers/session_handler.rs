@@ -0,0 +1,20 @@
+use actix_web::{web, Result};
+use uuid::Uuid;
+
+use crate::api::dto::session::SessionDto;
+use crate::domain::error::ApiError;
+use crate::domain::models::auth::AuthenticatedUser;
+use crate::domain::models::common::MessageWithPaginationResponse;
+use crate::domain::repositories::repository::ResultPaging;
+use crate::domain::repositories::session::SessionQueryParams;
+use crate::domain::services::session::SessionService;
+
+pub async fn list_user_session_handler(
+    session_service: web::Data<dyn SessionService>,
+    _authenticated_user: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+    params: web::Query<SessionQueryParams>,
+) -> Result<web::Json<MessageWithPaginationResponse<SessionDto>>, ApiError> {
+    let sessions: ResultPaging<SessionDto> = session_service.list_by_user(user_id.into_inner(), params.into_inner()).await?.into();
+    Ok(web::Json(sessions.into_response("Sessions retrieved successfully")))
+}
@@ -1,49 +1,74 @@
 use actix_web::{web, HttpResponse, Result};
 use uuid::Uuid;
 
-use crate::api::dto::user::{UserDto, CreateUserPlainTextDto, UpdateUserPlainTextDto};
+use crate::api::dto::user::{UserDetailsDto, CreateUserPlainTextDto, ImportUsersDto, UpdateUserPlainTextDto};
+use crate::api::validation::ValidatedJson;
 use crate::domain::error::ApiError;
+use crate::domain::models::auth::AuthenticatedUser;
+use crate::domain::models::common::{MessageWithListOfObjectsResponse, MessageWithPaginationResponse};
+use crate::domain::models::user::CreateUserHashed;
 use crate::domain::repositories::repository::ResultPaging;
 use crate::domain::repositories::user::UserQueryParams;
 use crate::domain::services::user::UserService;
 
 pub async fn create_user_handler(
     user_service: web::Data<dyn UserService>,
-    post_data: web::Json<CreateUserPlainTextDto>,
-) -> Result<web::Json<UserDto>, ApiError> {
-    let user = user_service.create(post_data.into_inner().into()).await?;
+    authenticated_user: AuthenticatedUser,
+    post_data: ValidatedJson<CreateUserPlainTextDto>,
+) -> Result<web::Json<UserDetailsDto>, ApiError> {
+    let user = user_service.create(post_data.into_inner().try_into()?, Some(&authenticated_user.user)).await?;
     Ok(web::Json(user.into()))
 }
 
+pub async fn import_user_handler(
+    user_service: web::Data<dyn UserService>,
+    authenticated_user: AuthenticatedUser,
+    post_data: ValidatedJson<ImportUsersDto>,
+) -> Result<web::Json<MessageWithListOfObjectsResponse<UserDetailsDto>>, ApiError> {
+    let new_users = post_data.into_inner().users
+        .into_iter()
+        .map(CreateUserHashed::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let users = user_service.import(new_users, &authenticated_user.user).await?;
+    Ok(web::Json(MessageWithListOfObjectsResponse {
+        message: "Users imported successfully".to_string(),
+        objects: users.into_iter().map(UserDetailsDto::from).collect(),
+    }))
+}
+
 pub async fn list_user_handler(
     user_service: web::Data<dyn UserService>,
+    _authenticated_user: AuthenticatedUser,
     params: web::Query<UserQueryParams>,
-) -> Result<web::Json<ResultPaging<UserDto>>, ApiError> {
-    let users = user_service.list(params.into_inner()).await?;
-    Ok(web::Json(users.into()))
+) -> Result<web::Json<MessageWithPaginationResponse<UserDetailsDto>>, ApiError> {
+    let users: ResultPaging<UserDetailsDto> = user_service.list(params.into_inner()).await?.into();
+    Ok(web::Json(users.into_response("Users retrieved successfully")))
 }
 
 pub async fn get_user_handler(
     user_service: web::Data<dyn UserService>,
+    _authenticated_user: AuthenticatedUser,
     user_id: web::Path<Uuid>,
-) -> Result<web::Json<UserDto>, ApiError> {
+) -> Result<web::Json<UserDetailsDto>, ApiError> {
     let user = user_service.get(user_id.into_inner()).await?;
     Ok(web::Json(user.into()))
 }
 
 pub async fn update_user_handler(
     user_service: web::Data<dyn UserService>,
+    authenticated_user: AuthenticatedUser,
     user_id: web::Path<Uuid>,
-    put_data: web::Json<UpdateUserPlainTextDto>,
-) -> Result<web::Json<UserDto>, ApiError> {
-    let user = user_service.update(user_id.into_inner(), put_data.into_inner().into()).await?;
+    put_data: ValidatedJson<UpdateUserPlainTextDto>,
+) -> Result<web::Json<UserDetailsDto>, ApiError> {
+    let user = user_service.update(user_id.into_inner(), put_data.into_inner().try_into()?, &authenticated_user.user).await?;
     Ok(web::Json(user.into()))
 }
 
 pub async fn delete_user_handler(
     user_service: web::Data<dyn UserService>,
+    authenticated_user: AuthenticatedUser,
     user_id: web::Path<Uuid>,
 ) -> Result<HttpResponse, ApiError> {
-    user_service.delete(user_id.into_inner()).await?;
+    user_service.delete(user_id.into_inner(), &authenticated_user.user).await?;
     Ok(HttpResponse::Ok().finish())
 }
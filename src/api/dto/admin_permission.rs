@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::models::admin_permission::{AdminPermission, CreateAdminPermission, UpdateAdminPermission};
+use crate::api::validation::{EnumValue, FieldErrors, Validate};
+use crate::domain::error::ApiError;
+use crate::domain::models::admin_permission::{AdminPermission, AdminPermissions, CreateAdminPermission, UpdateAdminPermission};
+use crate::domain::models::common::NamedEnum;
 use crate::domain::repositories::repository::ResultPaging;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,18 +12,21 @@ pub struct AdminPermissionDto {
     pub id: String,
     pub user_id: String,
     pub permission: i32,
+    pub permission_name: String,
 }
 
+/// `permission` accepts either the number or the stable name, e.g.
+/// `"can_manage_vocabulary"`.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateAdminPermissionDto {
     pub user_id: String,
-    pub permission: i32,
+    pub permission: EnumValue,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateAdminPermissionDto {
     pub user_id: String,
-    pub permission: i32,
+    pub permission: EnumValue,
 }
 
 impl From<AdminPermission> for AdminPermissionDto {
@@ -28,26 +34,54 @@ impl From<AdminPermission> for AdminPermissionDto {
         AdminPermissionDto {
             id: admin_permission.id.to_string(),
             user_id: admin_permission.user_id.to_string(),
-            permission: admin_permission.permission as i32,
+            permission: admin_permission.permission.as_i32(),
+            permission_name: admin_permission.permission.as_str().to_string(),
         }
     }
 }
 
-impl From<CreateAdminPermissionDto> for CreateAdminPermission {
-    fn from(dto: CreateAdminPermissionDto) -> Self {
-        CreateAdminPermission {
-            user_id: Uuid::parse_str(&dto.user_id).unwrap(),
-            permission: dto.permission.into(),
-        }
+/// Checks the fields shared by the create and update DTOs and returns them
+/// decoded.
+fn parse_permission_fields(user_id: &str, permission: &EnumValue) -> Result<(Uuid, AdminPermissions), ApiError> {
+    let mut errors = FieldErrors::new();
+    let user_id = if errors.required("user_id", user_id) {
+        errors.uuid("user_id", user_id)
+    } else {
+        None
+    };
+    let permission = errors.enum_value::<AdminPermissions>("permission", permission);
+    errors.into_result()?;
+
+    Ok(user_id.zip(permission).expect("invalid fields are reported above"))
+}
+
+impl Validate for CreateAdminPermissionDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        parse_permission_fields(&self.user_id, &self.permission).map(|_| ())
     }
 }
 
-impl From<UpdateAdminPermissionDto> for UpdateAdminPermission {
-    fn from(dto: UpdateAdminPermissionDto) -> Self {
-        UpdateAdminPermission {
-            user_id: Uuid::parse_str(&dto.user_id).unwrap(),
-            permission: dto.permission.into(),
-        }
+impl Validate for UpdateAdminPermissionDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        parse_permission_fields(&self.user_id, &self.permission).map(|_| ())
+    }
+}
+
+impl TryFrom<CreateAdminPermissionDto> for CreateAdminPermission {
+    type Error = ApiError;
+
+    fn try_from(dto: CreateAdminPermissionDto) -> Result<Self, Self::Error> {
+        let (user_id, permission) = parse_permission_fields(&dto.user_id, &dto.permission)?;
+        Ok(CreateAdminPermission { user_id, permission })
+    }
+}
+
+impl TryFrom<UpdateAdminPermissionDto> for UpdateAdminPermission {
+    type Error = ApiError;
+
+    fn try_from(dto: UpdateAdminPermissionDto) -> Result<Self, Self::Error> {
+        let (user_id, permission) = parse_permission_fields(&dto.user_id, &dto.permission)?;
+        Ok(UpdateAdminPermission { user_id, permission })
     }
 }
 
@@ -55,7 +89,7 @@ impl From<AdminPermission> for UpdateAdminPermissionDto {
     fn from(admin_permission: AdminPermission) -> Self {
         UpdateAdminPermissionDto {
             user_id: admin_permission.user_id.to_string(),
-            permission: admin_permission.permission as i32,
+            permission: admin_permission.permission.into(),
         }
     }
 }
@@ -64,7 +98,7 @@ impl From<AdminPermission> for CreateAdminPermissionDto {
     fn from(admin_permission: AdminPermission) -> Self {
         CreateAdminPermissionDto {
             user_id: admin_permission.user_id.to_string(),
-            permission: admin_permission.permission as i32,
+            permission: admin_permission.permission.into(),
         }
     }
 }
@@ -74,6 +108,8 @@ impl From<ResultPaging<AdminPermission>> for ResultPaging<AdminPermissionDto> {
         ResultPaging {
             items: result.items.into_iter().map(AdminPermissionDto::from).collect(),
             total: result.total,
+            limit: result.limit,
+            offset: result.offset,
         }
     }
 }
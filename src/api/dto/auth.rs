@@ -0,0 +1,290 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::api::validation::{FieldErrors, Validate};
+use crate::domain::error::ApiError;
+use crate::domain::models::auth::{
+    AuthForgotPassword,
+    AuthLogin,
+    AuthMfaChallenge,
+    AuthMfaCode,
+    AuthMfaVerify,
+    AuthRefresh,
+    AuthRegister,
+    AuthResendVerification,
+    AuthResetPassword,
+    AuthSuccessfulResponse,
+    AuthVerifyEmail
+};
+use crate::domain::models::mfa::{MfaEnrollment, MfaRecoveryCodes};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthLoginDto {
+    pub email: String,
+    pub password: String,
+}
+
+/// Unknown fields are rejected so that a client can't sneak a `role` into the
+/// registration payload and pick an elevated role for itself.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthRegisterDto {
+    pub email: String,
+    pub name: String,
+    pub password: String,
+    pub confirm_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthRefreshDto {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthForgotPasswordDto {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthResetPasswordDto {
+    pub token: String,
+    pub password: String,
+    pub confirm_password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthVerifyEmailDto {
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthResendVerificationDto {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthMfaCodeDto {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthMfaVerifyDto {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthSuccessfulResponseDto {
+    pub token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthMfaChallengeDto {
+    pub challenge_token: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MfaEnrollmentDto {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MfaRecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
+
+impl Validate for AuthLoginDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required("email", &self.email);
+        errors.required("password", &self.password);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthRegisterDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required_email("email", &self.email);
+        errors.required_text("name", &self.name);
+        errors.required("password", &self.password);
+        errors.required("confirm_password", &self.confirm_password);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthRefreshDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required("refresh_token", &self.refresh_token);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthForgotPasswordDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required_email("email", &self.email);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthResetPasswordDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required("token", &self.token);
+        errors.required("password", &self.password);
+        errors.required("confirm_password", &self.confirm_password);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthVerifyEmailDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required("token", &self.token);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthResendVerificationDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required_email("email", &self.email);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthMfaCodeDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required("code", &self.code);
+        errors.into_result()
+    }
+}
+
+impl Validate for AuthMfaVerifyDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        errors.required("challenge_token", &self.challenge_token);
+        errors.required("code", &self.code);
+        errors.into_result()
+    }
+}
+
+impl From<AuthLoginDto> for AuthLogin {
+    fn from(dto: AuthLoginDto) -> Self {
+        AuthLogin {
+            email: dto.email,
+            password: dto.password,
+        }
+    }
+}
+
+impl From<AuthRegisterDto> for AuthRegister {
+    fn from(dto: AuthRegisterDto) -> Self {
+        AuthRegister {
+            email: dto.email,
+            name: dto.name,
+            password: dto.password,
+            confirm_password: dto.confirm_password,
+        }
+    }
+}
+
+impl From<AuthRefreshDto> for AuthRefresh {
+    fn from(dto: AuthRefreshDto) -> Self {
+        AuthRefresh {
+            refresh_token: dto.refresh_token,
+        }
+    }
+}
+
+impl From<AuthForgotPasswordDto> for AuthForgotPassword {
+    fn from(dto: AuthForgotPasswordDto) -> Self {
+        AuthForgotPassword {
+            email: dto.email,
+        }
+    }
+}
+
+impl From<AuthResetPasswordDto> for AuthResetPassword {
+    fn from(dto: AuthResetPasswordDto) -> Self {
+        AuthResetPassword {
+            token: dto.token,
+            password: dto.password,
+            confirm_password: dto.confirm_password,
+        }
+    }
+}
+
+impl From<AuthVerifyEmailDto> for AuthVerifyEmail {
+    fn from(dto: AuthVerifyEmailDto) -> Self {
+        AuthVerifyEmail {
+            token: dto.token,
+        }
+    }
+}
+
+impl From<AuthResendVerificationDto> for AuthResendVerification {
+    fn from(dto: AuthResendVerificationDto) -> Self {
+        AuthResendVerification {
+            email: dto.email,
+        }
+    }
+}
+
+impl From<AuthMfaCodeDto> for AuthMfaCode {
+    fn from(dto: AuthMfaCodeDto) -> Self {
+        AuthMfaCode {
+            code: dto.code,
+        }
+    }
+}
+
+impl From<AuthMfaVerifyDto> for AuthMfaVerify {
+    fn from(dto: AuthMfaVerifyDto) -> Self {
+        AuthMfaVerify {
+            challenge_token: dto.challenge_token,
+            code: dto.code,
+        }
+    }
+}
+
+impl From<AuthSuccessfulResponse> for AuthSuccessfulResponseDto {
+    fn from(response: AuthSuccessfulResponse) -> Self {
+        AuthSuccessfulResponseDto {
+            token: response.token,
+            refresh_token: response.refresh_token,
+        }
+    }
+}
+
+impl From<AuthMfaChallenge> for AuthMfaChallengeDto {
+    fn from(challenge: AuthMfaChallenge) -> Self {
+        AuthMfaChallengeDto {
+            challenge_token: challenge.challenge_token,
+            expires_at: challenge.expires_at,
+        }
+    }
+}
+
+impl From<MfaEnrollment> for MfaEnrollmentDto {
+    fn from(enrollment: MfaEnrollment) -> Self {
+        MfaEnrollmentDto {
+            secret: enrollment.secret,
+            otpauth_uri: enrollment.otpauth_uri,
+        }
+    }
+}
+
+impl From<MfaRecoveryCodes> for MfaRecoveryCodesDto {
+    fn from(recovery_codes: MfaRecoveryCodes) -> Self {
+        MfaRecoveryCodesDto {
+            recovery_codes: recovery_codes.codes,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::status_code;
+use crate::domain::models::common::ErrorResponseType;
+use crate::error_codes::ErrorCodeEntry;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorCodeDto {
+    pub identifier: String,
+    pub code: u32,
+    pub status: u16,
+    pub error_type: ErrorResponseType,
+    pub message: String,
+}
+
+impl From<&ErrorCodeEntry> for ErrorCodeDto {
+    fn from(entry: &ErrorCodeEntry) -> Self {
+        ErrorCodeDto {
+            identifier: entry.identifier.to_string(),
+            code: entry.code,
+            status: status_code(entry.error_type).as_u16(),
+            error_type: entry.error_type,
+            message: entry.message.to_string(),
+        }
+    }
+}
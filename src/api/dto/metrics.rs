@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::concrete::hashing_pool::HashingPoolMetrics;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HashingMetricsDto {
+    pub workers: usize,
+    pub queue_capacity: usize,
+    pub queue_depth: usize,
+    pub in_flight: usize,
+    pub completed: u64,
+    pub rejected: u64,
+    pub avg_wait_ms: f64,
+    pub avg_hash_ms: f64,
+    pub max_hash_ms: f64,
+}
+
+impl From<HashingPoolMetrics> for HashingMetricsDto {
+    fn from(metrics: HashingPoolMetrics) -> Self {
+        HashingMetricsDto {
+            workers: metrics.workers,
+            queue_capacity: metrics.queue_capacity,
+            queue_depth: metrics.queue_depth,
+            in_flight: metrics.in_flight,
+            completed: metrics.completed,
+            rejected: metrics.rejected,
+            avg_wait_ms: metrics.avg_wait_ms,
+            avg_hash_ms: metrics.avg_hash_ms,
+            max_hash_ms: metrics.max_hash_ms,
+        }
+    }
+}
@@ -1,2 +1,6 @@
 pub mod admin_permission;
-pub mod user;
+pub mod auth;
+pub mod error;
+pub mod metrics;
+pub mod session;
+pub mod user;
\ No newline at end of file
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::domain::models::session::Session;
+use crate::domain::repositories::repository::ResultPaging;
+
+/// Public view of a session. The stored token digest is intentionally left out.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionDto {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub expiry: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<Session> for SessionDto {
+    fn from(session: Session) -> Self {
+        SessionDto {
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            expiry: session.expiry,
+            created_at: session.created_at,
+        }
+    }
+}
+
+impl From<ResultPaging<Session>> for ResultPaging<SessionDto> {
+    fn from(result_paging: ResultPaging<Session>) -> Self {
+        ResultPaging {
+            items: result_paging.items.into_iter().map(SessionDto::from).collect(),
+            total: result_paging.total,
+            limit: result_paging.limit,
+            offset: result_paging.offset,
+        }
+    }
+}
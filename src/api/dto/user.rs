@@ -2,7 +2,12 @@ use serde::{Deserialize, Serialize};
 use chrono::NaiveDateTime;
 use uuid::Uuid;
 
+use crate::api::validation::{EnumValue, FieldErrors, Validate};
+use crate::services::constants;
+use crate::domain::error::{ApiError, CommonError};
+use crate::domain::models::common::NamedEnum;
 use crate::domain::models::user::{
+    Role,
     User,
     CreateUserPlainText,
     UpdateUserPlainText,
@@ -11,44 +16,60 @@ use crate::domain::models::user::{
 };
 use crate::domain::repositories::repository::ResultPaging;
 
+/// Public view of a user. Credential material (password hash, reset token)
+/// never leaves the server.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserDto {
     pub id: Uuid,
     pub role: i32,
+    pub role_name: String,
     pub name: String,
     pub email: String,
-    pub password_hash: String,
-    pub reset_token: Option<String>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+/// Detailed view of a user for user management. It tells whether a password
+/// reset is pending, but not the reset token itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserDetailsDto {
+    pub id: Uuid,
+    pub role: i32,
+    pub role_name: String,
+    pub name: String,
+    pub email: String,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    pub reset_pending: bool,
     pub reset_token_expiry: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: Option<NaiveDateTime>,
 }
 
+/// `role` accepts either the number or the stable name, e.g. `"super_admin"`.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateUserPlainTextDto {
-    pub role: Option<i32>,
+    pub role: Option<EnumValue>,
     pub name: String,
     pub email: String,
     pub password: String,
     pub confirm_password: String,
-    pub reset_token: Option<String>,
-    pub reset_token_expiry: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateUserPlainTextDto {
-    pub role: Option<i32>,
+    pub role: Option<EnumValue>,
     pub name: Option<String>,
     pub email: Option<String>,
     pub password: Option<String>,
     pub confirm_password: Option<String>,
-    pub reset_token: Option<String>,
-    pub reset_token_expiry: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateUserHashedDto {
-    pub role: Option<i32>,
+    pub role: Option<EnumValue>,
     pub name: String,
     pub email: String,
     pub password_hash: String,
@@ -58,7 +79,7 @@ pub struct CreateUserHashedDto {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateUserHashedDto {
-    pub role: Option<i32>,
+    pub role: Option<EnumValue>,
     pub name: Option<String>,
     pub email: Option<String>,
     pub password_hash: Option<String>,
@@ -66,60 +87,169 @@ pub struct UpdateUserHashedDto {
     pub reset_token_expiry: Option<NaiveDateTime>,
 }
 
+/// A user moved over from another system, with the password hash it had there.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportUserDto {
+    pub role: Option<EnumValue>,
+    pub name: String,
+    pub email: String,
+    pub password_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportUsersDto {
+    pub users: Vec<ImportUserDto>,
+}
+
+impl Validate for ImportUsersDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        if self.users.is_empty() {
+            errors.required("users", "");
+        }
+        if self.users.len() > constants::USER_IMPORT_MAX_USERS {
+            errors.add("users", constants::VAL_ERR_TOO_MANY_ITEMS, std::format!(
+                "`users` must contain at most {} users", constants::USER_IMPORT_MAX_USERS
+            ));
+        }
+        for (index, user) in self.users.iter().enumerate() {
+            let field = |name: &str| std::format!("users[{}].{}", index, name);
+            if let Some(role) = &user.role {
+                errors.enum_value::<Role>(&field("role"), role);
+            }
+            errors.required_text(&field("name"), &user.name);
+            errors.required_email(&field("email"), &user.email);
+            errors.required(&field("password_hash"), &user.password_hash);
+        }
+        errors.into_result()
+    }
+}
+
+impl Validate for CreateUserPlainTextDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        if let Some(role) = &self.role {
+            errors.enum_value::<Role>("role", role);
+        }
+        errors.required_text("name", &self.name);
+        errors.required_email("email", &self.email);
+        errors.required("password", &self.password);
+        errors.required("confirm_password", &self.confirm_password);
+        errors.into_result()
+    }
+}
+
+impl Validate for UpdateUserPlainTextDto {
+    fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = FieldErrors::new();
+        if let Some(role) = &self.role {
+            errors.enum_value::<Role>("role", role);
+        }
+        if let Some(name) = &self.name {
+            errors.required_text("name", name);
+        }
+        if let Some(email) = &self.email {
+            errors.required_email("email", email);
+        }
+        // A password change needs both fields, otherwise it would be silently ignored.
+        match (&self.password, &self.confirm_password) {
+            (Some(password), Some(confirm_password)) => {
+                errors.required("password", password);
+                errors.required("confirm_password", confirm_password);
+            },
+            (Some(_), None) => { errors.required("confirm_password", ""); },
+            (None, Some(_)) => { errors.required("password", ""); },
+            (None, None) => {},
+        }
+        errors.into_result()
+    }
+}
+
 impl From<User> for UserDto {
     fn from(user: User) -> Self {
         UserDto {
             id: user.id,
-            role: user.role as i32,
+            role: user.role.as_i32(),
+            role_name: user.role.as_str().to_string(),
+            name: user.name,
+            email: user.email,
+            email_verified_at: user.email_verified_at,
+            totp_enabled_at: user.totp_enabled_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+impl From<User> for UserDetailsDto {
+    fn from(user: User) -> Self {
+        // An expired token can no longer be redeemed, so it doesn't count as pending.
+        let reset_token_expiry = user.reset_token
+            .and(user.reset_token_expiry)
+            .filter(|expiry| *expiry > chrono::Utc::now().naive_utc());
+
+        UserDetailsDto {
+            id: user.id,
+            role: user.role.as_i32(),
+            role_name: user.role.as_str().to_string(),
             name: user.name,
             email: user.email,
-            password_hash: user.password_hash,
-            reset_token: user.reset_token,
-            reset_token_expiry: user.reset_token_expiry,
+            email_verified_at: user.email_verified_at,
+            totp_enabled_at: user.totp_enabled_at,
+            reset_pending: reset_token_expiry.is_some(),
+            reset_token_expiry,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
 }
 
-impl From<CreateUserPlainTextDto> for CreateUserPlainText {
-    fn from(dto: CreateUserPlainTextDto) -> Self {
-        CreateUserPlainText {
-            role: dto.role.map(|role| role.into()),
+fn parse_role(role: Option<&EnumValue>) -> Result<Option<Role>, ApiError> {
+    role.map(EnumValue::parse::<Role>)
+        .transpose()
+        .map_err(|err| CommonError::from(err).into())
+}
+
+impl TryFrom<CreateUserPlainTextDto> for CreateUserPlainText {
+    type Error = ApiError;
+
+    fn try_from(dto: CreateUserPlainTextDto) -> Result<Self, Self::Error> {
+        Ok(CreateUserPlainText {
+            role: parse_role(dto.role.as_ref())?,
             name: dto.name,
             email: dto.email,
             password: dto.password,
             confirm_password: dto.confirm_password,
-            reset_token: dto.reset_token,
-            reset_token_expiry: dto.reset_token_expiry,
-        }
+            reset_token: None,
+            reset_token_expiry: None,
+        })
     }
 }
 
-impl From<UpdateUserPlainTextDto> for UpdateUserPlainText {
-    fn from(dto: UpdateUserPlainTextDto) -> Self {
-        UpdateUserPlainText {
-            role: dto.role.map(|role| role.into()),
+impl TryFrom<UpdateUserPlainTextDto> for UpdateUserPlainText {
+    type Error = ApiError;
+
+    fn try_from(dto: UpdateUserPlainTextDto) -> Result<Self, Self::Error> {
+        Ok(UpdateUserPlainText {
+            role: parse_role(dto.role.as_ref())?,
             name: dto.name,
             email: dto.email,
             password: dto.password,
             confirm_password: dto.confirm_password,
-            reset_token: dto.reset_token,
-            reset_token_expiry: dto.reset_token_expiry,
-        }
+            reset_token: None,
+            reset_token_expiry: None,
+        })
     }
 }
 
 impl From<CreateUserPlainText> for CreateUserPlainTextDto {
     fn from(create_user: CreateUserPlainText) -> Self {
         CreateUserPlainTextDto {
-            role: create_user.role.map(|role| role as i32),
+            role: create_user.role.map(EnumValue::from),
             name: create_user.name,
             email: create_user.email,
             password: create_user.password,
             confirm_password: create_user.confirm_password,
-            reset_token: create_user.reset_token,
-            reset_token_expiry: create_user.reset_token_expiry,
         }
     }
 }
@@ -127,47 +257,65 @@ impl From<CreateUserPlainText> for CreateUserPlainTextDto {
 impl From<UpdateUserPlainText> for UpdateUserPlainTextDto {
     fn from(update_user: UpdateUserPlainText) -> Self {
         UpdateUserPlainTextDto {
-            role: update_user.role.map(|role| role as i32),
+            role: update_user.role.map(EnumValue::from),
             name: update_user.name,
             email: update_user.email,
             password: update_user.password,
             confirm_password: update_user.confirm_password,
-            reset_token: update_user.reset_token,
-            reset_token_expiry: update_user.reset_token_expiry,
         }
     }
 }
 
-impl From<CreateUserHashedDto> for CreateUserHashed {
-    fn from(dto: CreateUserHashedDto) -> Self {
-        CreateUserHashed {
-            role: dto.role.map(|role| role.into()),
+impl TryFrom<CreateUserHashedDto> for CreateUserHashed {
+    type Error = ApiError;
+
+    fn try_from(dto: CreateUserHashedDto) -> Result<Self, Self::Error> {
+        Ok(CreateUserHashed {
+            role: parse_role(dto.role.as_ref())?,
             name: dto.name,
             email: dto.email,
             password_hash: dto.password_hash,
             reset_token: dto.reset_token,
             reset_token_expiry: dto.reset_token_expiry,
-        }
+        })
     }
 }
 
-impl From<UpdateUserHashedDto> for UpdateUserHashed {
-    fn from(dto: UpdateUserHashedDto) -> Self {
-        UpdateUserHashed {
-            role: dto.role.map(|role| role.into()),
+impl TryFrom<ImportUserDto> for CreateUserHashed {
+    type Error = ApiError;
+
+    fn try_from(dto: ImportUserDto) -> Result<Self, Self::Error> {
+        Ok(CreateUserHashed {
+            role: parse_role(dto.role.as_ref())?,
+            name: dto.name,
+            email: dto.email,
+            password_hash: dto.password_hash,
+            reset_token: None,
+            reset_token_expiry: None,
+        })
+    }
+}
+
+impl TryFrom<UpdateUserHashedDto> for UpdateUserHashed {
+    type Error = ApiError;
+
+    fn try_from(dto: UpdateUserHashedDto) -> Result<Self, Self::Error> {
+        Ok(UpdateUserHashed {
+            role: parse_role(dto.role.as_ref())?,
             name: dto.name,
             email: dto.email,
             password_hash: dto.password_hash,
             reset_token: dto.reset_token,
             reset_token_expiry: dto.reset_token_expiry,
-        }
+            email_verified_at: None,
+        })
     }
 }
 
 impl From<UpdateUserHashed> for UpdateUserHashedDto {
     fn from(update_user: UpdateUserHashed) -> Self {
         UpdateUserHashedDto {
-            role: update_user.role.map(|role| role as i32),
+            role: update_user.role.map(EnumValue::from),
             name: update_user.name,
             email: update_user.email,
             password_hash: update_user.password_hash,
@@ -180,7 +328,7 @@ impl From<UpdateUserHashed> for UpdateUserHashedDto {
 impl From<CreateUserHashed> for CreateUserHashedDto {
     fn from(create_user: CreateUserHashed) -> Self {
         CreateUserHashedDto {
-            role: create_user.role.map(|role| role as i32),
+            role: create_user.role.map(EnumValue::from),
             name: create_user.name,
             email: create_user.email,
             password_hash: create_user.password_hash,
@@ -195,6 +343,19 @@ impl From<ResultPaging<User>> for ResultPaging<UserDto> {
         ResultPaging {
             items: result_paging.items.into_iter().map(UserDto::from).collect(),
             total: result_paging.total,
+            limit: result_paging.limit,
+            offset: result_paging.offset,
+        }
+    }
+}
+
+impl From<ResultPaging<User>> for ResultPaging<UserDetailsDto> {
+    fn from(result_paging: ResultPaging<User>) -> Self {
+        ResultPaging {
+            items: result_paging.items.into_iter().map(UserDetailsDto::from).collect(),
+            total: result_paging.total,
+            limit: result_paging.limit,
+            offset: result_paging.offset,
         }
     }
 }
@@ -0,0 +1,236 @@
+use std::rc::Rc;
+
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::{web, Error, FromRequest, HttpMessage, HttpRequest};
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+
+use crate::domain::error::{ApiError, CommonError};
+use crate::domain::models::admin_permission::AdminPermissions;
+use crate::domain::models::auth::{AuthenticatedUser, VerifiedAction};
+use crate::domain::models::user::Role;
+use crate::domain::services::admin_permission::AdminPermissionService;
+use crate::domain::services::auth::AuthService;
+use crate::domain::services::email_verification::EmailVerificationService;
+use crate::domain::services::mfa::MfaService;
+use crate::services::constants;
+use crate::services::error::{AuthError, PermissionError};
+
+fn auth_error(error_identifier: &str, message: &str) -> CommonError {
+    CommonError::from(AuthError {
+        identifier: error_identifier.to_string(),
+        message: message.to_string(),
+        context: constants::ERR_CONTEXT_AUTHENTICATION.to_string(),
+    })
+}
+
+fn permission_error(message: &str) -> ApiError {
+    ApiError::from(CommonError::from(PermissionError {
+        identifier: constants::SEC_ERR_PERMISSION_DENIED.to_string(),
+        message: message.to_string(),
+        context: constants::ERR_CONTEXT_AUTHORIZATION.to_string(),
+    }))
+}
+
+/// Reads the bearer token from the `Authorization` header. A missing header is
+/// not an error here, but a header that isn't a bearer token is.
+fn bearer_token(req: &HttpRequest) -> Result<Option<String>, CommonError> {
+    let Some(value) = req.headers().get(AUTHORIZATION) else {
+        return Ok(None);
+    };
+
+    value.to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| Some(token.trim().to_string()))
+        .ok_or_else(|| auth_error(constants::SEC_ERR_TOKEN_INVALID, "Authorization header must hold a bearer token"))
+}
+
+/// Authenticates requests carrying a bearer token and stores the resulting
+/// `AuthenticatedUser` in the request extensions. Requests without a token are
+/// passed through untouched; handlers that need a caller take an
+/// `AuthenticatedUser` argument, which rejects anonymous requests.
+///
+/// A token that fails to authenticate doesn't reject the request either, since
+/// public routes such as login or refresh must keep working for clients still
+/// holding an expired one. The failure is kept instead and reported by
+/// `AuthenticatedUser` and `RequirePermission` in place of a missing token.
+pub struct Authentication;
+
+impl<S, B> Transform<S, ServiceRequest> for Authentication
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = AuthenticationMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthenticationMiddleware { service: Rc::new(service) }))
+    }
+}
+
+pub struct AuthenticationMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for AuthenticationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let token = match bearer_token(req.request()) {
+                Ok(token) => token,
+                Err(err) => {
+                    req.extensions_mut().insert(AuthenticationFailure(err));
+                    None
+                }
+            };
+
+            if let Some(token) = token {
+                let auth_service = req.app_data::<web::Data<dyn AuthService>>()
+                    .cloned()
+                    .ok_or_else(|| actix_web::error::ErrorInternalServerError("AuthService is not configured"))?;
+                match auth_service.authenticate(&token).await {
+                    Ok(authenticated_user) => {
+                        req.extensions_mut().insert(authenticated_user);
+                    }
+                    Err(err) => {
+                        req.extensions_mut().insert(AuthenticationFailure(err));
+                    }
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
+
+/// Why the bearer token of a request didn't authenticate, kept by
+/// `Authentication` for the routes that need a caller.
+struct AuthenticationFailure(CommonError);
+
+/// The caller stored by `Authentication`, or the reason there is none.
+fn authenticated_user(req: &HttpRequest) -> Result<AuthenticatedUser, ApiError> {
+    let extensions = req.extensions();
+    if let Some(authenticated_user) = extensions.get::<AuthenticatedUser>() {
+        return Ok(authenticated_user.clone());
+    }
+
+    match extensions.get::<AuthenticationFailure>() {
+        Some(AuthenticationFailure(err)) => Err(ApiError::from(err.clone())),
+        None => Err(ApiError::from(auth_error(constants::SEC_ERR_TOKEN_MISSING, "A bearer token is required"))),
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(authenticated_user(req))
+    }
+}
+
+/// Only lets requests through when the authenticated caller holds the given
+/// `AdminPermissions` value. Must be nested inside `Authentication`, which
+/// provides the caller. `Role::SuperAdmin` bypasses the check, but not the ones
+/// for a verified email and for two-factor authentication when the policies
+/// require them. Permissions over other accounts always take two-factor
+/// authentication, see `AdminPermissions::requires_mfa`.
+pub struct RequirePermission {
+    permission: AdminPermissions,
+}
+
+impl RequirePermission {
+    pub fn new(permission: AdminPermissions) -> Self {
+        Self { permission }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequirePermissionMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequirePermissionMiddleware {
+            service: Rc::new(service),
+            permission: self.permission,
+        }))
+    }
+}
+
+pub struct RequirePermissionMiddleware<S> {
+    service: Rc<S>,
+    permission: AdminPermissions,
+}
+
+impl<S, B> Service<ServiceRequest> for RequirePermissionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let permission = self.permission;
+
+        Box::pin(async move {
+            let authenticated_user = authenticated_user(req.request())?;
+
+            let email_verification_service = req.app_data::<web::Data<dyn EmailVerificationService>>()
+                .cloned()
+                .ok_or_else(|| actix_web::error::ErrorInternalServerError("EmailVerificationService is not configured"))?;
+            email_verification_service.ensure_verified(&authenticated_user.user, VerifiedAction::Admin)
+                .map_err(ApiError::from)?;
+
+            let mfa_service = req.app_data::<web::Data<dyn MfaService>>()
+                .cloned()
+                .ok_or_else(|| actix_web::error::ErrorInternalServerError("MfaService is not configured"))?;
+            mfa_service.ensure_satisfied(&authenticated_user).map_err(ApiError::from)?;
+
+            if !matches!(authenticated_user.user.role, Role::SuperAdmin) {
+                let admin_permission_service = req.app_data::<web::Data<dyn AdminPermissionService>>()
+                    .cloned()
+                    .ok_or_else(|| actix_web::error::ErrorInternalServerError("AdminPermissionService is not configured"))?;
+                let is_allowed = admin_permission_service.has_permission(authenticated_user.user.id, permission)
+                    .await
+                    .map_err(ApiError::from)?;
+                if !is_allowed {
+                    return Err(permission_error("Missing the permission required for this resource").into());
+                }
+            }
+
+            if permission.requires_mfa() {
+                mfa_service.ensure_used(&authenticated_user).map_err(ApiError::from)?;
+            }
+
+            service.call(req).await
+        })
+    }
+}
@@ -0,0 +1,184 @@
+use actix_web::dev::Payload;
+use actix_web::error::{JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::{web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::error::{ApiError, CommonError, UnknownEnumValue};
+use crate::domain::models::common::{ErrorItem, NamedEnum};
+use crate::services::constants;
+
+/// Longest value accepted for fields stored in `VARCHAR(255)` columns.
+pub const MAX_VARCHAR_LEN: usize = 255;
+
+/// Implemented by request DTOs that have to be checked before they reach the
+/// services. Every problem is reported, not just the first one found.
+pub trait Validate {
+    fn validate(&self) -> Result<(), ApiError>;
+}
+
+/// Collects field problems while a DTO is being validated.
+#[derive(Default)]
+pub struct FieldErrors {
+    errors: Vec<ErrorItem>,
+}
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, field: &str, identifier: &str, message: String) {
+        self.errors.push(ErrorItem::new(field, identifier, message));
+    }
+
+    /// Checks that the value isn't blank. Returns whether it passed, so callers
+    /// can skip further checks that would only repeat the same problem.
+    pub fn required(&mut self, field: &str, value: &str) -> bool {
+        if value.trim().is_empty() {
+            self.add(field, constants::VAL_ERR_FIELD_REQUIRED, std::format!("`{}` must not be empty", field));
+            return false;
+        }
+        true
+    }
+
+    pub fn max_length(&mut self, field: &str, value: &str, max: usize) {
+        if value.chars().count() > max {
+            self.add(field, constants::VAL_ERR_FIELD_TOO_LONG, std::format!("`{}` must be at most {} characters long", field, max));
+        }
+    }
+
+    pub fn email(&mut self, field: &str, value: &str) {
+        if !is_valid_email(value) {
+            self.add(field, constants::VAL_ERR_INVALID_EMAIL, std::format!("`{}` must be a valid email address", field));
+        }
+    }
+
+    pub fn uuid(&mut self, field: &str, value: &str) -> Option<Uuid> {
+        let parsed = Uuid::parse_str(value).ok();
+        if parsed.is_none() {
+            self.add(field, constants::VAL_ERR_INVALID_UUID, std::format!("`{}` must be a valid UUID", field));
+        }
+        parsed
+    }
+
+    /// Decodes an enum sent as a number or name. Returns `None` and records the
+    /// problem if it doesn't match any variant.
+    pub fn enum_value<E: NamedEnum>(&mut self, field: &str, value: &EnumValue) -> Option<E> {
+        match value.parse::<E>() {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.add(field, constants::VAL_ERR_INVALID_ENUM_VALUE, std::format!(
+                    "`{}` must be one of: {}", field, err.accepted.join(", ")
+                ));
+                None
+            },
+        }
+    }
+
+    /// Runs the usual checks for a required text field stored in a `VARCHAR(255)` column.
+    pub fn required_text(&mut self, field: &str, value: &str) {
+        if self.required(field, value) {
+            self.max_length(field, value, MAX_VARCHAR_LEN);
+        }
+    }
+
+    /// Runs the usual checks for a required email field.
+    pub fn required_email(&mut self, field: &str, value: &str) {
+        if self.required(field, value) {
+            self.max_length(field, value, MAX_VARCHAR_LEN);
+            self.email(field, value);
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), ApiError> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        Err(ApiError::from(CommonError::validation(self.errors)))
+    }
+}
+
+/// An enum field as sent by clients, either as its number or as its stable name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EnumValue {
+    Number(i32),
+    Name(String),
+}
+
+impl EnumValue {
+    pub fn parse<E: NamedEnum>(&self) -> Result<E, UnknownEnumValue> {
+        match self {
+            EnumValue::Number(value) => E::from_i32(*value),
+            EnumValue::Name(name) => E::from_name(name),
+        }
+    }
+}
+
+impl<E: NamedEnum> From<E> for EnumValue {
+    fn from(value: E) -> Self {
+        EnumValue::Name(value.as_str().to_string())
+    }
+}
+
+/// Deliberately loose: only rejects values that can't possibly be delivered
+/// to. Whether the address exists can only be proven by sending mail to it.
+fn is_valid_email(value: &str) -> bool {
+    // Surrounding whitespace is harmless, the services trim it off.
+    let value = value.trim();
+    let Some((local, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && !value.chars().any(char::is_whitespace)
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+}
+
+/// JSON body extractor that runs the DTO's validation after deserializing it.
+pub struct ValidatedJson<T>(pub T);
+
+impl<T> ValidatedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> FromRequest for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let json = web::Json::<T>::from_request(req, payload);
+
+        Box::pin(async move {
+            let value = json.await?.into_inner();
+            value.validate()?;
+            Ok(ValidatedJson(value))
+        })
+    }
+}
+
+fn extraction_error(context: &str, identifier: &str, message: String) -> actix_web::Error {
+    ApiError::from(CommonError::validation(vec![ErrorItem::new(context, identifier, message)])).into()
+}
+
+pub fn json_error_handler(error: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    extraction_error("body", constants::VAL_ERR_INVALID_JSON, error.to_string())
+}
+
+pub fn path_error_handler(error: PathError, _req: &HttpRequest) -> actix_web::Error {
+    extraction_error("path", constants::VAL_ERR_INVALID_PATH, error.to_string())
+}
+
+pub fn query_error_handler(error: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    extraction_error("query", constants::VAL_ERR_INVALID_QUERY, error.to_string())
+}
@@ -1,47 +1,149 @@
 use std::sync::Arc;
 
 use crate::domain::repositories::admin_permission::AdminPermissionRepository;
+use crate::domain::repositories::mfa_recovery_code::MfaRecoveryCodeRepository;
+use crate::domain::repositories::session::SessionRepository;
+use crate::domain::repositories::token_blacklist::TokenBlacklistRepository;
 use crate::domain::repositories::user::UserRepository;
 use crate::domain::services::admin_permission::AdminPermissionService;
+use crate::domain::services::auth::AuthService;
+use crate::domain::services::email_verification::EmailVerificationService;
+use crate::domain::services::mfa::MfaService;
+use crate::domain::services::session::SessionService;
 use crate::domain::services::user::UserService;
 use crate::infrastructure::database::postgresql::db_pool;
 use crate::infrastructure::repositories::admin_permission::AdminPermissionRepositoryImpl;
+use crate::infrastructure::repositories::mfa_recovery_code::MfaRecoveryCodeRepositoryImpl;
+use crate::infrastructure::repositories::session::SessionRepositoryImpl;
+use crate::infrastructure::repositories::token_blacklist::TokenBlacklistRepositoryImpl;
 use crate::infrastructure::repositories::user::UserRepositoryImpl;
 use crate::infrastructure::services::admin_permission::AdminPermissionServiceImpl;
+use crate::infrastructure::services::auth::AuthServiceImpl;
+use crate::infrastructure::services::email_verification::{EmailVerificationPolicy, EmailVerificationServiceImpl};
+use crate::infrastructure::services::mfa::{MfaPolicy, MfaServiceImpl};
+use crate::infrastructure::services::session::SessionServiceImpl;
 use crate::infrastructure::services::user::UserServiceImpl;
 use crate::services::concrete::argon2id_hash::Argon2IdHashService;
+use crate::services::concrete::composite_hash::CompositeHashService;
+use crate::services::concrete::file_mailer::FileMailerService;
+use crate::services::concrete::hashing_pool::HashingPool;
+use crate::services::concrete::jwt_token::JwtTokenService;
+use crate::services::concrete::log_mailer::LogMailerService;
+use crate::services::concrete::password_policy::ConfigurablePasswordPolicy;
+use crate::services::concrete::secret_cipher::SecretCipher;
+use crate::services::traits::mailer::MailerService;
+use crate::services::traits::password_hash::PasswordHashService;
+use crate::services::traits::password_policy::PasswordPolicyService;
+use crate::services::constants;
+use crate::services::traits::token::TokenService;
+use crate::services::utils::envutil::get_env_var_as_str;
 
+/// Picks the mail transport named by `MAIL_TRANSPORT`, logging by default.
+fn mailer_from_env() -> Arc<dyn MailerService> {
+    let transport = get_env_var_as_str(constants::MAIL_ENV_TRANSPORT)
+        .unwrap_or_else(|_| constants::MAIL_TRANSPORT_LOG.to_string());
+    match transport.as_str() {
+        constants::MAIL_TRANSPORT_FILE => {
+            let directory = get_env_var_as_str(constants::MAIL_ENV_FILE_DIR).unwrap_or_else(|_| {
+                panic!("{} must be set when {} is `file`", constants::MAIL_ENV_FILE_DIR, constants::MAIL_ENV_TRANSPORT);
+            });
+            Arc::new(FileMailerService::new(directory))
+        },
+        constants::MAIL_TRANSPORT_LOG => Arc::new(LogMailerService::new()),
+        other => panic!("Unknown {} `{}`", constants::MAIL_ENV_TRANSPORT, other),
+    }
+}
+
+/// Every service the app needs, wired up once at startup. Cloning only clones
+/// the `Arc`s, so all workers share the same services and connection pool.
+#[derive(Clone)]
 pub struct Container {
     pub admin_permission_service: Arc<dyn AdminPermissionService>,
+    pub auth_service: Arc<dyn AuthService>,
+    pub email_verification_service: Arc<dyn EmailVerificationService>,
+    pub mfa_service: Arc<dyn MfaService>,
+    pub session_service: Arc<dyn SessionService>,
     pub user_service: Arc<dyn UserService>, 
+    pub hashing_pool: Arc<HashingPool>,
 }
 
 impl Container {
-    pub fn new() -> Self {
+    pub async fn new() -> Self {
+        let pool = Arc::new(db_pool().await);
         let admin_permission_repository: Arc<dyn AdminPermissionRepository> = Arc::new(
-            AdminPermissionRepositoryImpl::new(Arc::new(db_pool()))
+            AdminPermissionRepositoryImpl::new(pool.clone())
+        );
+        let mfa_recovery_code_repository: Arc<dyn MfaRecoveryCodeRepository> = Arc::new(
+            MfaRecoveryCodeRepositoryImpl::new(pool.clone())
+        );
+        let session_repository: Arc<dyn SessionRepository> = Arc::new(
+            SessionRepositoryImpl::new(pool.clone())
+        );
+        let token_blacklist_repository: Arc<dyn TokenBlacklistRepository> = Arc::new(
+            TokenBlacklistRepositoryImpl::new(pool.clone())
         );
         let user_repository: Arc<dyn UserRepository> = Arc::new(
-            UserRepositoryImpl::new(Arc::new(db_pool()))
+            UserRepositoryImpl::new(pool)
         );
+        let hashing_pool = Arc::new(HashingPool::from_env());
+        let password_hash_service: Arc<dyn PasswordHashService> = Arc::new(
+            CompositeHashService::new(Arc::new(Argon2IdHashService::new(hashing_pool.clone())), hashing_pool.clone())
+        );
+        let password_policy: Arc<dyn PasswordPolicyService> = Arc::new(ConfigurablePasswordPolicy::from_env());
+        let token_service: Arc<dyn TokenService> = Arc::new(JwtTokenService::new());
+        let mailer_service = mailer_from_env();
         let admin_permission_service: Arc<dyn AdminPermissionService> = Arc::new(
             AdminPermissionServiceImpl::new(admin_permission_repository)
         );
+        let session_service: Arc<dyn SessionService> = Arc::new(
+            SessionServiceImpl::new(session_repository.clone())
+        );
+        let email_verification_service: Arc<dyn EmailVerificationService> = Arc::new(
+            EmailVerificationServiceImpl::new(
+                user_repository.clone(),
+                token_service.clone(),
+                mailer_service.clone(),
+                EmailVerificationPolicy::from_env()
+            )
+        );
+        let mfa_service: Arc<dyn MfaService> = Arc::new(
+            MfaServiceImpl::new(
+                user_repository.clone(),
+                mfa_recovery_code_repository,
+                SecretCipher::from_env(),
+                MfaPolicy::from_env()
+            )
+        );
         let user_service: Arc<dyn UserService> = Arc::new(
             UserServiceImpl::new(
+                user_repository.clone(),
+                password_hash_service.clone(),
+                password_policy.clone(),
+                email_verification_service.clone()
+            )
+        );
+        let auth_service: Arc<dyn AuthService> = Arc::new(
+            AuthServiceImpl::new(
                 user_repository,
-                Arc::new(Argon2IdHashService::new())
+                session_repository,
+                token_blacklist_repository,
+                user_service.clone(),
+                email_verification_service.clone(),
+                mfa_service.clone(),
+                password_hash_service,
+                password_policy,
+                token_service,
+                mailer_service
             )
         );
         Container {
             admin_permission_service,
+            auth_service,
+            email_verification_service,
+            mfa_service,
+            session_service,
             user_service,
+            hashing_pool,
         }
     }
 }
-
-impl Default for Container {
-    fn default() -> Self {
-        Self::new()
-    }
-}
\ No newline at end of file
@@ -11,16 +11,41 @@ use crate::api::controllers::admin_permission_handler::{
     update_admin_permission_handler,
     delete_admin_permission_handler 
 };
+use crate::api::controllers::auth_handler::{
+    confirm_mfa_handler,
+    disable_mfa_handler,
+    enroll_mfa_handler,
+    forgot_password_handler,
+    list_own_session_handler,
+    login_handler,
+    logout_all_handler,
+    logout_handler,
+    me_handler,
+    refresh_handler,
+    regenerate_recovery_codes_handler,
+    register_handler,
+    resend_verification_handler,
+    reset_password_handler,
+    verify_email_handler,
+    verify_mfa_handler
+};
+use crate::api::controllers::error_handler::list_error_code_handler;
+use crate::api::controllers::metrics_handler::get_hashing_metrics_handler;
+use crate::api::controllers::session_handler::list_user_session_handler;
 use crate::api::controllers::user_handler::{
     create_user_handler,
+    import_user_handler,
     list_user_handler,
     get_user_handler,
     update_user_handler,
     delete_user_handler
 };
+use crate::api::middleware::{Authentication, RequirePermission};
+use crate::api::validation::{json_error_handler, path_error_handler, query_error_handler};
 use crate::container::Container;
+use crate::domain::models::admin_permission::AdminPermissions;
 
-pub fn create_app() -> App<
+pub fn create_app(container: &Container) -> App<
     impl ServiceFactory<
         ServiceRequest,
         Response = ServiceResponse<impl MessageBody>,
@@ -29,29 +54,69 @@ pub fn create_app() -> App<
         Error = Error,
     >,
 > {
-    let container = Container::new();
     let admin_permission_service = container.admin_permission_service.clone();
+    let auth_service = container.auth_service.clone();
+    let email_verification_service = container.email_verification_service.clone();
+    let mfa_service = container.mfa_service.clone();
+    let session_service = container.session_service.clone();
     let user_service = container.user_service.clone();
     
     App::new()
         .app_data(web::Data::from(admin_permission_service.clone()))
+        .app_data(web::Data::from(auth_service.clone()))
+        .app_data(web::Data::from(email_verification_service.clone()))
+        .app_data(web::Data::from(mfa_service.clone()))
+        .app_data(web::Data::from(session_service.clone()))
         .app_data(web::Data::from(user_service.clone()))
+        .app_data(web::Data::from(container.hashing_pool.clone()))
+        .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+        .app_data(web::PathConfig::default().error_handler(path_error_handler))
+        .app_data(web::QueryConfig::default().error_handler(query_error_handler))
         .wrap(TracingLogger::default())
         .service(
-            web::scope("/api").service(
+            web::scope("/api").wrap(Authentication).service(
                 web::scope("/admin_permissions")
+                    .wrap(RequirePermission::new(AdminPermissions::CanManageRoles))
                     .route("", web::post().to(create_admin_permission_handler))
                     .route("", web::get().to(list_admin_permission_handler))
                     .route("/{admin_permission_id}", web::get().to(get_admin_permission_handler))
                     .route("/{admin_permission_id}", web::put().to(update_admin_permission_handler))
                     .route("/{admin_permission_id}", web::delete().to(delete_admin_permission_handler))
+            ).service(
+                web::scope("/auth")
+                    .route("/login", web::post().to(login_handler))
+                    .route("/register", web::post().to(register_handler))
+                    .route("/refresh", web::post().to(refresh_handler))
+                    .route("/logout", web::post().to(logout_handler))
+                    .route("/logout-all", web::post().to(logout_all_handler))
+                    .route("/forgot-password", web::post().to(forgot_password_handler))
+                    .route("/reset-password", web::post().to(reset_password_handler))
+                    .route("/verify-email", web::post().to(verify_email_handler))
+                    .route("/resend-verification", web::post().to(resend_verification_handler))
+                    .route("/mfa/verify", web::post().to(verify_mfa_handler))
+                    .route("/mfa/enroll", web::post().to(enroll_mfa_handler))
+                    .route("/mfa/confirm", web::post().to(confirm_mfa_handler))
+                    .route("/mfa/disable", web::post().to(disable_mfa_handler))
+                    .route("/mfa/recovery-codes", web::post().to(regenerate_recovery_codes_handler))
+                    .route("/me", web::get().to(me_handler))
+                    .route("/sessions", web::get().to(list_own_session_handler))
+            ).service(
+                web::scope("/errors")
+                    .route("", web::get().to(list_error_code_handler))
+            ).service(
+                web::scope("/metrics")
+                    .wrap(RequirePermission::new(AdminPermissions::CanViewReports))
+                    .route("/hashing", web::get().to(get_hashing_metrics_handler))
             ).service(
                 web::scope("/users")
+                    .wrap(RequirePermission::new(AdminPermissions::CanManageUsers))
                     .route("", web::post().to(create_user_handler))
                     .route("", web::get().to(list_user_handler))
+                    .route("/import", web::post().to(import_user_handler))
                     .route("/{user_id}", web::get().to(get_user_handler))
                     .route("/{user_id}", web::put().to(update_user_handler))
                     .route("/{user_id}", web::delete().to(delete_user_handler))
+                    .route("/{user_id}/sessions", web::get().to(list_user_session_handler))
             )
         )
 }
\ No newline at end of file
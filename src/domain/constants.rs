@@ -1 +1,20 @@
 pub const POSTGRESQL_DB_URI: &str = "DATABASE_URL";
+pub const POSTGRESQL_ENV_POOL_MAX_SIZE: &str = "DATABASE_POOL_MAX_SIZE";
+pub const POSTGRESQL_ENV_POOL_MIN_IDLE: &str = "DATABASE_POOL_MIN_IDLE";
+pub const POSTGRESQL_ENV_POOL_CONNECTION_TIMEOUT_SECS: &str = "DATABASE_POOL_CONNECTION_TIMEOUT_SECS";
+pub const POSTGRESQL_ENV_POOL_MAX_LIFETIME_SECS: &str = "DATABASE_POOL_MAX_LIFETIME_SECS";
+
+pub const POSTGRESQL_POOL_MAX_SIZE_DEFAULT: u32 = 10;
+pub const POSTGRESQL_POOL_CONNECTION_TIMEOUT_SECS_DEFAULT: u64 = 30;
+pub const POSTGRESQL_POOL_MAX_LIFETIME_SECS_DEFAULT: u64 = 1800;
+
+pub const REPO_ERR_NOT_FOUND: &str = "record_not_found";
+pub const REPO_ERR_UNIQUE_VIOLATION: &str = "unique_violation";
+pub const REPO_ERR_FOREIGN_KEY_VIOLATION: &str = "foreign_key_violation";
+pub const REPO_ERR_CONNECTION: &str = "database_unavailable";
+pub const REPO_ERR_OTHER: &str = "database_error";
+
+pub const DB_CONSTRAINT_USERS_EMAIL: &str = "users_email_lower_key";
+pub const DB_CONSTRAINT_ADMIN_PERMISSIONS_USER_ID: &str = "admin_permissions_user_id_fkey";
+
+pub const RETRY_AFTER_SECS: u64 = 1;
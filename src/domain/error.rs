@@ -1,16 +1,54 @@
 use serde::Serialize;
 
-use crate::error_codes::ERR_CODE_REPOSITORY;
+use crate::domain::constants;
+use crate::services::constants as services_constants;
+use crate::domain::models::common::{ErrorItem, ErrorResponse, ErrorResponseType, NamedEnum};
+use crate::error_codes::find_error_code;
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 pub struct CommonError {
+    pub identifier: String,
     pub message: String,
+    pub context: String,
     pub code: u32,
+    pub error_type: ErrorResponseType,
+    pub errors: Vec<ErrorItem>,
+}
+
+impl CommonError {
+    /// Builds an error for a catalog identifier, taking its numeric code and
+    /// response type from the catalog. `fallback_type` is only used for
+    /// identifiers missing from the catalog, which get code 0.
+    pub fn new(identifier: &str, message: String, context: String, fallback_type: ErrorResponseType) -> Self {
+        let entry = find_error_code(identifier);
+        CommonError {
+            identifier: identifier.to_string(),
+            message,
+            context,
+            code: entry.map_or(0, |entry| entry.code),
+            error_type: entry.map_or(fallback_type, |entry| entry.error_type),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Groups several problems, usually one per invalid request field, into a
+    /// single validation error.
+    pub fn validation(errors: Vec<ErrorItem>) -> Self {
+        CommonError {
+            errors,
+            ..CommonError::new(
+                services_constants::VAL_ERR_VALIDATION_FAILED,
+                "The request contains invalid fields".to_string(),
+                "validation".to_string(),
+                ErrorResponseType::Validation
+            )
+        }
+    }
 }
 
 impl std::fmt::Display for CommonError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Error: {}, Code: {}", self.message, self.code)
+        write!(f, "Error: {} - {}, Context: {}, Code: {}", self.identifier, self.message, self.context, self.code)
     }
 }
 
@@ -33,15 +71,110 @@ impl std::fmt::Display for ApiError {
 
 impl std::error::Error for ApiError { }
 
+pub fn status_code(error_type: ErrorResponseType) -> actix_web::http::StatusCode {
+    use actix_web::http::StatusCode;
+
+    match error_type {
+        ErrorResponseType::General => StatusCode::BAD_REQUEST,
+        ErrorResponseType::Validation => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorResponseType::NotFound => StatusCode::NOT_FOUND,
+        ErrorResponseType::Conflict => StatusCode::CONFLICT,
+        ErrorResponseType::Forbidden => StatusCode::FORBIDDEN,
+        ErrorResponseType::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorResponseType::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorResponseType::Unauthorized => StatusCode::UNAUTHORIZED,
+        ErrorResponseType::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
 impl actix_web::ResponseError for ApiError {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        status_code(self.0.error_type)
+    }
+
     fn error_response(&self) -> actix_web::HttpResponse {
-        actix_web::HttpResponse::BadRequest().json(&self.0)
+        let catalog_message = find_error_code(&self.0.identifier)
+            .map_or_else(|| self.0.message.clone(), |entry| entry.message.to_string());
+
+        // Internal failures are logged by the tracing middleware with their
+        // details, but clients only get the catalog message.
+        let message = match self.0.error_type {
+            ErrorResponseType::InternalServerError => catalog_message.clone(),
+            _ => self.0.message.clone(),
+        };
+
+        let errors = if self.0.errors.is_empty() {
+            vec![ErrorItem {
+                context: self.0.context.clone(),
+                message,
+                identifier: Some(self.0.identifier.clone()),
+                error_code: Some(self.0.code),
+            }]
+        } else {
+            self.0.errors.clone()
+        };
+
+        let mut response = actix_web::HttpResponse::build(self.status_code());
+        // Load shedding is temporary, so tell clients they may try again.
+        if self.0.error_type == ErrorResponseType::ServiceUnavailable {
+            response.insert_header((actix_web::http::header::RETRY_AFTER, constants::RETRY_AFTER_SECS));
+        }
+
+        response.json(ErrorResponse {
+            message: catalog_message,
+            error_type: self.0.error_type,
+            errors,
+        })
+    }
+}
+
+/// What went wrong in the storage layer, independent of the database driver.
+/// Constraint names are kept so callers can tell which rule was broken.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RepositoryErrorKind {
+    NotFound,
+    UniqueViolation { constraint: Option<String> },
+    ForeignKeyViolation { constraint: Option<String> },
+    Connection,
+    Other,
+}
+
+impl RepositoryErrorKind {
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            RepositoryErrorKind::NotFound => constants::REPO_ERR_NOT_FOUND,
+            RepositoryErrorKind::UniqueViolation { .. } => constants::REPO_ERR_UNIQUE_VIOLATION,
+            RepositoryErrorKind::ForeignKeyViolation { .. } => constants::REPO_ERR_FOREIGN_KEY_VIOLATION,
+            RepositoryErrorKind::Connection => constants::REPO_ERR_CONNECTION,
+            RepositoryErrorKind::Other => constants::REPO_ERR_OTHER,
+        }
+    }
+
+    pub fn error_type(&self) -> ErrorResponseType {
+        match self {
+            RepositoryErrorKind::NotFound => ErrorResponseType::NotFound,
+            RepositoryErrorKind::UniqueViolation { .. } => ErrorResponseType::Conflict,
+            RepositoryErrorKind::ForeignKeyViolation { .. } => ErrorResponseType::Conflict,
+            RepositoryErrorKind::Connection => ErrorResponseType::InternalServerError,
+            RepositoryErrorKind::Other => ErrorResponseType::InternalServerError,
+        }
+    }
+
+    /// Whether this is a violation of the named unique constraint.
+    pub fn is_unique_violation_of(&self, name: &str) -> bool {
+        matches!(self, RepositoryErrorKind::UniqueViolation { constraint: Some(constraint) } if constraint == name)
+    }
+
+    /// Whether this is a violation of the named foreign key constraint.
+    pub fn is_foreign_key_violation_of(&self, name: &str) -> bool {
+        matches!(self, RepositoryErrorKind::ForeignKeyViolation { constraint: Some(constraint) } if constraint == name)
     }
 }
 
 #[derive(Debug)]
 pub struct RepositoryError {
     pub message: String,
+    pub kind: RepositoryErrorKind,
 }
 
 impl std::fmt::Display for RepositoryError {
@@ -54,9 +187,38 @@ impl std::error::Error for RepositoryError { }
 
 impl From<RepositoryError> for CommonError {
     fn from(val: RepositoryError) -> Self {
-        CommonError {
-            message: std::format!("{}", val),
-            code: ERR_CODE_REPOSITORY,
+        CommonError::new(val.kind.identifier(), val.message, "repository".to_string(), val.kind.error_type())
+    }
+}
+
+/// A number or name that doesn't match any variant of a `NamedEnum`.
+#[derive(Debug)]
+pub struct UnknownEnumValue {
+    pub enum_name: &'static str,
+    pub value: String,
+    pub accepted: Vec<&'static str>,
+}
+
+impl UnknownEnumValue {
+    pub fn of<E: NamedEnum>(value: impl std::fmt::Display) -> Self {
+        UnknownEnumValue {
+            enum_name: E::NAME,
+            value: value.to_string(),
+            accepted: E::VARIANTS.iter().map(NamedEnum::as_str).collect(),
         }
     }
 }
+
+impl std::fmt::Display for UnknownEnumValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown {} `{}`, expected one of: {}", self.enum_name, self.value, self.accepted.join(", "))
+    }
+}
+
+impl std::error::Error for UnknownEnumValue { }
+
+impl From<UnknownEnumValue> for CommonError {
+    fn from(val: UnknownEnumValue) -> Self {
+        CommonError::validation(vec![ErrorItem::new(val.enum_name, services_constants::VAL_ERR_INVALID_ENUM_VALUE, val.to_string())])
+    }
+}
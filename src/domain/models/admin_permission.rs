@@ -1,8 +1,13 @@
-use serde::Deserialize;
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use chrono::NaiveDateTime;
 
-#[derive(Clone, Debug, Deserialize)]
+use crate::domain::error::UnknownEnumValue;
+use crate::domain::models::common::{deserialize_named_enum, serialize_named_enum, NamedEnum};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AdminPermissions {
     CanSignIn = 0,
     CanRecoverAccount = 1,
@@ -20,26 +25,84 @@ pub enum AdminPermissions {
     CanProvideSupport = 13,
 }
 
-impl From<i32> for AdminPermissions {
-    fn from(permission: i32) -> Self {
-        match permission {
-            0 => AdminPermissions::CanSignIn,
-            1 => AdminPermissions::CanRecoverAccount,
-            2 => AdminPermissions::CanManageUsers,
-            3 => AdminPermissions::CanManageRoles,
-            4 => AdminPermissions::CanManageLanguages,
-            5 => AdminPermissions::CanManageCharacterSet,
-            6 => AdminPermissions::CanManagePhonetics,
-            7 => AdminPermissions::CanManageLessonTemplates,
-            8 => AdminPermissions::CanManageLessons,
-            9 => AdminPermissions::CanManageVocabulary,
-            10 => AdminPermissions::CanManageQuizzes,
-            11 => AdminPermissions::CanViewFeedback,
-            12 => AdminPermissions::CanViewReports,
-            13 => AdminPermissions::CanProvideSupport,
-            _ => AdminPermissions::CanSignIn,
+impl AdminPermissions {
+    /// Whether the permission lets its holder take over or delete other
+    /// accounts, in which case using it takes two-factor authentication
+    /// whatever the holder's role.
+    pub fn requires_mfa(self) -> bool {
+        matches!(self, AdminPermissions::CanManageUsers | AdminPermissions::CanManageRoles)
+    }
+}
+
+impl NamedEnum for AdminPermissions {
+    const NAME: &'static str = "permission";
+    const VARIANTS: &'static [Self] = &[
+        AdminPermissions::CanSignIn,
+        AdminPermissions::CanRecoverAccount,
+        AdminPermissions::CanManageUsers,
+        AdminPermissions::CanManageRoles,
+        AdminPermissions::CanManageLanguages,
+        AdminPermissions::CanManageCharacterSet,
+        AdminPermissions::CanManagePhonetics,
+        AdminPermissions::CanManageLessonTemplates,
+        AdminPermissions::CanManageLessons,
+        AdminPermissions::CanManageVocabulary,
+        AdminPermissions::CanManageQuizzes,
+        AdminPermissions::CanViewFeedback,
+        AdminPermissions::CanViewReports,
+        AdminPermissions::CanProvideSupport,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AdminPermissions::CanSignIn => "can_sign_in",
+            AdminPermissions::CanRecoverAccount => "can_recover_account",
+            AdminPermissions::CanManageUsers => "can_manage_users",
+            AdminPermissions::CanManageRoles => "can_manage_roles",
+            AdminPermissions::CanManageLanguages => "can_manage_languages",
+            AdminPermissions::CanManageCharacterSet => "can_manage_character_set",
+            AdminPermissions::CanManagePhonetics => "can_manage_phonetics",
+            AdminPermissions::CanManageLessonTemplates => "can_manage_lesson_templates",
+            AdminPermissions::CanManageLessons => "can_manage_lessons",
+            AdminPermissions::CanManageVocabulary => "can_manage_vocabulary",
+            AdminPermissions::CanManageQuizzes => "can_manage_quizzes",
+            AdminPermissions::CanViewFeedback => "can_view_feedback",
+            AdminPermissions::CanViewReports => "can_view_reports",
+            AdminPermissions::CanProvideSupport => "can_provide_support",
         }
     }
+
+    fn as_i32(&self) -> i32 {
+        *self as i32
+    }
+}
+
+impl TryFrom<i32> for AdminPermissions {
+    type Error = UnknownEnumValue;
+
+    fn try_from(permission: i32) -> Result<Self, Self::Error> {
+        AdminPermissions::from_i32(permission)
+    }
+}
+
+impl FromStr for AdminPermissions {
+    type Err = UnknownEnumValue;
+
+    fn from_str(permission: &str) -> Result<Self, Self::Err> {
+        AdminPermissions::from_name(permission)
+    }
+}
+
+impl Serialize for AdminPermissions {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_named_enum(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AdminPermissions {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_named_enum(deserializer)
+    }
 }
 
 #[derive(Clone, Deserialize)]
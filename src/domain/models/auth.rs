@@ -1,5 +1,35 @@
+use chrono::NaiveDateTime;
 use serde::{Serialize, Deserialize};
 
+use crate::domain::models::common::{NamedEnum, TokenType};
+use crate::domain::models::user::User;
+
+/// What an account can be kept from doing until its email is verified, see
+/// `EMAIL_VERIFICATION_REQUIRED_FOR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifiedAction {
+    /// Signing in, including registering and refreshing or using tokens.
+    Login = 0,
+    /// Using the endpoints that need an admin permission.
+    Admin = 1,
+}
+
+impl NamedEnum for VerifiedAction {
+    const NAME: &'static str = "verified_action";
+    const VARIANTS: &'static [Self] = &[VerifiedAction::Login, VerifiedAction::Admin];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            VerifiedAction::Login => "login",
+            VerifiedAction::Admin => "admin",
+        }
+    }
+
+    fn as_i32(&self) -> i32 {
+        *self as i32
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AuthLogin {
     pub email: String,
@@ -14,8 +44,100 @@ pub struct AuthRegister {
     pub confirm_password: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthRefresh {
+    pub refresh_token: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthForgotPassword {
+    pub email: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthResetPassword {
+    pub token: String,
+    pub password: String,
+    pub confirm_password: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthVerifyEmail {
+    pub token: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthResendVerification {
+    pub email: String,
+}
+
+/// A TOTP code or, where one is accepted, a recovery code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthMfaCode {
+    pub code: String,
+}
+
+/// The second step of signing in with two-factor authentication.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthMfaVerify {
+    pub challenge_token: String,
+    pub code: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AuthSuccessfulResponse {
     pub token: String,
     pub refresh_token: String,
+}
+
+/// Handed out instead of tokens when the password was right but the account
+/// also needs a second factor. `challenge_token` is exchanged for tokens
+/// together with a code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuthMfaChallenge {
+    pub challenge_token: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug)]
+pub enum AuthLoginOutcome {
+    Authenticated(AuthSuccessfulResponse),
+    MfaRequired(AuthMfaChallenge),
+}
+
+/// Claims carried by every token issued by the token service. `sub` holds the
+/// user id and `jti` uniquely identifies the pair of tokens issued together.
+/// These claims never travel in plain text: they are encrypted and wrapped in
+/// an `EncryptedTokenPayload` before the JWT is signed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub sub: String,
+    pub jti: String,
+    pub token_type: TokenType,
+    pub iat: i64,
+    pub exp: i64,
+    /// The address an email verification token was sent to. A token only
+    /// verifies the address it was sent to, not whatever the user has since.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Whether the user passed a second factor when signing in. Refreshing
+    /// carries it over to the new tokens.
+    #[serde(default)]
+    pub mfa: bool,
+}
+
+/// A freshly signed token together with the claims it was issued with, so
+/// callers don't need to decode a token they have just created.
+#[derive(Clone, Debug)]
+pub struct IssuedToken {
+    pub token: String,
+    pub claims: TokenClaims,
+}
+
+/// The caller behind a validated access token, made available to handlers by
+/// the authentication middleware.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedUser {
+    pub user: User,
+    pub claims: TokenClaims,
 }
\ No newline at end of file
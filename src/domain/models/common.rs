@@ -1,4 +1,10 @@
-use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::{de, Deserializer, Serialize, Serializer, Deserialize};
+
+use crate::domain::error::UnknownEnumValue;
+use crate::error_codes::find_error_code;
 
 /// Simple and effective response body for returning a message. Sometimes all
 /// you need is a friendly message to convey info back to the client.
@@ -38,6 +44,28 @@ pub struct PaginationParams {
     pub has_next: bool,
 }
 
+impl PaginationParams {
+    /// Derives the page metadata from the total row count and the window that
+    /// was queried. Pages are numbered from 1. The window comes from the query
+    /// string, so the arithmetic saturates instead of overflowing.
+    pub fn new(total: i64, limit: i64, offset: i64) -> Self {
+        let (total_pages, current_page) = if limit > 0 {
+            (total.max(0).saturating_add(limit - 1) / limit, offset.max(0) / limit + 1)
+        } else {
+            (0, 1)
+        };
+
+        PaginationParams {
+            total,
+            limit,
+            offset,
+            total_pages,
+            current_page,
+            has_next: limit > 0 && offset.saturating_add(limit) < total,
+        }
+    }
+}
+
 /// Response body for returning a message along with a list of objects and
 /// pagination parameters. Perfect for endpoints returning a list of objects
 /// along with metadata like pagination details. It provides a comprehensive
@@ -55,11 +83,89 @@ pub struct MessageWithPaginationResponse<T> {
 pub struct ErrorItem {
     pub context: String,
     pub message: String,
+    pub identifier: Option<String>,
     pub error_code: Option<u32>,
 }
 
+impl ErrorItem {
+    /// Builds an item for a catalog identifier. `context` names what the error
+    /// is about, e.g. the request field that failed validation.
+    pub fn new(context: &str, identifier: &str, message: String) -> Self {
+        ErrorItem {
+            context: context.to_string(),
+            message,
+            identifier: Some(identifier.to_string()),
+            error_code: find_error_code(identifier).map(|entry| entry.code),
+        }
+    }
+}
+
+/// Implemented by enums that are stored as numbers but also have a stable
+/// snake_case name. Clients get the name and may send either form. Unknown
+/// values are rejected instead of falling back to some default variant.
+pub trait NamedEnum: Sized + Copy + 'static {
+    /// Name of the enum itself, used in error messages.
+    const NAME: &'static str;
+    const VARIANTS: &'static [Self];
+
+    fn as_str(&self) -> &'static str;
+    fn as_i32(&self) -> i32;
+
+    fn from_i32(value: i32) -> Result<Self, UnknownEnumValue> {
+        Self::VARIANTS.iter()
+            .copied()
+            .find(|variant| variant.as_i32() == value)
+            .ok_or_else(|| UnknownEnumValue::of::<Self>(value))
+    }
+
+    fn from_name(name: &str) -> Result<Self, UnknownEnumValue> {
+        Self::VARIANTS.iter()
+            .copied()
+            .find(|variant| variant.as_str() == name)
+            .ok_or_else(|| UnknownEnumValue::of::<Self>(name))
+    }
+}
+
+/// Serializes a `NamedEnum` as its stable name.
+pub fn serialize_named_enum<S: Serializer, E: NamedEnum>(value: &E, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(value.as_str())
+}
+
+/// Deserializes a `NamedEnum` from either its number or its stable name.
+pub fn deserialize_named_enum<'de, D: Deserializer<'de>, E: NamedEnum>(deserializer: D) -> Result<E, D::Error> {
+    struct NamedEnumVisitor<E>(PhantomData<E>);
+
+    impl<E: NamedEnum> de::Visitor<'_> for NamedEnumVisitor<E> {
+        type Value = E;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a {} number or name", E::NAME)
+        }
+
+        fn visit_i64<Err: de::Error>(self, value: i64) -> Result<E, Err> {
+            i32::try_from(value)
+                .map_err(|_| UnknownEnumValue::of::<E>(value))
+                .and_then(E::from_i32)
+                .map_err(Err::custom)
+        }
+
+        fn visit_u64<Err: de::Error>(self, value: u64) -> Result<E, Err> {
+            i32::try_from(value)
+                .map_err(|_| UnknownEnumValue::of::<E>(value))
+                .and_then(E::from_i32)
+                .map_err(Err::custom)
+        }
+
+        fn visit_str<Err: de::Error>(self, value: &str) -> Result<E, Err> {
+            E::from_name(value).map_err(Err::custom)
+        }
+    }
+
+    deserializer.deserialize_any(NamedEnumVisitor(PhantomData))
+}
+
 /// Enum representing different types of error responses.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ErrorResponseType {
     General = 0,
     Validation = 1,
@@ -68,6 +174,8 @@ pub enum ErrorResponseType {
     Forbidden = 4,
     UnprocessableEntity = 5,
     InternalServerError = 6,
+    Unauthorized = 7,
+    ServiceUnavailable = 8,
 }
 
 /// Implementation of conversion from integer to ErrorResponseType enum
@@ -81,6 +189,8 @@ impl From<i32> for ErrorResponseType {
             4 => ErrorResponseType::Forbidden,
             5 => ErrorResponseType::UnprocessableEntity,
             6 => ErrorResponseType::InternalServerError,
+            7 => ErrorResponseType::Unauthorized,
+            8 => ErrorResponseType::ServiceUnavailable,
             _ => ErrorResponseType::General,
         }
     }
@@ -99,10 +209,12 @@ pub struct ErrorResponse {
 
 
 /// Enum representing different types of tokens.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TokenType {
     AccessToken = 0,
     RefreshToken = 1,
+    EmailVerificationToken = 2,
+    MfaChallengeToken = 3,
 }
 
 /// Implementation of conversion from integer to TokenType enum
@@ -111,6 +223,8 @@ impl From<i32> for TokenType {
         match token_type {
             0 => TokenType::AccessToken,
             1 => TokenType::RefreshToken,
+            2 => TokenType::EmailVerificationToken,
+            3 => TokenType::MfaChallengeToken,
             _ => TokenType::AccessToken,
         }
     }
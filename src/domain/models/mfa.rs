@@ -0,0 +1,17 @@
+use serde::{Serialize, Deserialize};
+
+/// What an authenticator app needs to start producing codes. Only shown while
+/// enrolling; the secret can't be read back afterwards.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MfaEnrollment {
+    /// The base32 encoded secret, for apps that can't scan `otpauth_uri`.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+/// Freshly generated recovery codes. Only digests are kept, so this is the
+/// one time they are shown.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MfaRecoveryCodes {
+    pub codes: Vec<String>,
+}
@@ -1,5 +1,6 @@
 pub mod admin_permission;
 pub mod auth;
 pub mod common;
+pub mod mfa;
 pub mod session;
 pub mod user;
@@ -2,18 +2,45 @@ use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use chrono::NaiveDateTime;
 
+/// A refresh token handed out at login. `token` holds a digest of the refresh
+/// token rather than the token itself, so a leaked table can't be replayed.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Session {
     pub id: Uuid,
     pub user_id: Uuid,
     pub token: String,
     pub expiry: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: Option<NaiveDateTime>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub expiry: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// Details about the client a session was opened from, shown back to the user
+/// when listing where they are logged in.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SessionClient {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// Revokes every token carrying the given `jti`. Access and refresh tokens
+/// issued together share their `jti`, so one entry revokes the whole pair.
+/// The entry only has to outlive the tokens it revokes, after which `expiry`
+/// allows it to be pruned.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CreateTokenBlacklist {
     pub user_id: Uuid,
     pub token: String,
+    pub expiry: NaiveDateTime,
 }
\ No newline at end of file
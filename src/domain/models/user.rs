@@ -1,23 +1,62 @@
+use std::str::FromStr;
+
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use chrono::NaiveDateTime;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+use crate::domain::error::UnknownEnumValue;
+use crate::domain::models::common::{deserialize_named_enum, serialize_named_enum, NamedEnum};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Role {
     User = 0,
     Admin = 1,
     SuperAdmin = 2,
 }
 
-impl From<i32> for Role {
-    fn from(role: i32) -> Self {
-        match role {
-            0 => Role::User,
-            1 => Role::Admin,
-            2 => Role::SuperAdmin,
-            _ => Role::User,
+impl NamedEnum for Role {
+    const NAME: &'static str = "role";
+    const VARIANTS: &'static [Self] = &[Role::User, Role::Admin, Role::SuperAdmin];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+            Role::SuperAdmin => "super_admin",
         }
     }
+
+    fn as_i32(&self) -> i32 {
+        *self as i32
+    }
+}
+
+impl TryFrom<i32> for Role {
+    type Error = UnknownEnumValue;
+
+    fn try_from(role: i32) -> Result<Self, Self::Error> {
+        Role::from_i32(role)
+    }
+}
+
+impl FromStr for Role {
+    type Err = UnknownEnumValue;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        Role::from_name(role)
+    }
+}
+
+impl Serialize for Role {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_named_enum(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Role {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_named_enum(deserializer)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -30,7 +69,15 @@ pub struct User {
     pub reset_token: Option<String>,
     pub reset_token_expiry: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
-    pub updated_at: Option<NaiveDateTime>, 
+    pub updated_at: Option<NaiveDateTime>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub email_verification_sent_at: Option<NaiveDateTime>,
+    /// TOTP secret, encrypted. It is stored as soon as enrollment starts but
+    /// only asked for once `totp_enabled_at` is set.
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    /// Time step of the last code accepted, so no code works twice.
+    pub totp_last_used_step: Option<i64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -73,4 +120,6 @@ pub struct UpdateUserHashed {
     pub password_hash: Option<String>,
     pub reset_token: Option<String>,
     pub reset_token_expiry: Option<NaiveDateTime>,
+    /// `Some(None)` marks the email as unverified again, e.g. after it changed.
+    pub email_verified_at: Option<Option<NaiveDateTime>>,
 }
\ No newline at end of file
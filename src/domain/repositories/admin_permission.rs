@@ -2,8 +2,8 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::repositories::repository::{QueryParams, ResultPaging, RepositoryResult, DEFAULT_LIMIT, DEFAULT_OFFSET};
-use crate::domain::models::admin_permission::{AdminPermission, CreateAdminPermission, UpdateAdminPermission};
+use crate::domain::repositories::repository::{QueryParams, ResultPaging, RepositoryResult, page_limit, page_offset};
+use crate::domain::models::admin_permission::{AdminPermission, AdminPermissions, CreateAdminPermission, UpdateAdminPermission};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AdminPermissionQueryParams {
@@ -13,10 +13,10 @@ pub struct AdminPermissionQueryParams {
 
 impl QueryParams for AdminPermissionQueryParams {
     fn limit(&self) -> i64 {
-        self.limit.or(DEFAULT_LIMIT).unwrap_or_default()
+        page_limit(self.limit)
     }
     fn offset(&self) -> i64 {
-        self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
+        page_offset(self.offset)
     }
 }
 
@@ -27,4 +27,5 @@ pub trait AdminPermissionRepository: Send + Sync {
     async fn get(&self, admin_permission_id: Uuid) -> RepositoryResult<AdminPermission>;
     async fn update(&self, admin_permission_id: Uuid, updated_admin_permission: &UpdateAdminPermission) -> RepositoryResult<AdminPermission>;
     async fn delete(&self, admin_permission_id: Uuid) -> RepositoryResult<bool>;
+    async fn has_permission(&self, user_id: Uuid, permission: AdminPermissions) -> RepositoryResult<bool>;
 }
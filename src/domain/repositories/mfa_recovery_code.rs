@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::repositories::repository::RepositoryResult;
+
+/// One-time codes that stand in for a TOTP code when the authenticator is
+/// lost. Only digests of the codes are stored.
+#[async_trait]
+pub trait MfaRecoveryCodeRepository: Send + Sync {
+    /// Replaces every code of the user with `codes`, all at once.
+    async fn replace(&self, user_id: Uuid, codes: &[String]) -> RepositoryResult<()>;
+    /// Deletes the code if the user has it. Returns whether it did, so a code
+    /// can only be used once.
+    async fn consume(&self, user_id: Uuid, code: &str) -> RepositoryResult<bool>;
+    async fn delete_by_user(&self, user_id: Uuid) -> RepositoryResult<usize>;
+}
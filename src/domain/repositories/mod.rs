@@ -1,3 +1,6 @@
 pub mod admin_permission;
+pub mod mfa_recovery_code;
 pub mod repository;
+pub mod session;
+pub mod token_blacklist;
 pub mod user;
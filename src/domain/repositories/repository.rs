@@ -1,16 +1,45 @@
 use serde::{Deserialize, Serialize};
 use crate::domain::error::RepositoryError;
+use crate::domain::models::common::{MessageWithPaginationResponse, PaginationParams};
 
 pub type RepositoryResult<T> = Result<T, RepositoryError>;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResultPaging<T> {
     pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
     pub items: Vec<T>,
 }
 
+impl<T> ResultPaging<T> {
+    pub fn pagination(&self) -> PaginationParams {
+        PaginationParams::new(self.total, self.limit, self.offset)
+    }
+
+    pub fn into_response(self, message: &str) -> MessageWithPaginationResponse<T> {
+        MessageWithPaginationResponse {
+            message: message.to_string(),
+            pagination: self.pagination(),
+            objects: self.items,
+        }
+    }
+}
+
 pub const DEFAULT_OFFSET: Option<i64> = Some(0);
 pub const DEFAULT_LIMIT: Option<i64> = Some(25);
+/// Most rows a single page may hold, so one request can't read a whole table.
+pub const MAX_LIMIT: i64 = 100;
+
+/// The requested page size, or the default, kept between 1 and `MAX_LIMIT`.
+pub fn page_limit(limit: Option<i64>) -> i64 {
+    limit.or(DEFAULT_LIMIT).unwrap_or_default().clamp(1, MAX_LIMIT)
+}
+
+/// The requested offset, or the default, but never negative.
+pub fn page_offset(offset: Option<i64>) -> i64 {
+    offset.or(DEFAULT_OFFSET).unwrap_or_default().max(0)
+}
 
 pub trait QueryParams: Send + Sync {
     fn limit(&self) -> i64;
@@ -25,9 +54,9 @@ pub struct QueryParamsImpl {
 
 impl QueryParams for QueryParamsImpl {
     fn limit(&self) -> i64 {
-        self.limit.or(DEFAULT_LIMIT).unwrap_or_default()
+        page_limit(self.limit)
     }
     fn offset(&self) -> i64 {
-        self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
+        page_offset(self.offset)
     }
 }
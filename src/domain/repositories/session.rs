@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::repositories::repository::{QueryParams, ResultPaging, RepositoryResult, page_limit, page_offset};
+use crate::domain::models::session::{Session, CreateSession};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionQueryParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl QueryParams for SessionQueryParams {
+    fn limit(&self) -> i64 {
+        page_limit(self.limit)
+    }
+    fn offset(&self) -> i64 {
+        page_offset(self.offset)
+    }
+}
+
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    async fn create(&self, new_session: &CreateSession) -> RepositoryResult<Session>;
+    async fn list_by_user(&self, user_id: Uuid, params: SessionQueryParams) -> RepositoryResult<ResultPaging<Session>>;
+    async fn get_by_token(&self, token: &str) -> RepositoryResult<Session>;
+    async fn delete(&self, session_id: Uuid) -> RepositoryResult<bool>;
+    async fn delete_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>>;
+    async fn delete_expired(&self) -> RepositoryResult<usize>;
+}
@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+
+use crate::domain::repositories::repository::RepositoryResult;
+use crate::domain::models::session::CreateTokenBlacklist;
+
+#[async_trait]
+pub trait TokenBlacklistRepository: Send + Sync {
+    async fn create(&self, new_entry: &CreateTokenBlacklist) -> RepositoryResult<()>;
+    async fn is_blacklisted(&self, token: &str) -> RepositoryResult<bool>;
+    async fn delete_expired(&self) -> RepositoryResult<usize>;
+}
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::repositories::repository::{QueryParams, ResultPaging, RepositoryResult, DEFAULT_LIMIT, DEFAULT_OFFSET};
+use crate::domain::repositories::repository::{QueryParams, ResultPaging, RepositoryResult, page_limit, page_offset};
 use crate::domain::models::user::{User, CreateUserHashed, UpdateUserHashed};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -13,18 +14,43 @@ pub struct UserQueryParams {
 
 impl QueryParams for UserQueryParams {
     fn limit(&self) -> i64 {
-        self.limit.or(DEFAULT_LIMIT).unwrap_or_default()
+        page_limit(self.limit)
     }
     fn offset(&self) -> i64 {
-        self.offset.or(DEFAULT_OFFSET).unwrap_or_default()
+        page_offset(self.offset)
     }
 }
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     async fn create(&self, new_user: &CreateUserHashed) -> RepositoryResult<User>;
+    /// Inserts every user or, if any of them is rejected, none of them.
+    async fn create_many(&self, new_users: &[CreateUserHashed]) -> RepositoryResult<Vec<User>>;
     async fn list(&self, params: UserQueryParams) -> RepositoryResult<ResultPaging<User>>;
     async fn get(&self, user_id: Uuid) -> RepositoryResult<User>;
+    /// Looks the user up ignoring case, the same way email uniqueness works.
+    async fn get_by_email(&self, user_email: &str) -> RepositoryResult<User>;
+    async fn get_by_reset_token(&self, reset_token: &str) -> RepositoryResult<User>;
     async fn update(&self, user_id: Uuid, updated_user: &UpdateUserHashed) -> RepositoryResult<User>;
+    async fn reset_password(&self, user_id: Uuid, reset_token: &str, password_hash: &str) -> RepositoryResult<User>;
+    /// Records that a verification email is being sent, unless the email is
+    /// already verified or one was sent after `sent_before`. Returns whether it
+    /// was recorded, so concurrent requests can't both send one.
+    async fn mark_verification_sent(&self, user_id: Uuid, sent_before: NaiveDateTime) -> RepositoryResult<bool>;
+    /// Puts back when the previous verification email was sent, for when the
+    /// one just recorded could not be delivered.
+    async fn restore_verification_sent(&self, user_id: Uuid, sent_at: Option<NaiveDateTime>) -> RepositoryResult<bool>;
+    /// Marks the email as verified, as long as the user still has `email`.
+    async fn mark_email_verified(&self, user_id: Uuid, email: &str) -> RepositoryResult<User>;
+    /// Stores the secret of an enrollment that is yet to be confirmed, replacing
+    /// any earlier one. Fails with "not found" once TOTP is enabled.
+    async fn start_totp_enrollment(&self, user_id: Uuid, totp_secret: &str) -> RepositoryResult<User>;
+    /// Enables TOTP, as long as the pending secret is still `totp_secret`. The
+    /// code that confirmed it was for `step`, which is recorded as used.
+    async fn enable_totp(&self, user_id: Uuid, totp_secret: &str, step: i64) -> RepositoryResult<User>;
+    async fn disable_totp(&self, user_id: Uuid) -> RepositoryResult<User>;
+    /// Records a code of `step` as used, unless one of the same or a later step
+    /// already was. Returns whether it was recorded, so a code only works once.
+    async fn mark_totp_used(&self, user_id: Uuid, step: i64) -> RepositoryResult<bool>;
     async fn delete(&self, user_id: Uuid) -> RepositoryResult<bool>;
 }
@@ -2,7 +2,7 @@ use uuid::Uuid;
 use async_trait::async_trait;
 
 use crate::domain::error::CommonError;
-use crate::domain::models::admin_permission::{AdminPermission, CreateAdminPermission, UpdateAdminPermission};
+use crate::domain::models::admin_permission::{AdminPermission, AdminPermissions, CreateAdminPermission, UpdateAdminPermission};
 use crate::domain::repositories::repository::ResultPaging;
 use crate::domain::repositories::admin_permission::AdminPermissionQueryParams;
 
@@ -13,4 +13,5 @@ pub trait AdminPermissionService: Send + Sync {
     async fn get(&self, admin_permission_id: Uuid) -> Result<AdminPermission, CommonError>;
     async fn update(&self, admin_permission_id: Uuid, updated_admin_permission: UpdateAdminPermission) -> Result<AdminPermission, CommonError>;
     async fn delete(&self, admin_permission_id: Uuid) -> Result<bool, CommonError>;
+    async fn has_permission(&self, user_id: Uuid, permission: AdminPermissions) -> Result<bool, CommonError>;
 }
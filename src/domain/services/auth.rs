@@ -1,5 +1,37 @@
+use async_trait::async_trait;
+
+use crate::domain::error::CommonError;
+use crate::domain::models::auth::{
+    AuthForgotPassword,
+    AuthLogin,
+    AuthLoginOutcome,
+    AuthMfaVerify,
+    AuthRefresh,
+    AuthRegister,
+    AuthResetPassword,
+    AuthSuccessfulResponse,
+    AuthenticatedUser,
+    TokenClaims
+};
+use crate::domain::models::session::SessionClient;
 
 #[async_trait]
 pub trait AuthService: Send + Sync {
-
-}
\ No newline at end of file
+    /// Signs the user in, or asks for a second factor first if they have
+    /// two-factor authentication enabled.
+    async fn login(&self, credentials: AuthLogin, client: SessionClient) -> Result<AuthLoginOutcome, CommonError>;
+    /// Exchanges the challenge of a login and a TOTP or recovery code for
+    /// tokens. A challenge can only be tried once.
+    async fn verify_mfa(&self, verification: AuthMfaVerify, client: SessionClient) -> Result<AuthSuccessfulResponse, CommonError>;
+    /// Signs the new user in, unless they have to verify their email first, in
+    /// which case no tokens are issued.
+    async fn register(&self, registration: AuthRegister, client: SessionClient) -> Result<Option<AuthSuccessfulResponse>, CommonError>;
+    async fn refresh(&self, refresh: AuthRefresh, client: SessionClient) -> Result<AuthSuccessfulResponse, CommonError>;
+    async fn validate_access_token(&self, access_token: &str) -> Result<TokenClaims, CommonError>;
+    async fn authenticate(&self, access_token: &str) -> Result<AuthenticatedUser, CommonError>;
+    async fn logout(&self, claims: &TokenClaims) -> Result<(), CommonError>;
+    async fn logout_all(&self, claims: &TokenClaims) -> Result<(), CommonError>;
+    async fn forgot_password(&self, request: AuthForgotPassword) -> Result<(), CommonError>;
+    async fn reset_password(&self, reset: AuthResetPassword) -> Result<(), CommonError>;
+    async fn prune_expired_tokens(&self) -> Result<usize, CommonError>;
+}
@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+use crate::domain::error::CommonError;
+use crate::domain::models::auth::{AuthResendVerification, AuthVerifyEmail, VerifiedAction};
+use crate::domain::models::user::User;
+
+#[async_trait]
+pub trait EmailVerificationService: Send + Sync {
+    /// Mails the user a token proving they own their current address.
+    async fn send_verification(&self, user: &User) -> Result<(), CommonError>;
+    /// Sends the token again, at most once per cooldown. Like a password reset
+    /// request it succeeds for unknown addresses too, so it can't be used to
+    /// find out which accounts exist.
+    async fn resend_verification(&self, request: AuthResendVerification) -> Result<(), CommonError>;
+    async fn verify_email(&self, verification: AuthVerifyEmail) -> Result<User, CommonError>;
+    fn is_required_for(&self, action: VerifiedAction) -> bool;
+    /// Fails if `action` needs a verified email and the user's isn't.
+    fn ensure_verified(&self, user: &User, action: VerifiedAction) -> Result<(), CommonError>;
+}
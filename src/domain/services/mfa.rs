@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+
+use crate::domain::error::CommonError;
+use crate::domain::models::auth::{AuthMfaCode, AuthenticatedUser};
+use crate::domain::models::mfa::{MfaEnrollment, MfaRecoveryCodes};
+use crate::domain::models::user::User;
+
+#[async_trait]
+pub trait MfaService: Send + Sync {
+    /// Generates a new TOTP secret. It isn't asked for until the enrollment is
+    /// confirmed with a code, so a half finished enrollment locks no one out.
+    async fn enroll(&self, user: &User) -> Result<MfaEnrollment, CommonError>;
+    /// Enables TOTP once the user proves their app produces the right codes,
+    /// and hands out the first recovery codes.
+    async fn confirm_enrollment(&self, user: &User, confirmation: AuthMfaCode) -> Result<MfaRecoveryCodes, CommonError>;
+    /// Turns TOTP off. Takes a TOTP or recovery code, so a stolen access token
+    /// alone can't remove the second factor.
+    async fn disable(&self, user: &User, confirmation: AuthMfaCode) -> Result<(), CommonError>;
+    /// Replaces the recovery codes, invalidating the old ones.
+    async fn regenerate_recovery_codes(&self, user: &User, confirmation: AuthMfaCode) -> Result<MfaRecoveryCodes, CommonError>;
+    /// Checks a TOTP code, allowing for some clock skew, or a recovery code.
+    /// Either works only once.
+    async fn verify_code(&self, user: &User, code: &str) -> Result<(), CommonError>;
+    fn is_enabled(&self, user: &User) -> bool;
+    /// Whether the user's role has to use two-factor authentication.
+    fn is_required_for(&self, user: &User) -> bool;
+    /// Fails if the user's role requires two-factor authentication and the
+    /// caller's tokens weren't issued after passing it.
+    fn ensure_satisfied(&self, authenticated_user: &AuthenticatedUser) -> Result<(), CommonError>;
+    /// Fails unless the caller's tokens were issued after passing two-factor
+    /// authentication, whatever their role.
+    fn ensure_used(&self, authenticated_user: &AuthenticatedUser) -> Result<(), CommonError>;
+}
@@ -1,3 +1,6 @@
 pub mod admin_permission;
 pub mod auth;
+pub mod email_verification;
+pub mod mfa;
+pub mod session;
 pub mod user;
@@ -0,0 +1,12 @@
+use uuid::Uuid;
+use async_trait::async_trait;
+
+use crate::domain::error::CommonError;
+use crate::domain::models::session::Session;
+use crate::domain::repositories::repository::ResultPaging;
+use crate::domain::repositories::session::SessionQueryParams;
+
+#[async_trait]
+pub trait SessionService: Send + Sync {
+    async fn list_by_user(&self, user_id: Uuid, params: SessionQueryParams) -> Result<ResultPaging<Session>, CommonError>;
+}
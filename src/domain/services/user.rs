@@ -2,15 +2,22 @@ use uuid::Uuid;
 use async_trait::async_trait;
 
 use crate::domain::error::CommonError;
-use crate::domain::models::user::{User, CreateUserPlainText, UpdateUserPlainText};
+use crate::domain::models::user::{User, CreateUserHashed, CreateUserPlainText, UpdateUserPlainText};
 use crate::domain::repositories::repository::ResultPaging;
 use crate::domain::repositories::user::UserQueryParams;
 
+/// The methods that change accounts take the `actor` making the change. Only a
+/// `Role::SuperAdmin` assigns roles other than `Role::User`, changes roles at
+/// all, or changes and deletes the accounts of other super admins.
 #[async_trait]
 pub trait UserService: Send + Sync {
-    async fn create(&self, new_user: CreateUserPlainText) -> Result<User, CommonError>;
+    /// `actor` is `None` when people sign up themselves.
+    async fn create(&self, new_user: CreateUserPlainText, actor: Option<&User>) -> Result<User, CommonError>;
+    /// Creates users whose passwords were hashed elsewhere, e.g. when moving
+    /// accounts over from another platform.
+    async fn import(&self, new_users: Vec<CreateUserHashed>, actor: &User) -> Result<Vec<User>, CommonError>;
     async fn list(&self, params: UserQueryParams) -> Result<ResultPaging<User>, CommonError>;
     async fn get(&self, user_id: Uuid) -> Result<User, CommonError>;
-    async fn update(&self, user_id: Uuid, updated_user: UpdateUserPlainText) -> Result<User, CommonError>;
-    async fn delete(&self, user_id: Uuid) -> Result<bool, CommonError>;
+    async fn update(&self, user_id: Uuid, updated_user: UpdateUserPlainText, actor: &User) -> Result<User, CommonError>;
+    async fn delete(&self, user_id: Uuid, actor: &User) -> Result<bool, CommonError>;
 }
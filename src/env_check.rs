@@ -6,28 +6,140 @@ use tracing::{info, warn, error};
 
 use crate::constants as main_constants;
 use crate::domain::constants as domain_constants;
+use crate::infrastructure::services::email_verification::EmailVerificationPolicy;
+use crate::infrastructure::services::mfa::MfaPolicy;
+use crate::services::concrete::jwt_token::JwtTokenService;
+use crate::services::concrete::pepper::PepperRing;
+use crate::services::concrete::secret_cipher::SecretCipher;
 use crate::services::constants as services_constants;
+use crate::services::utils::envutil::get_env_var_as_type_or_default;
 
 const ENV_CHECK_MSG_FAILED_TO_START: &str = "Program failed to start.";
 const ENV_CHECK_MSG_VARIABLE_MISSING: &str = "Variable is missing.";
 const ENV_CHECK_MSG_VARIABLE_UNICODE: &str = "Variable might be of invalid format.";
+const ENV_CHECK_MSG_VARIABLE_INVALID: &str = "Variable has an invalid value.";
 const ENV_CHECK_MSG_DEFAULT_FALLBACK: &str = "Using default value when needed.";
+const ENV_CHECK_MSG_PEPPER_MISSING: &str = "Passwords are hashed without a pepper.";
+const ENV_CHECK_MSG_PEPPER_TWICE: &str = "Set either PASSWORD_PEPPER or PASSWORD_PEPPER_FILE, not both.";
+const ENV_CHECK_MSG_PASSWORD_LENGTHS_INVERTED: &str = "Must not be greater than PASSWORD_MAX_LENGTH.";
+const ENV_CHECK_MSG_MAIL_FILE_DIR_MISSING: &str = "Must be set when MAIL_TRANSPORT is `file`.";
 const ENV_CHECK_MSG_REQUIRED_VARS_PRESENT: &str = "All required variables are present.";
 const ENV_CHECK_MSG_EVERYTHING_OK: &str = "Everything seems okay. Program is ready to start.";
 
+/// Variable name, validator and a hint shown when the value is rejected.
+type ValidatedVar<'a> = (&'a str, fn(&str) -> bool, &'a str);
+
+fn is_valid_jwt_secret(value: &str) -> bool {
+    value.len() >= services_constants::SEC_JWT_SECRET_MIN_LEN
+}
+
+fn is_valid_jwt_encryption_key(value: &str) -> bool {
+    JwtTokenService::decode_encryption_key(value).is_some()
+}
+
+/// Peppers must be long enough and must not reuse the token secrets, so leaking
+/// one secret doesn't give away the other.
+fn is_valid_pepper_ring(value: &str) -> bool {
+    PepperRing::parse(value).is_ok_and(|peppers| {
+        [services_constants::SEC_JWT_ENV_SECRET, services_constants::SEC_JWT_ENV_ENCRYPTION_KEY]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .all(|secret| !peppers.contains(secret.as_bytes()))
+    })
+}
+
+fn is_valid_pepper_file(path: &str) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|contents| is_valid_pepper_ring(&contents))
+}
+
+fn is_readable_file(path: &str) -> bool {
+    std::fs::read_to_string(path).is_ok()
+}
+
+/// Must not reuse the token encryption key, for the same reason as peppers.
+fn is_valid_mfa_encryption_key(value: &str) -> bool {
+    SecretCipher::decode_key(value).is_some()
+        && env::var(services_constants::SEC_JWT_ENV_ENCRYPTION_KEY).map_or(true, |key| key.trim() != value.trim())
+}
+
+fn is_valid_mfa_roles(value: &str) -> bool {
+    MfaPolicy::parse_required_roles(value).is_ok()
+}
+
+fn is_valid_verified_actions(value: &str) -> bool {
+    EmailVerificationPolicy::parse_required_for(value).is_ok()
+}
+
+fn is_valid_mail_transport(value: &str) -> bool {
+    [services_constants::MAIL_TRANSPORT_LOG, services_constants::MAIL_TRANSPORT_FILE].contains(&value)
+}
+
+fn is_positive_integer(value: &str) -> bool {
+    value.parse::<i64>().is_ok_and(|value| value > 0)
+}
+
+fn is_non_negative_integer(value: &str) -> bool {
+    value.parse::<u32>().is_ok()
+}
+
 pub fn check_env_variables() {
     dotenv().ok();
 
     let required_vars = vec![
         main_constants::ENV_SERVER_DOMAIN,
         main_constants::ENV_SERVER_PORT,
-        domain_constants::POSTGRESQL_DB_URI 
+        domain_constants::POSTGRESQL_DB_URI,
+        services_constants::SEC_JWT_ENV_SECRET,
+        services_constants::SEC_JWT_ENV_ENCRYPTION_KEY,
+        services_constants::SEC_MFA_ENV_ENCRYPTION_KEY
     ];
     let recommended_vars = vec![
+        domain_constants::POSTGRESQL_ENV_POOL_MAX_SIZE,
+        domain_constants::POSTGRESQL_ENV_POOL_CONNECTION_TIMEOUT_SECS,
+        domain_constants::POSTGRESQL_ENV_POOL_MAX_LIFETIME_SECS,
         services_constants::SEC_ARGON2ID_ENV_MEMORY_SIZE_MB,
         services_constants::SEC_ARGON2ID_ENV_NUM_ITERATIONS,
         services_constants::SEC_ARGON2ID_ENV_NUM_THREADS,
-        services_constants::SEC_ARGON2ID_ENV_OUTPUT_LEN
+        services_constants::SEC_ARGON2ID_ENV_OUTPUT_LEN,
+        services_constants::SEC_HASHING_POOL_ENV_WORKERS,
+        services_constants::SEC_HASHING_POOL_ENV_QUEUE_SIZE,
+        services_constants::SEC_PASSWORD_ENV_MIN_LENGTH,
+        services_constants::SEC_PASSWORD_ENV_MAX_LENGTH,
+        services_constants::SEC_JWT_ENV_ACCESS_TOKEN_LIFETIME_SECS,
+        services_constants::SEC_JWT_ENV_REFRESH_TOKEN_LIFETIME_SECS,
+        services_constants::SEC_TOKEN_PRUNE_ENV_INTERVAL_SECS,
+        services_constants::SEC_PASSWORD_RESET_ENV_TOKEN_LIFETIME_SECS,
+        services_constants::SEC_EMAIL_VERIFICATION_ENV_TOKEN_LIFETIME_SECS,
+        services_constants::SEC_EMAIL_VERIFICATION_ENV_REQUIRED_FOR,
+        services_constants::SEC_MFA_ENV_REQUIRED_ROLES,
+        services_constants::MAIL_ENV_TRANSPORT
+    ];
+    let validated_vars: Vec<ValidatedVar> = vec![
+        (domain_constants::POSTGRESQL_ENV_POOL_MAX_SIZE, is_positive_integer, "Must be a positive number of connections."),
+        (domain_constants::POSTGRESQL_ENV_POOL_MIN_IDLE, is_non_negative_integer, "Must be zero or a positive number of connections."),
+        (domain_constants::POSTGRESQL_ENV_POOL_CONNECTION_TIMEOUT_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (domain_constants::POSTGRESQL_ENV_POOL_MAX_LIFETIME_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (services_constants::SEC_HASHING_POOL_ENV_WORKERS, is_positive_integer, "Must be a positive number of threads."),
+        (services_constants::SEC_HASHING_POOL_ENV_QUEUE_SIZE, is_non_negative_integer, "Must be zero or a positive number of queued jobs."),
+        (services_constants::SEC_JWT_ENV_SECRET, is_valid_jwt_secret, "Must be at least 32 bytes long."),
+        (services_constants::SEC_JWT_ENV_ENCRYPTION_KEY, is_valid_jwt_encryption_key, "Must be a base64 encoded 32 byte key."),
+        (services_constants::SEC_PASSWORD_ENV_MIN_LENGTH, is_positive_integer, "Must be a positive number of characters."),
+        (services_constants::SEC_PASSWORD_ENV_MAX_LENGTH, is_positive_integer, "Must be a positive number of characters."),
+        (services_constants::SEC_PASSWORD_ENV_BLOCKLIST_FILE, is_readable_file, "Must be a readable file with one password per line."),
+        (services_constants::SEC_PEPPER_ENV_VALUE, is_valid_pepper_ring, "Must be `<version>:<pepper>` entries, each pepper at least 32 bytes long and different from the JWT secrets."),
+        (services_constants::SEC_PEPPER_ENV_FILE, is_valid_pepper_file, "Must be a readable file of `<version>:<pepper>` entries, each pepper at least 32 bytes long and different from the JWT secrets."),
+        (services_constants::SEC_JWT_ENV_ACCESS_TOKEN_LIFETIME_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (services_constants::SEC_JWT_ENV_REFRESH_TOKEN_LIFETIME_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (services_constants::SEC_TOKEN_PRUNE_ENV_INTERVAL_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (services_constants::SEC_PASSWORD_RESET_ENV_TOKEN_LIFETIME_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (services_constants::SEC_EMAIL_VERIFICATION_ENV_TOKEN_LIFETIME_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (services_constants::SEC_EMAIL_VERIFICATION_ENV_RESEND_COOLDOWN_SECS, is_non_negative_integer, "Must be zero or a positive number of seconds."),
+        (services_constants::SEC_EMAIL_VERIFICATION_ENV_REQUIRED_FOR, is_valid_verified_actions, "Must be a comma separated list of: login, admin."),
+        (services_constants::SEC_MFA_ENV_ENCRYPTION_KEY, is_valid_mfa_encryption_key, "Must be a base64 encoded 32 byte key, different from JWT_ENCRYPTION_KEY."),
+        (services_constants::SEC_MFA_ENV_REQUIRED_ROLES, is_valid_mfa_roles, "Must be a comma separated list of: user, admin, super_admin."),
+        (services_constants::SEC_MFA_ENV_SKEW_STEPS, is_non_negative_integer, "Must be zero or a positive number of 30 second steps."),
+        (services_constants::SEC_MFA_ENV_CHALLENGE_TOKEN_LIFETIME_SECS, is_positive_integer, "Must be a positive number of seconds."),
+        (services_constants::MAIL_ENV_TRANSPORT, is_valid_mail_transport, "Must be `log` or `file`.")
     ];
     let mut is_everything_ok = true;
 
@@ -51,6 +163,43 @@ pub fn check_env_variables() {
 
     info!("{}", Color::Green.bold().paint(ENV_CHECK_MSG_REQUIRED_VARS_PRESENT));
 
+    for (var, is_valid, hint) in validated_vars {
+        if let Ok(value) = env::var(var) {
+            if !is_valid(&value) {
+                error!("{} - {} {} {}", Color::Red.bold().paint(var), ENV_CHECK_MSG_FAILED_TO_START, ENV_CHECK_MSG_VARIABLE_INVALID, hint);
+                is_everything_ok = false;
+            }
+        }
+    }
+
+    match (env::var(services_constants::SEC_PEPPER_ENV_VALUE).is_ok(), env::var(services_constants::SEC_PEPPER_ENV_FILE).is_ok()) {
+        (true, true) => {
+            error!("{} - {} {}", Color::Red.bold().paint(services_constants::SEC_PEPPER_ENV_FILE), ENV_CHECK_MSG_FAILED_TO_START, ENV_CHECK_MSG_PEPPER_TWICE);
+            is_everything_ok = false;
+        }
+        (false, false) => {
+            warn!("{} - {} {}", Color::Yellow.bold().paint(services_constants::SEC_PEPPER_ENV_VALUE), ENV_CHECK_MSG_VARIABLE_MISSING, ENV_CHECK_MSG_PEPPER_MISSING);
+        }
+        _ => {}
+    }
+
+    let password_min_length = get_env_var_as_type_or_default(services_constants::SEC_PASSWORD_ENV_MIN_LENGTH, &services_constants::SEC_PASSWORD_MIN_LENGTH_DEFAULT);
+    let password_max_length = get_env_var_as_type_or_default(services_constants::SEC_PASSWORD_ENV_MAX_LENGTH, &services_constants::SEC_PASSWORD_MAX_LENGTH_DEFAULT);
+    if password_min_length > password_max_length {
+        error!("{} - {} {}", Color::Red.bold().paint(services_constants::SEC_PASSWORD_ENV_MIN_LENGTH), ENV_CHECK_MSG_FAILED_TO_START, ENV_CHECK_MSG_PASSWORD_LENGTHS_INVERTED);
+        is_everything_ok = false;
+    }
+
+    let uses_file_mailer = env::var(services_constants::MAIL_ENV_TRANSPORT).is_ok_and(|value| value == services_constants::MAIL_TRANSPORT_FILE);
+    if uses_file_mailer && env::var(services_constants::MAIL_ENV_FILE_DIR).is_err() {
+        error!("{} - {} {}", Color::Red.bold().paint(services_constants::MAIL_ENV_FILE_DIR), ENV_CHECK_MSG_FAILED_TO_START, ENV_CHECK_MSG_MAIL_FILE_DIR_MISSING);
+        is_everything_ok = false;
+    }
+
+    if !is_everything_ok {
+        std::process::exit(1);
+    }
+
     for var in recommended_vars {
         match env::var(var) {
             Ok(_) => {},
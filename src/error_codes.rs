@@ -1,4 +1,92 @@
-pub const ERR_CODE_REPOSITORY: u32 = 0x8000_0001;
-pub const ERR_CODE_SECURITY: u32 = 0x8000_0002;
-pub const ERR_CODE_ENV_VARIABLE: u32 = 0x8000_0003;
-pub const ERR_CODE_AUTH: u32 = 0x8000_0004;
\ No newline at end of file
+use crate::domain::constants as domain_constants;
+use crate::domain::models::common::ErrorResponseType;
+use crate::services::constants as services_constants;
+
+/// A known error: the identifier carried by errors raised in the code base,
+/// its stable numeric code, the response type (and so the HTTP status) it maps
+/// to by default, and a human readable message clients can localize.
+#[derive(Clone, Debug)]
+pub struct ErrorCodeEntry {
+    pub identifier: &'static str,
+    pub code: u32,
+    pub error_type: ErrorResponseType,
+    pub message: &'static str,
+}
+
+const fn entry(identifier: &'static str, code: u32, error_type: ErrorResponseType, message: &'static str) -> ErrorCodeEntry {
+    ErrorCodeEntry { identifier, code, error_type, message }
+}
+
+/// Every error identifier the API can return. Codes are grouped by area and
+/// must never be reused or renumbered once published.
+pub const ERROR_CATALOG: &[ErrorCodeEntry] = &[
+    // 1xxx - environment
+    entry(services_constants::ENV_ERR_VARIABLE_MISSING, 1001, ErrorResponseType::InternalServerError, "A required environment variable is missing"),
+    entry(services_constants::ENV_ERR_PARSE_FAIL, 1002, ErrorResponseType::InternalServerError, "An environment variable has an invalid value"),
+
+    // 2xxx - storage
+    entry(domain_constants::REPO_ERR_NOT_FOUND, 2001, ErrorResponseType::NotFound, "The requested resource was not found"),
+    entry(domain_constants::REPO_ERR_UNIQUE_VIOLATION, 2002, ErrorResponseType::Conflict, "The resource conflicts with an existing one"),
+    entry(domain_constants::REPO_ERR_FOREIGN_KEY_VIOLATION, 2003, ErrorResponseType::Conflict, "The resource is referenced by or refers to another resource"),
+    entry(domain_constants::REPO_ERR_CONNECTION, 2004, ErrorResponseType::InternalServerError, "The database is currently unavailable"),
+    entry(domain_constants::REPO_ERR_OTHER, 2005, ErrorResponseType::InternalServerError, "The database could not process the request"),
+
+    // 3xxx - passwords, tokens and authentication
+    entry(services_constants::SEC_ERR_HASH_PARSE_FAIL, 3001, ErrorResponseType::InternalServerError, "A stored password hash could not be read"),
+    entry(services_constants::SEC_ERR_PASS_VERIFY, 3002, ErrorResponseType::Unauthorized, "The password could not be verified"),
+    entry(services_constants::SEC_ERR_HASH_FAILED, 3003, ErrorResponseType::InternalServerError, "The password could not be hashed"),
+    entry(services_constants::SEC_ERR_PASS_NOT_MATCH, 3004, ErrorResponseType::Validation, "The password and its confirmation do not match"),
+    entry(services_constants::SEC_ERR_AUTHENTICATION, 3005, ErrorResponseType::Unauthorized, "Authentication failed"),
+    entry(services_constants::SEC_ERR_INVALID_CREDENTIALS, 3006, ErrorResponseType::Unauthorized, "Invalid email or password"),
+    entry(services_constants::SEC_ERR_TOKEN_ENCODE, 3007, ErrorResponseType::InternalServerError, "The token could not be issued"),
+    entry(services_constants::SEC_ERR_TOKEN_ENCRYPT, 3008, ErrorResponseType::InternalServerError, "The token could not be issued"),
+    entry(services_constants::SEC_ERR_TOKEN_INVALID, 3009, ErrorResponseType::Unauthorized, "The token is invalid"),
+    entry(services_constants::SEC_ERR_TOKEN_EXPIRED, 3010, ErrorResponseType::Unauthorized, "The token has expired"),
+    entry(services_constants::SEC_ERR_TOKEN_TYPE_MISMATCH, 3011, ErrorResponseType::Unauthorized, "The token was not issued for this purpose"),
+    entry(services_constants::SEC_ERR_SESSION_INVALID, 3012, ErrorResponseType::Unauthorized, "The session has ended or does not exist"),
+    entry(services_constants::SEC_ERR_TOKEN_REVOKED, 3013, ErrorResponseType::Unauthorized, "The token has been revoked"),
+    entry(services_constants::SEC_ERR_TOKEN_MISSING, 3014, ErrorResponseType::Unauthorized, "A bearer token is required"),
+    entry(services_constants::SEC_ERR_RESET_TOKEN_INVALID, 3015, ErrorResponseType::General, "The reset token is invalid or has expired"),
+    entry(services_constants::SEC_ERR_HASHING_OVERLOADED, 3016, ErrorResponseType::ServiceUnavailable, "The server is busy, please retry shortly"),
+    entry(services_constants::SEC_ERR_PEPPER_UNKNOWN, 3017, ErrorResponseType::InternalServerError, "The password could not be verified"),
+    entry(services_constants::SEC_ERR_EMAIL_NOT_VERIFIED, 3018, ErrorResponseType::Forbidden, "The email address has to be verified first"),
+    entry(services_constants::SEC_ERR_VERIFICATION_TOKEN_INVALID, 3019, ErrorResponseType::General, "The verification token is invalid or has expired"),
+    entry(services_constants::SEC_ERR_MFA_CODE_INVALID, 3020, ErrorResponseType::General, "The two-factor code is invalid or was already used"),
+    entry(services_constants::SEC_ERR_MFA_CHALLENGE_INVALID, 3021, ErrorResponseType::Unauthorized, "The two-factor challenge is invalid or has expired"),
+    entry(services_constants::SEC_ERR_MFA_REQUIRED, 3022, ErrorResponseType::Forbidden, "Two-factor authentication is required for this resource"),
+    entry(services_constants::SEC_ERR_MFA_ALREADY_ENABLED, 3023, ErrorResponseType::Conflict, "Two-factor authentication is already enabled"),
+    entry(services_constants::SEC_ERR_MFA_NOT_ENABLED, 3024, ErrorResponseType::Conflict, "Two-factor authentication is not enabled"),
+    entry(services_constants::SEC_ERR_SECRET_ENCRYPT, 3025, ErrorResponseType::InternalServerError, "A secret could not be stored"),
+    entry(services_constants::SEC_ERR_SECRET_DECRYPT, 3026, ErrorResponseType::InternalServerError, "A stored secret could not be read"),
+
+    // 4xxx - authorization
+    entry(services_constants::SEC_ERR_PERMISSION_DENIED, 4001, ErrorResponseType::Forbidden, "Missing the permission required for this resource"),
+
+    // 5xxx - users and permissions
+    entry(services_constants::USER_ERR_EMAIL_TAKEN, 5001, ErrorResponseType::Conflict, "The email is already registered"),
+    entry(services_constants::ADMIN_PERMISSION_ERR_USER_NOT_FOUND, 5002, ErrorResponseType::Validation, "The user of the permission does not exist"),
+
+    // 6xxx - mail delivery
+    entry(services_constants::MAIL_ERR_DELIVERY_FAILED, 6001, ErrorResponseType::InternalServerError, "The email could not be sent"),
+
+    // 7xxx - request validation
+    entry(services_constants::VAL_ERR_VALIDATION_FAILED, 7001, ErrorResponseType::Validation, "The request contains invalid fields"),
+    entry(services_constants::VAL_ERR_FIELD_REQUIRED, 7002, ErrorResponseType::Validation, "The field is required"),
+    entry(services_constants::VAL_ERR_FIELD_TOO_LONG, 7003, ErrorResponseType::Validation, "The field is too long"),
+    entry(services_constants::VAL_ERR_INVALID_EMAIL, 7004, ErrorResponseType::Validation, "The field is not a valid email address"),
+    entry(services_constants::VAL_ERR_INVALID_UUID, 7005, ErrorResponseType::Validation, "The field is not a valid UUID"),
+    entry(services_constants::VAL_ERR_INVALID_ENUM_VALUE, 7006, ErrorResponseType::Validation, "The field is not one of the accepted values"),
+    entry(services_constants::VAL_ERR_INVALID_JSON, 7007, ErrorResponseType::Validation, "The request body could not be read"),
+    entry(services_constants::VAL_ERR_INVALID_PATH, 7008, ErrorResponseType::Validation, "The request path contains invalid values"),
+    entry(services_constants::VAL_ERR_INVALID_QUERY, 7009, ErrorResponseType::Validation, "The query string contains invalid values"),
+    entry(services_constants::VAL_ERR_UNSUPPORTED_PASSWORD_HASH, 7010, ErrorResponseType::Validation, "The password hash is not in a supported format"),
+    entry(services_constants::VAL_ERR_TOO_MANY_ITEMS, 7011, ErrorResponseType::Validation, "The list has too many items"),
+    entry(services_constants::VAL_ERR_PASSWORD_TOO_SHORT, 7012, ErrorResponseType::Validation, "The password is too short"),
+    entry(services_constants::VAL_ERR_PASSWORD_TOO_LONG, 7013, ErrorResponseType::Validation, "The password is too long"),
+    entry(services_constants::VAL_ERR_PASSWORD_TOO_COMMON, 7014, ErrorResponseType::Validation, "The password is too common"),
+    entry(services_constants::VAL_ERR_PASSWORD_PERSONAL, 7015, ErrorResponseType::Validation, "The password contains the name or email of its owner"),
+];
+
+pub fn find_error_code(identifier: &str) -> Option<&'static ErrorCodeEntry> {
+    ERROR_CATALOG.iter().find(|entry| entry.identifier == identifier)
+}
@@ -1,22 +1,61 @@
-use diesel::pg::PgConnection;
-use diesel::r2d2;
-use diesel::r2d2::ConnectionManager;
+use std::time::Duration;
+
+use diesel_async::pooled_connection::bb8::{Pool, PooledConnection};
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::AsyncPgConnection;
 use dotenv::dotenv;
 
-use crate::domain::constants::POSTGRESQL_DB_URI;
-use crate::services::utils::envutil::get_env_var_as_str;
+use crate::domain::constants::{
+    POSTGRESQL_DB_URI,
+    POSTGRESQL_ENV_POOL_CONNECTION_TIMEOUT_SECS,
+    POSTGRESQL_ENV_POOL_MAX_LIFETIME_SECS,
+    POSTGRESQL_ENV_POOL_MAX_SIZE,
+    POSTGRESQL_ENV_POOL_MIN_IDLE,
+    POSTGRESQL_POOL_CONNECTION_TIMEOUT_SECS_DEFAULT,
+    POSTGRESQL_POOL_MAX_LIFETIME_SECS_DEFAULT,
+    POSTGRESQL_POOL_MAX_SIZE_DEFAULT
+};
+use crate::domain::repositories::repository::RepositoryResult;
+use crate::infrastructure::error::DieselRepositoryError;
+use crate::services::utils::envutil::{get_env_var_as_str, get_env_var_as_type, get_env_var_as_type_or_default};
 
-pub type Pool<T> = r2d2::Pool<ConnectionManager<T>>;
-pub type PostgresPool = Pool<diesel::pg::PgConnection>;
+pub type PostgresPool = Pool<AsyncPgConnection>;
 pub type DBConn = PostgresPool;
 
-pub fn db_pool() -> DBConn {
+/// Builds the connection pool. It is meant to be called once at startup and
+/// shared by every repository and worker. Sizing comes from the environment;
+/// without `DATABASE_POOL_MIN_IDLE` connections are only opened on demand.
+/// One connection is checked out right away so a bad `DATABASE_URL` stops the
+/// program at startup rather than on the first request.
+pub async fn db_pool() -> DBConn {
     dotenv().ok();
     let database_url = get_env_var_as_str(POSTGRESQL_DB_URI).unwrap_or_else(|_| {
         panic!("{} must be set in .env file", POSTGRESQL_DB_URI);
     });
-    let manager = ConnectionManager::<PgConnection>::new(database_url);
-    Pool::builder()
+    let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(database_url);
+    let pool = Pool::builder()
+        .max_size(get_env_var_as_type_or_default(POSTGRESQL_ENV_POOL_MAX_SIZE, &POSTGRESQL_POOL_MAX_SIZE_DEFAULT))
+        .min_idle(get_env_var_as_type::<u32>(POSTGRESQL_ENV_POOL_MIN_IDLE).ok())
+        .connection_timeout(Duration::from_secs(get_env_var_as_type_or_default(
+            POSTGRESQL_ENV_POOL_CONNECTION_TIMEOUT_SECS,
+            &POSTGRESQL_POOL_CONNECTION_TIMEOUT_SECS_DEFAULT
+        )))
+        .max_lifetime(Some(Duration::from_secs(get_env_var_as_type_or_default(
+            POSTGRESQL_ENV_POOL_MAX_LIFETIME_SECS,
+            &POSTGRESQL_POOL_MAX_LIFETIME_SECS_DEFAULT
+        ))))
         .build(manager)
-        .expect("Failed to create pool")
+        .await
+        .expect("Failed to create pool");
+
+    drop(pool.get().await.expect("Failed to connect to the database"));
+    pool
+}
+
+/// Checks out a connection. A pool that can't hand one out within the timeout
+/// is reported as a connection error.
+pub async fn connection(pool: &DBConn) -> RepositoryResult<PooledConnection<'_, AsyncPgConnection>> {
+    pool.get()
+        .await
+        .map_err(|v| DieselRepositoryError::from(v).into_inner())
 }
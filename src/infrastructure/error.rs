@@ -1,8 +1,6 @@
-use diesel::r2d2;
-pub use actix_threadpool::{run, BlockingError};
-use crate::domain::error::RepositoryError;
-
-pub type AsyncPoolError <T> = BlockingError<T>;
+use diesel::result::DatabaseErrorKind;
+use diesel_async::pooled_connection::bb8::RunError;
+use crate::domain::error::{RepositoryError, RepositoryErrorKind, UnknownEnumValue};
 
 #[derive(Debug)]
 pub struct DieselRepositoryError(RepositoryError);
@@ -13,26 +11,44 @@ impl DieselRepositoryError {
     }
 }
 
-impl From<r2d2::Error> for DieselRepositoryError {
-    fn from(error: r2d2::Error) -> DieselRepositoryError {
+/// Failing to get a connection, whether the pool timed out or the database
+/// refused it, means the database is unavailable.
+impl From<RunError> for DieselRepositoryError {
+    fn from(error: RunError) -> DieselRepositoryError {
         DieselRepositoryError(RepositoryError {
             message: error.to_string(),
+            kind: RepositoryErrorKind::Connection,
         })
     }
 }
 
 impl From<diesel::result::Error> for DieselRepositoryError {
     fn from(error: diesel::result::Error) -> DieselRepositoryError {
+        let kind = match &error {
+            diesel::result::Error::NotFound => RepositoryErrorKind::NotFound,
+            diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => RepositoryErrorKind::UniqueViolation {
+                constraint: info.constraint_name().map(str::to_string),
+            },
+            diesel::result::Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, info) => RepositoryErrorKind::ForeignKeyViolation {
+                constraint: info.constraint_name().map(str::to_string),
+            },
+            diesel::result::Error::DatabaseError(DatabaseErrorKind::ClosedConnection, _) => RepositoryErrorKind::Connection,
+            _ => RepositoryErrorKind::Other,
+        };
         DieselRepositoryError(RepositoryError {
             message: error.to_string(),
+            kind,
         })
     }
 }
 
-impl<T: std::fmt::Debug> From<AsyncPoolError<T>> for DieselRepositoryError {
-    fn from(error: AsyncPoolError<T>) -> DieselRepositoryError {
+/// A stored number that no longer maps to an enum variant means the data is
+/// corrupt or was written by a newer version, so it is not the caller's fault.
+impl From<UnknownEnumValue> for DieselRepositoryError {
+    fn from(error: UnknownEnumValue) -> DieselRepositoryError {
         DieselRepositoryError(RepositoryError {
             message: error.to_string(),
+            kind: RepositoryErrorKind::Other,
         })
     }
 }
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::rt;
+use tracing::{info, warn};
+
+use crate::domain::services::auth::AuthService;
+
+/// Periodically removes blacklist entries and sessions whose tokens have
+/// expired. Once a token can no longer be validated there is no reason to keep
+/// remembering that it was revoked.
+pub fn spawn_expired_token_pruner(auth_service: Arc<dyn AuthService>, period: Duration) {
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(period);
+        loop {
+            interval.tick().await;
+            match auth_service.prune_expired_tokens().await {
+                Ok(pruned) => info!("Pruned {} expired token records", pruned),
+                Err(err) => warn!("Failed to prune expired token records: {}", err),
+            }
+        }
+    });
+}
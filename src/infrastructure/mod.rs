@@ -4,4 +4,5 @@ pub mod repositories;
 pub mod services;
 
 pub mod error;
+pub mod jobs;
 pub mod schema;
@@ -3,9 +3,15 @@ use diesel::prelude::*;
 use uuid::Uuid;
 use chrono::NaiveDateTime;
 
-use crate::domain::models::admin_permission::{AdminPermission, CreateAdminPermission, UpdateAdminPermission};
+use crate::domain::error::RepositoryError;
+use crate::domain::models::admin_permission::{AdminPermission, AdminPermissions, CreateAdminPermission, UpdateAdminPermission};
+use crate::infrastructure::error::DieselRepositoryError;
 use crate::infrastructure::schema::admin_permissions;
 
+fn decode_permission(permission: i32) -> Result<AdminPermissions, RepositoryError> {
+    AdminPermissions::try_from(permission).map_err(|v| DieselRepositoryError::from(v).into_inner())
+}
+
 #[derive(Queryable)]
 pub struct AdminPermissionDiesel {
     pub id: Uuid,
@@ -15,15 +21,17 @@ pub struct AdminPermissionDiesel {
     pub updated_at: Option<NaiveDateTime>,
 }
 
-impl From<AdminPermissionDiesel> for AdminPermission {
-    fn from(permission: AdminPermissionDiesel) -> Self {
-        AdminPermission {
+impl TryFrom<AdminPermissionDiesel> for AdminPermission {
+    type Error = RepositoryError;
+
+    fn try_from(permission: AdminPermissionDiesel) -> Result<Self, Self::Error> {
+        Ok(AdminPermission {
             id: permission.id,
             user_id: permission.user_id,
-            permission: permission.permission.into(),
+            permission: decode_permission(permission.permission)?,
             created_at: permission.created_at,
             updated_at: permission.updated_at,
-        }
+        })
     }
 }
 
@@ -42,37 +50,45 @@ impl From<AdminPermission> for AdminPermissionDiesel {
 #[derive(Insertable)]
 #[diesel(table_name = admin_permissions)]
 pub struct CreateAdminPermissionDiesel {
+    pub id: Uuid,
     pub user_id: Uuid,
     pub permission: i32,
+    pub created_at: NaiveDateTime,
 }
 
-impl From<CreateAdminPermissionDiesel> for CreateAdminPermission {
-    fn from(permission: CreateAdminPermissionDiesel) -> Self {
-        CreateAdminPermission {
+impl TryFrom<CreateAdminPermissionDiesel> for CreateAdminPermission {
+    type Error = RepositoryError;
+
+    fn try_from(permission: CreateAdminPermissionDiesel) -> Result<Self, Self::Error> {
+        Ok(CreateAdminPermission {
             user_id: permission.user_id,
-            permission: permission.permission.into(),
-        }
+            permission: decode_permission(permission.permission)?,
+        })
     }
 }
 
 impl From<CreateAdminPermission> for CreateAdminPermissionDiesel {
     fn from(permission: CreateAdminPermission) -> Self {
         CreateAdminPermissionDiesel {
+            id: Uuid::new_v4(),
             user_id: permission.user_id,
             permission: permission.permission as i32,
+            created_at: chrono::Utc::now().naive_utc(),
         }
     }
 }
 
-impl From<CreateAdminPermissionDiesel> for AdminPermission {
-    fn from(permission: CreateAdminPermissionDiesel) -> Self {
-        AdminPermission {
-            id: Uuid::new_v4(),
+impl TryFrom<CreateAdminPermissionDiesel> for AdminPermission {
+    type Error = RepositoryError;
+
+    fn try_from(permission: CreateAdminPermissionDiesel) -> Result<Self, Self::Error> {
+        Ok(AdminPermission {
+            id: permission.id,
             user_id: permission.user_id,
-            permission: permission.permission.into(),
-            created_at: chrono::Utc::now().naive_utc(),
+            permission: decode_permission(permission.permission)?,
+            created_at: permission.created_at,
             updated_at: None,
-        }
+        })
     }
 }
 
@@ -83,12 +99,14 @@ pub struct UpdateAdminPermissionDiesel {
     pub permission: i32,
 }
 
-impl From<UpdateAdminPermissionDiesel> for UpdateAdminPermission {
-    fn from(permission: UpdateAdminPermissionDiesel) -> Self {
-        UpdateAdminPermission {
+impl TryFrom<UpdateAdminPermissionDiesel> for UpdateAdminPermission {
+    type Error = RepositoryError;
+
+    fn try_from(permission: UpdateAdminPermissionDiesel) -> Result<Self, Self::Error> {
+        Ok(UpdateAdminPermission {
             user_id: permission.user_id,
-            permission: permission.permission.into(),
-        }
+            permission: decode_permission(permission.permission)?,
+        })
     }
 }
 
@@ -101,14 +119,16 @@ impl From<UpdateAdminPermission> for UpdateAdminPermissionDiesel {
     }
 }
 
-impl From<UpdateAdminPermissionDiesel> for AdminPermission {
-    fn from(permission: UpdateAdminPermissionDiesel) -> Self {
-        AdminPermission {
+impl TryFrom<UpdateAdminPermissionDiesel> for AdminPermission {
+    type Error = RepositoryError;
+
+    fn try_from(permission: UpdateAdminPermissionDiesel) -> Result<Self, Self::Error> {
+        Ok(AdminPermission {
             id: Uuid::new_v4(),
             user_id: permission.user_id,
-            permission: permission.permission.into(),
+            permission: decode_permission(permission.permission)?,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: None,
-        }
+        })
     }
 }
\ No newline at end of file
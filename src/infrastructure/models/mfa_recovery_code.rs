@@ -0,0 +1,25 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+
+use crate::infrastructure::schema::mfa_recovery_codes;
+
+#[derive(Insertable)]
+#[diesel(table_name = mfa_recovery_codes)]
+pub struct CreateMfaRecoveryCodeDiesel {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub code: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl CreateMfaRecoveryCodeDiesel {
+    pub fn new(user_id: Uuid, code: String) -> Self {
+        CreateMfaRecoveryCodeDiesel {
+            id: Uuid::new_v4(),
+            user_id,
+            code,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+
+use crate::domain::models::session::{Session, CreateSession};
+use crate::infrastructure::schema::sessions;
+
+#[derive(Queryable)]
+pub struct SessionDiesel {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub expiry: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+impl From<SessionDiesel> for Session {
+    fn from(session: SessionDiesel) -> Self {
+        Session {
+            id: session.id,
+            user_id: session.user_id,
+            token: session.token,
+            expiry: session.expiry,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sessions)]
+pub struct CreateSessionDiesel {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub expiry: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<CreateSession> for CreateSessionDiesel {
+    fn from(session: CreateSession) -> Self {
+        CreateSessionDiesel {
+            id: session.id,
+            user_id: session.user_id,
+            token: session.token,
+            expiry: session.expiry,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
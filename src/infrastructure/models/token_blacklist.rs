@@ -0,0 +1,28 @@
+use diesel::prelude::*;
+use uuid::Uuid;
+use chrono::NaiveDateTime;
+
+use crate::domain::models::session::CreateTokenBlacklist;
+use crate::infrastructure::schema::token_blacklist;
+
+#[derive(Insertable)]
+#[diesel(table_name = token_blacklist)]
+pub struct CreateTokenBlacklistDiesel {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub expiry: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<CreateTokenBlacklist> for CreateTokenBlacklistDiesel {
+    fn from(entry: CreateTokenBlacklist) -> Self {
+        CreateTokenBlacklistDiesel {
+            id: Uuid::new_v4(),
+            user_id: entry.user_id,
+            token: entry.token,
+            expiry: entry.expiry,
+            created_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
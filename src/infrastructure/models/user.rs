@@ -3,9 +3,15 @@ use diesel::prelude::*;
 use uuid::Uuid;
 use chrono::NaiveDateTime;
 
+use crate::domain::error::RepositoryError;
 use crate::domain::models::user::{User, CreateUserHashed, UpdateUserHashed, Role};
+use crate::infrastructure::error::DieselRepositoryError;
 use crate::infrastructure::schema::users;
 
+fn decode_role(role: i32) -> Result<Role, RepositoryError> {
+    Role::try_from(role).map_err(|v| DieselRepositoryError::from(v).into_inner())
+}
+
 #[derive(Queryable)]
 pub struct UserDiesel {
     pub id: Uuid,
@@ -17,13 +23,20 @@ pub struct UserDiesel {
     pub reset_token_expiry: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: Option<NaiveDateTime>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub email_verification_sent_at: Option<NaiveDateTime>,
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<NaiveDateTime>,
+    pub totp_last_used_step: Option<i64>,
 }
 
-impl From<UserDiesel> for User {
-    fn from(user: UserDiesel) -> Self {
-        User {
+impl TryFrom<UserDiesel> for User {
+    type Error = RepositoryError;
+
+    fn try_from(user: UserDiesel) -> Result<Self, Self::Error> {
+        Ok(User {
             id: user.id,
-            role: user.role.into(),
+            role: decode_role(user.role)?,
             name: user.name,
             email: user.email,
             password_hash: user.password_hash,
@@ -31,7 +44,12 @@ impl From<UserDiesel> for User {
             reset_token_expiry: user.reset_token_expiry,
             created_at: user.created_at,
             updated_at: user.updated_at,
-        }
+            email_verified_at: user.email_verified_at,
+            email_verification_sent_at: user.email_verification_sent_at,
+            totp_secret: user.totp_secret,
+            totp_enabled_at: user.totp_enabled_at,
+            totp_last_used_step: user.totp_last_used_step,
+        })
     }
 }
 
@@ -47,6 +65,11 @@ impl From<User> for UserDiesel {
             reset_token_expiry: user.reset_token_expiry,
             created_at: user.created_at,
             updated_at: user.updated_at,
+            email_verified_at: user.email_verified_at,
+            email_verification_sent_at: user.email_verification_sent_at,
+            totp_secret: user.totp_secret,
+            totp_enabled_at: user.totp_enabled_at,
+            totp_last_used_step: user.totp_last_used_step,
         }
     }
 }
@@ -65,16 +88,18 @@ pub struct CreateUserDiesel {
     pub updated_at: Option<NaiveDateTime>,
 }
 
-impl From<CreateUserDiesel> for CreateUserHashed {
-    fn from(user: CreateUserDiesel) -> Self {
-        CreateUserHashed {
-            role: Some(user.role.into()), 
+impl TryFrom<CreateUserDiesel> for CreateUserHashed {
+    type Error = RepositoryError;
+
+    fn try_from(user: CreateUserDiesel) -> Result<Self, Self::Error> {
+        Ok(CreateUserHashed {
+            role: Some(decode_role(user.role)?), 
             name: user.name,
             email: user.email,
             password_hash: user.password_hash,
             reset_token: user.reset_token,
             reset_token_expiry: user.reset_token_expiry,
-        }
+        })
     }
 }
 
@@ -94,11 +119,13 @@ impl From<CreateUserHashed> for CreateUserDiesel {
     }
 }
 
-impl From<CreateUserDiesel> for User {
-    fn from(user: CreateUserDiesel) -> Self {
-        User {
+impl TryFrom<CreateUserDiesel> for User {
+    type Error = RepositoryError;
+
+    fn try_from(user: CreateUserDiesel) -> Result<Self, Self::Error> {
+        Ok(User {
             id: user.id,
-            role: user.role.into(),
+            role: decode_role(user.role)?,
             name: user.name,
             email: user.email,
             password_hash: user.password_hash,
@@ -106,7 +133,12 @@ impl From<CreateUserDiesel> for User {
             reset_token_expiry: None,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: None,
-        }
+            email_verified_at: None,
+            email_verification_sent_at: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_used_step: None,
+        })
     }
 }
 
@@ -119,19 +151,23 @@ pub struct UpdateUserDiesel {
     pub password_hash: Option<String>,
     pub reset_token: Option<String>,
     pub reset_token_expiry: Option<NaiveDateTime>,
+    pub email_verified_at: Option<Option<NaiveDateTime>>,
     pub updated_at: Option<NaiveDateTime>,
 }
 
-impl From<UpdateUserDiesel> for UpdateUserHashed {
-    fn from(user: UpdateUserDiesel) -> Self {
-        UpdateUserHashed {
-            role: user.role.map(|role| role.into()),
+impl TryFrom<UpdateUserDiesel> for UpdateUserHashed {
+    type Error = RepositoryError;
+
+    fn try_from(user: UpdateUserDiesel) -> Result<Self, Self::Error> {
+        Ok(UpdateUserHashed {
+            role: user.role.map(decode_role).transpose()?,
             name: user.name,
             email: user.email,
             password_hash: user.password_hash,
             reset_token: user.reset_token,
             reset_token_expiry: user.reset_token_expiry,
-        }
+            email_verified_at: user.email_verified_at,
+        })
     }
 }
 
@@ -144,6 +180,7 @@ impl From<UpdateUserHashed> for UpdateUserDiesel {
             password_hash: user.password_hash,
             reset_token: user.reset_token,
             reset_token_expiry: user.reset_token_expiry,
+            email_verified_at: user.email_verified_at,
             updated_at: Some(chrono::Utc::now().naive_utc()),
         }
     }
@@ -161,6 +198,11 @@ impl From<UpdateUserDiesel> for User {
             reset_token_expiry: None,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: Some(chrono::Utc::now().naive_utc()),
+            email_verified_at: user.email_verified_at.flatten(),
+            email_verification_sent_at: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_used_step: None,
         }
     }
 }
@@ -1,14 +1,14 @@
 use std::sync::Arc;
-use actix_threadpool::run;
 use async_trait::async_trait;
-use diesel::prelude::*;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
-use crate::domain::models::admin_permission::{AdminPermission, CreateAdminPermission, UpdateAdminPermission};
+use crate::domain::models::admin_permission::{AdminPermission, AdminPermissions, CreateAdminPermission, UpdateAdminPermission};
 use crate::domain::repositories::repository::{QueryParams, RepositoryResult, ResultPaging};
 use crate::domain::repositories::admin_permission::{AdminPermissionQueryParams, AdminPermissionRepository};
 use crate::infrastructure::error::DieselRepositoryError;
-use crate::infrastructure::database::postgresql::DBConn;
+use crate::infrastructure::database::postgresql::{connection, DBConn};
 use crate::infrastructure::models::admin_permission::{AdminPermissionDiesel, CreateAdminPermissionDiesel, UpdateAdminPermissionDiesel};
 
 pub struct AdminPermissionRepositoryImpl {
@@ -26,68 +26,81 @@ impl AdminPermissionRepository for AdminPermissionRepositoryImpl {
     async fn create(&self, new_admin_permission: &CreateAdminPermission) -> RepositoryResult<AdminPermission> {
         use crate::infrastructure::schema::admin_permissions::dsl::admin_permissions;
         let new_admin_permission_diesel = CreateAdminPermissionDiesel::from(new_admin_permission.clone());
-        let conn = self.pool.clone();
-        let result = run(move || {
-            let mut conn = conn.get().unwrap();
-            diesel::insert_into(admin_permissions)
-                .values(&new_admin_permission_diesel)
-                .get_result::<AdminPermissionDiesel>(&mut conn)
-        })
+        let mut conn = connection(&self.pool).await?;
+        let result = diesel::insert_into(admin_permissions)
+            .values(&new_admin_permission_diesel)
+            .get_result::<AdminPermissionDiesel>(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
-            .map(AdminPermission::from)?;
+            .and_then(AdminPermission::try_from)?;
         Ok(result)
     }
 
     async fn list(&self, query_params: AdminPermissionQueryParams) -> RepositoryResult<ResultPaging<AdminPermission>> {
-        use crate::infrastructure::schema::admin_permissions::dsl::admin_permissions;
-        let pool = self.pool.clone();
-        let builder = admin_permissions.limit(query_params.limit()).offset(query_params.offset());
-        let result = run(move || {
-            let mut conn = pool.get().unwrap();
-            builder.load::<AdminPermissionDiesel>(&mut conn)
-        })
+        use crate::infrastructure::schema::admin_permissions::dsl::{admin_permissions, id, created_at};
+        let (limit, offset) = (query_params.limit(), query_params.offset());
+        let mut conn = connection(&self.pool).await?;
+        let total = admin_permissions.count()
+            .get_result::<i64>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        // Without an order, Postgres may return rows in a different order for
+        // each page, repeating some and skipping others.
+        let result = admin_permissions.order((created_at.asc(), id.asc()))
+            .limit(limit)
+            .offset(offset)
+            .load::<AdminPermissionDiesel>(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
         Ok(ResultPaging {
-            total: result.len() as i64,
-            items: result.into_iter().map(AdminPermission::from).collect(),
+            total,
+            limit,
+            offset,
+            items: result.into_iter().map(AdminPermission::try_from).collect::<RepositoryResult<_>>()?,
         })
     }
 
     async fn get(&self, admin_permission_id: Uuid) -> RepositoryResult<AdminPermission> {
         use crate::infrastructure::schema::admin_permissions::dsl::{admin_permissions, id};
-        let mut conn = self.pool.get().unwrap();
-        run(move || admin_permissions.filter(id.eq(admin_permission_id)).first::<AdminPermissionDiesel>(&mut conn))
+        let mut conn = connection(&self.pool).await?;
+        admin_permissions.filter(id.eq(admin_permission_id))
+            .first::<AdminPermissionDiesel>(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
-            .map(|v| -> AdminPermission { AdminPermission::from(v) })
+            .and_then(AdminPermission::try_from)
     }
 
     async fn update(&self, admin_permission_id: Uuid, updated_admin_permission: &UpdateAdminPermission) -> RepositoryResult<AdminPermission> {
         use crate::infrastructure::schema::admin_permissions::dsl::{admin_permissions, id};
         let updated_admin_permission_diesel = UpdateAdminPermissionDiesel::from(updated_admin_permission.clone());
-        let pool = self.pool.clone();
-        run(move || {
-            let mut conn = pool.get().unwrap();
-            diesel::update(admin_permissions.filter(id.eq(admin_permission_id)))
-                .set(updated_admin_permission_diesel)
-                .get_result::<AdminPermissionDiesel>(&mut conn)
-        })
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(admin_permissions.filter(id.eq(admin_permission_id)))
+            .set(updated_admin_permission_diesel)
+            .get_result::<AdminPermissionDiesel>(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
-            .map(|v| -> AdminPermission { AdminPermission::from(v) })
+            .and_then(AdminPermission::try_from)
     }
 
     async fn delete(&self, admin_permission_id: Uuid) -> RepositoryResult<bool> {
         use crate::infrastructure::schema::admin_permissions::dsl::{admin_permissions, id};
-        let pool = self.pool.clone();
-        run(move || {
-            let mut conn = pool.get().unwrap();
-            diesel::delete(admin_permissions.filter(id.eq(admin_permission_id))).execute(&mut conn)
-        })
+        let mut conn = connection(&self.pool).await?;
+        diesel::delete(admin_permissions.filter(id.eq(admin_permission_id)))
+            .execute(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
             .map(|v| v > 0)
     }
-}
\ No newline at end of file
+    async fn has_permission(&self, permission_user_id: Uuid, required_permission: AdminPermissions) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::admin_permissions::dsl::{admin_permissions, user_id, permission};
+        let mut conn = connection(&self.pool).await?;
+        diesel::select(diesel::dsl::exists(
+            admin_permissions
+                .filter(user_id.eq(permission_user_id))
+                .filter(permission.eq(required_permission as i32))
+        ))
+            .get_result::<bool>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+    }
+}
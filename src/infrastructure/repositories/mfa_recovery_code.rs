@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::domain::repositories::mfa_recovery_code::MfaRecoveryCodeRepository;
+use crate::domain::repositories::repository::RepositoryResult;
+use crate::infrastructure::error::DieselRepositoryError;
+use crate::infrastructure::database::postgresql::{connection, DBConn};
+use crate::infrastructure::models::mfa_recovery_code::CreateMfaRecoveryCodeDiesel;
+
+pub struct MfaRecoveryCodeRepositoryImpl {
+    pool: Arc<DBConn>,
+}
+
+impl MfaRecoveryCodeRepositoryImpl {
+    pub fn new(db: Arc<DBConn>) -> Self {
+        Self { pool: db }
+    }
+}
+
+#[async_trait]
+impl MfaRecoveryCodeRepository for MfaRecoveryCodeRepositoryImpl {
+    async fn replace(&self, owner_id: Uuid, codes: &[String]) -> RepositoryResult<()> {
+        use crate::infrastructure::schema::mfa_recovery_codes::dsl::{mfa_recovery_codes, user_id};
+        let new_codes: Vec<CreateMfaRecoveryCodeDiesel> = codes.iter()
+            .map(|code| CreateMfaRecoveryCodeDiesel::new(owner_id, code.clone()))
+            .collect();
+        let mut conn = connection(&self.pool).await?;
+        // In one transaction, so the old codes never stop working before the
+        // new ones are stored.
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            diesel::delete(mfa_recovery_codes.filter(user_id.eq(owner_id)))
+                .execute(conn)
+                .await?;
+            diesel::insert_into(mfa_recovery_codes)
+                .values(&new_codes)
+                .execute(conn)
+                .await?;
+            Ok(())
+        }.scope_boxed())
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+    }
+
+    async fn consume(&self, owner_id: Uuid, recovery_code: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::mfa_recovery_codes::dsl::{mfa_recovery_codes, user_id, code};
+        let mut conn = connection(&self.pool).await?;
+        diesel::delete(mfa_recovery_codes.filter(user_id.eq(owner_id)).filter(code.eq(recovery_code)))
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .map(|v| v > 0)
+    }
+
+    async fn delete_by_user(&self, owner_id: Uuid) -> RepositoryResult<usize> {
+        use crate::infrastructure::schema::mfa_recovery_codes::dsl::{mfa_recovery_codes, user_id};
+        let mut conn = connection(&self.pool).await?;
+        diesel::delete(mfa_recovery_codes.filter(user_id.eq(owner_id)))
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+    }
+}
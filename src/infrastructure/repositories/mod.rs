@@ -1,2 +1,5 @@
 pub mod admin_permission;
+pub mod mfa_recovery_code;
+pub mod session;
+pub mod token_blacklist;
 pub mod user;
\ No newline at end of file
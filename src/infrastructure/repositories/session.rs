@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::domain::models::session::{Session, CreateSession};
+use crate::domain::repositories::repository::{QueryParams, RepositoryResult, ResultPaging};
+use crate::domain::repositories::session::{SessionQueryParams, SessionRepository};
+use crate::infrastructure::error::DieselRepositoryError;
+use crate::infrastructure::database::postgresql::{connection, DBConn};
+use crate::infrastructure::models::session::{SessionDiesel, CreateSessionDiesel};
+
+pub struct SessionRepositoryImpl {
+    pool: Arc<DBConn>,
+}
+
+impl SessionRepositoryImpl {
+    pub fn new(db: Arc<DBConn>) -> Self {
+        Self { pool: db }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SessionRepositoryImpl {
+    async fn create(&self, new_session: &CreateSession) -> RepositoryResult<Session> {
+        use crate::infrastructure::schema::sessions::dsl::sessions;
+        let new_session_diesel = CreateSessionDiesel::from(new_session.clone());
+        let mut conn = connection(&self.pool).await?;
+        diesel::insert_into(sessions)
+            .values(&new_session_diesel)
+            .get_result::<SessionDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .map(Session::from)
+    }
+
+    async fn list_by_user(&self, session_user_id: Uuid, params: SessionQueryParams) -> RepositoryResult<ResultPaging<Session>> {
+        use crate::infrastructure::schema::sessions::dsl::{sessions, id, user_id, created_at};
+        let (limit, offset) = (params.limit(), params.offset());
+        let mut conn = connection(&self.pool).await?;
+        let total = sessions.filter(user_id.eq(session_user_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        let result = sessions
+            .filter(user_id.eq(session_user_id))
+            .order((created_at.desc(), id.desc()))
+            .limit(limit)
+            .offset(offset)
+            .load::<SessionDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        Ok(ResultPaging {
+            total,
+            limit,
+            offset,
+            items: result.into_iter().map(Session::from).collect(),
+        })
+    }
+
+    async fn get_by_token(&self, session_token: &str) -> RepositoryResult<Session> {
+        use crate::infrastructure::schema::sessions::dsl::{sessions, token};
+        let mut conn = connection(&self.pool).await?;
+        sessions.filter(token.eq(session_token))
+            .first::<SessionDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .map(Session::from)
+    }
+
+    async fn delete(&self, session_id: Uuid) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::sessions::dsl::{sessions, id};
+        let mut conn = connection(&self.pool).await?;
+        diesel::delete(sessions.filter(id.eq(session_id)))
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .map(|v| v > 0)
+    }
+    async fn delete_by_user(&self, session_user_id: Uuid) -> RepositoryResult<Vec<Session>> {
+        use crate::infrastructure::schema::sessions::dsl::{sessions, user_id};
+        let mut conn = connection(&self.pool).await?;
+        let result = diesel::delete(sessions.filter(user_id.eq(session_user_id)))
+            .get_results::<SessionDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        Ok(result.into_iter().map(Session::from).collect())
+    }
+
+    async fn delete_expired(&self) -> RepositoryResult<usize> {
+        use crate::infrastructure::schema::sessions::dsl::{sessions, expiry};
+        let mut conn = connection(&self.pool).await?;
+        diesel::delete(sessions.filter(expiry.le(diesel::dsl::now)))
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+    }
+}
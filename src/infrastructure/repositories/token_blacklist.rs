@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+use crate::domain::models::session::CreateTokenBlacklist;
+use crate::domain::repositories::repository::RepositoryResult;
+use crate::domain::repositories::token_blacklist::TokenBlacklistRepository;
+use crate::infrastructure::error::DieselRepositoryError;
+use crate::infrastructure::database::postgresql::{connection, DBConn};
+use crate::infrastructure::models::token_blacklist::CreateTokenBlacklistDiesel;
+
+pub struct TokenBlacklistRepositoryImpl {
+    pool: Arc<DBConn>,
+}
+
+impl TokenBlacklistRepositoryImpl {
+    pub fn new(db: Arc<DBConn>) -> Self {
+        Self { pool: db }
+    }
+}
+
+#[async_trait]
+impl TokenBlacklistRepository for TokenBlacklistRepositoryImpl {
+    async fn create(&self, new_entry: &CreateTokenBlacklist) -> RepositoryResult<()> {
+        use crate::infrastructure::schema::token_blacklist::dsl::token_blacklist;
+        let new_entry_diesel = CreateTokenBlacklistDiesel::from(new_entry.clone());
+        let mut conn = connection(&self.pool).await?;
+        // Revoking an already revoked token is not an error.
+        diesel::insert_into(token_blacklist)
+            .values(&new_entry_diesel)
+            .on_conflict_do_nothing()
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .map(|_| ())
+    }
+
+    async fn is_blacklisted(&self, blacklisted_token: &str) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::token_blacklist::dsl::{token_blacklist, token};
+        let mut conn = connection(&self.pool).await?;
+        diesel::select(diesel::dsl::exists(token_blacklist.filter(token.eq(blacklisted_token))))
+            .get_result::<bool>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+    }
+
+    async fn delete_expired(&self) -> RepositoryResult<usize> {
+        use crate::infrastructure::schema::token_blacklist::dsl::{token_blacklist, expiry};
+        let mut conn = connection(&self.pool).await?;
+        diesel::delete(token_blacklist.filter(expiry.le(diesel::dsl::now)))
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+    }
+}
@@ -1,16 +1,21 @@
 use std::sync::Arc;
-use actix_threadpool::run;
 use async_trait::async_trait;
-use diesel::prelude::*;
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
 use uuid::Uuid;
 
 use crate::domain::models::user::{User, CreateUserHashed, UpdateUserHashed};
 use crate::domain::repositories::repository::{QueryParams, RepositoryResult, ResultPaging};
 use crate::domain::repositories::user::{UserQueryParams, UserRepository};
 use crate::infrastructure::error::DieselRepositoryError;
-use crate::infrastructure::database::postgresql::DBConn;
+use crate::infrastructure::database::postgresql::{connection, DBConn};
 use crate::infrastructure::models::user::{UserDiesel, CreateUserDiesel, UpdateUserDiesel};
 
+diesel::sql_function! {
+    /// Postgres `lower()`, matching the expression `users_email_lower_key` indexes.
+    fn lower(value: diesel::sql_types::Text) -> diesel::sql_types::Text;
+}
+
 pub struct UserRepositoryImpl {
     pool: Arc<DBConn>,
 }
@@ -26,68 +31,211 @@ impl UserRepository for UserRepositoryImpl {
     async fn create(&self, new_user: &CreateUserHashed) -> RepositoryResult<User> {
         use crate::infrastructure::schema::users::dsl::users;
         let new_user_diesel = CreateUserDiesel::from(new_user.clone());
-        let conn = self.pool.clone();
-        let result = run(move || {
-            let mut conn = conn.get().unwrap();
-            diesel::insert_into(users)
-                .values(&new_user_diesel)
-                .get_result::<UserDiesel>(&mut conn)
-        })
+        let mut conn = connection(&self.pool).await?;
+        let result = diesel::insert_into(users)
+            .values(&new_user_diesel)
+            .get_result::<UserDiesel>(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
-            .map(User::from)?;
+            .and_then(User::try_from)?;
         Ok(result)
     }
 
-    async fn list(&self, params: UserQueryParams) -> RepositoryResult<ResultPaging<User>> {
+    async fn create_many(&self, new_users: &[CreateUserHashed]) -> RepositoryResult<Vec<User>> {
         use crate::infrastructure::schema::users::dsl::users;
-        let pool = self.pool.clone();
-        let builder = users.limit(params.limit()).offset(params.offset());
-        let result = run(move || {
-            let mut conn = pool.get().unwrap();
-            builder.load::<UserDiesel>(&mut conn)
-        })
+        let new_users_diesel: Vec<CreateUserDiesel> = new_users.iter().cloned().map(CreateUserDiesel::from).collect();
+        let mut conn = connection(&self.pool).await?;
+        // A single statement, so a conflict on any row rolls back the whole batch.
+        let result = diesel::insert_into(users)
+            .values(&new_users_diesel)
+            .get_results::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        result.into_iter().map(User::try_from).collect()
+    }
+
+    async fn list(&self, params: UserQueryParams) -> RepositoryResult<ResultPaging<User>> {
+        use crate::infrastructure::schema::users::dsl::{users, id, created_at};
+        let (limit, offset) = (params.limit(), params.offset());
+        let mut conn = connection(&self.pool).await?;
+        let total = users.count()
+            .get_result::<i64>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
+        // Without an order, Postgres may return rows in a different order for
+        // each page, repeating some and skipping others.
+        let result = users.order((created_at.asc(), id.asc()))
+            .limit(limit)
+            .offset(offset)
+            .load::<UserDiesel>(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())?;
         Ok(ResultPaging {
-            total: result.len() as i64,
-            items: result.into_iter().map(User::from).collect(),
+            total,
+            limit,
+            offset,
+            items: result.into_iter().map(User::try_from).collect::<RepositoryResult<_>>()?,
         })
     }
 
     async fn get(&self, user_id: Uuid) -> RepositoryResult<User> {
         use crate::infrastructure::schema::users::dsl::{users, id};
-        let mut conn = self.pool.get().unwrap();
-        run(move || users.filter(id.eq(user_id)).first::<UserDiesel>(&mut conn))
+        let mut conn = connection(&self.pool).await?;
+        users.filter(id.eq(user_id))
+            .first::<UserDiesel>(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
-            .map(|v| -> User { User::from(v) })
+            .and_then(User::try_from)
+    }
+
+    async fn get_by_email(&self, user_email: &str) -> RepositoryResult<User> {
+        use crate::infrastructure::schema::users::dsl::{users, email};
+        let mut conn = connection(&self.pool).await?;
+        users.filter(lower(email).eq(lower(user_email)))
+            .first::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
+    }
+
+    async fn get_by_reset_token(&self, user_reset_token: &str) -> RepositoryResult<User> {
+        use crate::infrastructure::schema::users::dsl::{users, reset_token};
+        let mut conn = connection(&self.pool).await?;
+        users.filter(reset_token.eq(user_reset_token))
+            .first::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
     }
 
     async fn update(&self, user_id: Uuid, updated_user: &UpdateUserHashed) -> RepositoryResult<User> {
         use crate::infrastructure::schema::users::dsl::{users, id};
         let updated_user_diesel = UpdateUserDiesel::from(updated_user.clone());
-        let pool = self.pool.clone();
-        run(move || {
-            let mut conn = pool.get().unwrap();
-            diesel::update(users.filter(id.eq(user_id)))
-                .set(updated_user_diesel)
-                .get_result::<UserDiesel>(&mut conn)
-        })
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(users.filter(id.eq(user_id)))
+            .set(updated_user_diesel)
+            .get_result::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
+    }
+
+    async fn reset_password(&self, user_id: Uuid, user_reset_token: &str, new_password_hash: &str) -> RepositoryResult<User> {
+        use crate::infrastructure::schema::users::dsl::{users, id, password_hash, reset_token, reset_token_expiry, updated_at};
+        let mut conn = connection(&self.pool).await?;
+        // Matching on the token as well makes the update fail if the token was
+        // already used, so it can only be redeemed once.
+        diesel::update(users.filter(id.eq(user_id)).filter(reset_token.eq(user_reset_token)))
+            .set((
+                password_hash.eq(new_password_hash),
+                reset_token.eq(None::<String>),
+                reset_token_expiry.eq(None::<chrono::NaiveDateTime>),
+                updated_at.eq(Some(chrono::Utc::now().naive_utc())),
+            ))
+            .get_result::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
+    }
+
+    async fn mark_verification_sent(&self, user_id: Uuid, sent_before: chrono::NaiveDateTime) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::users::dsl::{users, id, email_verified_at, email_verification_sent_at};
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(
+            users.filter(id.eq(user_id))
+                .filter(email_verified_at.is_null())
+                .filter(email_verification_sent_at.is_null().or(email_verification_sent_at.le(sent_before)))
+        )
+            .set(email_verification_sent_at.eq(Some(chrono::Utc::now().naive_utc())))
+            .execute(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
-            .map(|v| -> User { User::from(v) })
+            .map(|v| v > 0)
+    }
+
+    async fn restore_verification_sent(&self, user_id: Uuid, sent_at: Option<chrono::NaiveDateTime>) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::users::dsl::{users, id, email_verification_sent_at};
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(users.filter(id.eq(user_id)))
+            .set(email_verification_sent_at.eq(sent_at))
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .map(|v| v > 0)
+    }
+
+    async fn mark_email_verified(&self, user_id: Uuid, user_email: &str) -> RepositoryResult<User> {
+        use crate::infrastructure::schema::users::dsl::{users, id, email, email_verified_at};
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(users.filter(id.eq(user_id)).filter(lower(email).eq(lower(user_email))))
+            .set(email_verified_at.eq(Some(chrono::Utc::now().naive_utc())))
+            .get_result::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
+    }
+
+    async fn start_totp_enrollment(&self, user_id: Uuid, secret: &str) -> RepositoryResult<User> {
+        use crate::infrastructure::schema::users::dsl::{users, id, totp_secret, totp_enabled_at};
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(users.filter(id.eq(user_id)).filter(totp_enabled_at.is_null()))
+            .set(totp_secret.eq(Some(secret)))
+            .get_result::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
+    }
+
+    async fn enable_totp(&self, user_id: Uuid, secret: &str, step: i64) -> RepositoryResult<User> {
+        use crate::infrastructure::schema::users::dsl::{users, id, totp_secret, totp_enabled_at, totp_last_used_step};
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(users.filter(id.eq(user_id)).filter(totp_secret.eq(secret)).filter(totp_enabled_at.is_null()))
+            .set((
+                totp_enabled_at.eq(Some(chrono::Utc::now().naive_utc())),
+                totp_last_used_step.eq(Some(step)),
+            ))
+            .get_result::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
+    }
+
+    async fn disable_totp(&self, user_id: Uuid) -> RepositoryResult<User> {
+        use crate::infrastructure::schema::users::dsl::{users, id, totp_secret, totp_enabled_at, totp_last_used_step};
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(users.filter(id.eq(user_id)))
+            .set((
+                totp_secret.eq(None::<String>),
+                totp_enabled_at.eq(None::<chrono::NaiveDateTime>),
+                totp_last_used_step.eq(None::<i64>),
+            ))
+            .get_result::<UserDiesel>(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .and_then(User::try_from)
+    }
+
+    async fn mark_totp_used(&self, user_id: Uuid, step: i64) -> RepositoryResult<bool> {
+        use crate::infrastructure::schema::users::dsl::{users, id, totp_last_used_step};
+        let mut conn = connection(&self.pool).await?;
+        diesel::update(
+            users.filter(id.eq(user_id))
+                .filter(totp_last_used_step.is_null().or(totp_last_used_step.lt(step)))
+        )
+            .set(totp_last_used_step.eq(Some(step)))
+            .execute(&mut conn)
+            .await
+            .map_err(|v| DieselRepositoryError::from(v).into_inner())
+            .map(|v| v > 0)
     }
 
     async fn delete(&self, user_id: Uuid) -> RepositoryResult<bool> {
         use crate::infrastructure::schema::users::dsl::{users, id};
-        let pool = self.pool.clone();
-        run(move || {
-            let mut conn = pool.get().unwrap();
-            diesel::delete(users.filter(id.eq(user_id))).execute(&mut conn)
-        })
+        let mut conn = connection(&self.pool).await?;
+        diesel::delete(users.filter(id.eq(user_id)))
+            .execute(&mut conn)
             .await
             .map_err(|v| DieselRepositoryError::from(v).into_inner())
             .map(|v| v > 0)
     }
-}
\ No newline at end of file
+}
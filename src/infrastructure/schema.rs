@@ -10,6 +10,43 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mfa_recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 255]
+        code -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 255]
+        token -> Varchar,
+        expiry -> Timestamptz,
+        #[max_length = 255]
+        user_agent -> Nullable<Varchar>,
+        #[max_length = 64]
+        ip_address -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    token_blacklist (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 255]
+        token -> Varchar,
+        expiry -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Uuid,
@@ -25,12 +62,24 @@ diesel::table! {
         reset_token_expiry -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
+        email_verified_at -> Nullable<Timestamptz>,
+        email_verification_sent_at -> Nullable<Timestamptz>,
+        #[max_length = 255]
+        totp_secret -> Nullable<Varchar>,
+        totp_enabled_at -> Nullable<Timestamptz>,
+        totp_last_used_step -> Nullable<Int8>,
     }
 }
 
 diesel::joinable!(admin_permissions -> users (user_id));
+diesel::joinable!(mfa_recovery_codes -> users (user_id));
+diesel::joinable!(sessions -> users (user_id));
+diesel::joinable!(token_blacklist -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     admin_permissions,
+    mfa_recovery_codes,
+    sessions,
+    token_blacklist,
     users,
 );
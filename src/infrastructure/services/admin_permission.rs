@@ -3,11 +3,14 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use uuid::Uuid;
 
-use crate::domain::error::CommonError;
-use crate::domain::models::admin_permission::{AdminPermission, CreateAdminPermission, UpdateAdminPermission};
+use crate::domain::constants as domain_constants;
+use crate::domain::error::{CommonError, RepositoryError};
+use crate::domain::models::common::ErrorResponseType;
+use crate::domain::models::admin_permission::{AdminPermission, AdminPermissions, CreateAdminPermission, UpdateAdminPermission};
 use crate::domain::repositories::repository::ResultPaging;
 use crate::domain::repositories::admin_permission::{AdminPermissionQueryParams, AdminPermissionRepository};
 use crate::domain::services::admin_permission::AdminPermissionService;
+use crate::services::constants;
 
 #[derive(Clone)]
 pub struct AdminPermissionServiceImpl {
@@ -18,6 +21,19 @@ impl AdminPermissionServiceImpl {
     pub fn new(repository: Arc<dyn AdminPermissionRepository>) -> Self {
         Self { repository }
     }
+
+    /// Turns storage errors the caller can act on into meaningful messages.
+    fn repository_error(&self, error: RepositoryError) -> CommonError {
+        if error.kind.is_foreign_key_violation_of(domain_constants::DB_CONSTRAINT_ADMIN_PERMISSIONS_USER_ID) {
+            return CommonError::new(
+                constants::ADMIN_PERMISSION_ERR_USER_NOT_FOUND,
+                "`user_id` does not refer to an existing user".to_string(),
+                constants::ERR_CONTEXT_ADMIN_PERMISSION_SERV.to_string(),
+                ErrorResponseType::Validation
+            );
+        }
+        CommonError::from(error)
+    }
 }
 
 #[async_trait]
@@ -26,7 +42,7 @@ impl AdminPermissionService for AdminPermissionServiceImpl {
         let cloned = new_admin_permission.clone();
         self.repository.create(&cloned)
             .await
-            .map_err(CommonError::from)
+            .map_err(|err| self.repository_error(err))
     }
 
     async fn list(&self, params: AdminPermissionQueryParams) -> Result<ResultPaging<AdminPermission>, CommonError> {
@@ -45,7 +61,7 @@ impl AdminPermissionService for AdminPermissionServiceImpl {
         let cloned = updated_admin_permission.clone();
         self.repository.update(admin_permission_id, &cloned)
             .await
-            .map_err(CommonError::from)
+            .map_err(|err| self.repository_error(err))
     }
 
     async fn delete(&self, admin_permission_id: Uuid) -> Result<bool, CommonError> {
@@ -53,4 +69,9 @@ impl AdminPermissionService for AdminPermissionServiceImpl {
             .await
             .map_err(CommonError::from)
     }
+    async fn has_permission(&self, user_id: Uuid, permission: AdminPermissions) -> Result<bool, CommonError> {
+        self.repository.has_permission(user_id, permission)
+            .await
+            .map_err(CommonError::from)
+    }
 }
\ No newline at end of file
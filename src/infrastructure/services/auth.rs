@@ -0,0 +1,502 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::domain::error::{CommonError, RepositoryError, RepositoryErrorKind};
+use crate::domain::models::auth::{
+    AuthForgotPassword,
+    AuthLogin,
+    AuthLoginOutcome,
+    AuthMfaChallenge,
+    AuthMfaVerify,
+    AuthRefresh,
+    AuthRegister,
+    AuthResetPassword,
+    AuthSuccessfulResponse,
+    AuthenticatedUser,
+    TokenClaims,
+    VerifiedAction
+};
+use crate::domain::models::session::{CreateSession, CreateTokenBlacklist, SessionClient};
+use crate::domain::models::user::{CreateUserPlainText, Role, UpdateUserHashed};
+use crate::domain::repositories::session::SessionRepository;
+use crate::domain::repositories::token_blacklist::TokenBlacklistRepository;
+use crate::domain::repositories::user::UserRepository;
+use crate::domain::services::auth::AuthService;
+use crate::domain::services::email_verification::EmailVerificationService;
+use crate::domain::services::mfa::MfaService;
+use crate::domain::services::user::UserService;
+use crate::services::constants;
+use crate::services::error::{AuthError, SecurityError};
+use crate::services::traits::mailer::{MailMessage, MailerService};
+use crate::services::traits::password_hash::PasswordHashService;
+use crate::services::traits::password_policy::{PasswordOwner, PasswordPolicyService};
+use crate::services::traits::token::TokenService;
+use crate::services::utils::{digest, random};
+use crate::services::utils::email::normalize_email;
+use crate::services::utils::envutil::get_env_var_as_type_or_default;
+
+#[derive(Clone)]
+pub struct AuthServiceImpl {
+    pub user_repository: Arc<dyn UserRepository>,
+    pub session_repository: Arc<dyn SessionRepository>,
+    pub token_blacklist_repository: Arc<dyn TokenBlacklistRepository>,
+    user_service: Arc<dyn UserService>,
+    email_verification: Arc<dyn EmailVerificationService>,
+    mfa_service: Arc<dyn MfaService>,
+    hash_service: Arc<dyn PasswordHashService>,
+    password_policy: Arc<dyn PasswordPolicyService>,
+    token_service: Arc<dyn TokenService>,
+    mailer_service: Arc<dyn MailerService>,
+    password_reset_token_lifetime_secs: i64,
+    dummy_password_hash: Arc<OnceCell<String>>,
+}
+
+impl AuthServiceImpl {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        session_repository: Arc<dyn SessionRepository>,
+        token_blacklist_repository: Arc<dyn TokenBlacklistRepository>,
+        user_service: Arc<dyn UserService>,
+        email_verification: Arc<dyn EmailVerificationService>,
+        mfa_service: Arc<dyn MfaService>,
+        hash_service: Arc<dyn PasswordHashService>,
+        password_policy: Arc<dyn PasswordPolicyService>,
+        token_service: Arc<dyn TokenService>,
+        mailer_service: Arc<dyn MailerService>
+    ) -> Self {
+        Self {
+            user_repository,
+            session_repository,
+            token_blacklist_repository,
+            user_service,
+            email_verification,
+            mfa_service,
+            hash_service,
+            password_policy,
+            token_service,
+            mailer_service,
+            password_reset_token_lifetime_secs: get_env_var_as_type_or_default(
+                constants::SEC_PASSWORD_RESET_ENV_TOKEN_LIFETIME_SECS,
+                &constants::SEC_PASSWORD_RESET_TOKEN_LIFETIME_SECS_DEFAULT
+            ),
+            dummy_password_hash: Arc::new(OnceCell::new()),
+        }
+    }
+
+    fn invalid_credentials_error(&self) -> CommonError {
+        CommonError::from(AuthError {
+            identifier: constants::SEC_ERR_INVALID_CREDENTIALS.to_string(),
+            message: "Invalid email or password".to_string(),
+            context: constants::ERR_CONTEXT_LOGIN.to_string(),
+        })
+    }
+
+    fn invalid_token_error(&self, error: SecurityError, context: &str) -> CommonError {
+        CommonError::from(AuthError {
+            identifier: error.identifier,
+            message: error.message,
+            context: context.to_string(),
+        })
+    }
+
+    fn invalid_session_error(&self) -> CommonError {
+        CommonError::from(AuthError {
+            identifier: constants::SEC_ERR_SESSION_INVALID.to_string(),
+            message: "Session has ended or does not exist".to_string(),
+            context: constants::ERR_CONTEXT_REFRESH.to_string(),
+        })
+    }
+
+    fn invalid_challenge_error(&self) -> CommonError {
+        CommonError::from(AuthError {
+            identifier: constants::SEC_ERR_MFA_CHALLENGE_INVALID.to_string(),
+            message: "Two-factor challenge is invalid or has expired".to_string(),
+            context: constants::ERR_CONTEXT_MFA.to_string(),
+        })
+    }
+
+    fn revoked_token_error(&self, context: &str) -> CommonError {
+        CommonError::from(AuthError {
+            identifier: constants::SEC_ERR_TOKEN_REVOKED.to_string(),
+            message: "Token has been revoked".to_string(),
+            context: context.to_string(),
+        })
+    }
+
+    fn invalid_reset_token_error(&self) -> CommonError {
+        CommonError::from(SecurityError {
+            identifier: constants::SEC_ERR_RESET_TOKEN_INVALID.to_string(),
+            message: "Reset token is invalid or has expired".to_string(),
+            context: constants::ERR_CONTEXT_PASSWORD_RESET.to_string(),
+        })
+    }
+
+    async fn ensure_not_revoked(&self, claims: &TokenClaims, context: &str) -> Result<(), CommonError> {
+        let is_blacklisted = self.token_blacklist_repository.is_blacklisted(&claims.jti)
+            .await
+            .map_err(CommonError::from)?;
+        if is_blacklisted {
+            return Err(self.revoked_token_error(context));
+        }
+        Ok(())
+    }
+
+    async fn revoke(&self, user_id: Uuid, token_id: &str, expiry: NaiveDateTime) -> Result<(), CommonError> {
+        let entry = CreateTokenBlacklist {
+            user_id,
+            token: token_id.to_string(),
+            expiry,
+        };
+        self.token_blacklist_repository.create(&entry)
+            .await
+            .map_err(CommonError::from)
+    }
+
+    /// Deletes every session of the user and revokes the access tokens that may
+    /// still be in circulation for them.
+    async fn end_all_sessions(&self, user_id: Uuid) -> Result<(), CommonError> {
+        // Access tokens of other sessions are never seen here, but they share the
+        // session id as `jti`, so revoking every session id until the session's
+        // expiry covers them. Sessions replaced by a refresh are gone from the
+        // table, but their ids were revoked when they were replaced.
+        let sessions = self.session_repository.delete_by_user(user_id)
+            .await
+            .map_err(CommonError::from)?;
+        for session in sessions {
+            self.revoke(user_id, &session.id.to_string(), session.expiry).await?;
+        }
+        Ok(())
+    }
+
+    /// Stands in for checking the password of an account that doesn't exist, so
+    /// unknown emails take as long to turn away as wrong passwords. The hash of
+    /// a random password is made with the current settings the first time it's
+    /// needed.
+    async fn verify_dummy_password(&self, password: &str) -> Result<bool, SecurityError> {
+        let dummy_hash = self.dummy_password_hash
+            .get_or_try_init(|| async {
+                self.hash_service.hash_password(&random::random_token(constants::SEC_PASSWORD_RESET_TOKEN_BYTES)).await
+            })
+            .await?;
+        self.hash_service.verify_password(password, dummy_hash).await
+    }
+
+    /// Replaces a hash made with outdated settings. The plain password is only
+    /// known while signing in, so this is the one chance to upgrade it. Failing
+    /// here doesn't fail the login; the old hash still works and the upgrade is
+    /// tried again next time.
+    async fn rehash_password(&self, user_id: Uuid, password: &str) {
+        let password_hash = match self.hash_service.hash_password(password).await {
+            Ok(password_hash) => password_hash,
+            Err(err) => {
+                warn!("Could not rehash password of user {}: {}", user_id, err);
+                return;
+            }
+        };
+        let update = UpdateUserHashed {
+            role: None,
+            name: None,
+            email: None,
+            password_hash: Some(password_hash),
+            reset_token: None,
+            reset_token_expiry: None,
+            email_verified_at: None,
+        };
+        match self.user_repository.update(user_id, &update).await {
+            Ok(_) => info!("Upgraded password hash of user {}", user_id),
+            Err(err) => warn!("Could not store rehashed password of user {}: {}", user_id, err),
+        }
+    }
+
+    /// Signs a new access/refresh token pair and records the refresh token as a
+    /// session. Both tokens share the session id as their `jti`, and `mfa` tells
+    /// whether the user passed a second factor.
+    async fn issue_tokens(&self, user_id: Uuid, client: SessionClient, mfa: bool) -> Result<AuthSuccessfulResponse, CommonError> {
+        let session_id = Uuid::new_v4();
+        let access_token = self.token_service.generate_access_token(user_id, session_id, mfa)
+            .map_err(CommonError::from)?;
+        let refresh_token = self.token_service.generate_refresh_token(user_id, session_id, mfa)
+            .map_err(CommonError::from)?;
+
+        let new_session = CreateSession {
+            id: session_id,
+            user_id,
+            token: digest::sha256_hex(&refresh_token.token),
+            expiry: timestamp_to_naive(refresh_token.claims.exp),
+            user_agent: client.user_agent,
+            ip_address: client.ip_address,
+        };
+        self.session_repository.create(&new_session)
+            .await
+            .map_err(CommonError::from)?;
+
+        Ok(AuthSuccessfulResponse {
+            token: access_token.token,
+            refresh_token: refresh_token.token,
+        })
+    }
+}
+
+#[async_trait]
+impl AuthService for AuthServiceImpl {
+    async fn login(&self, credentials: AuthLogin, client: SessionClient) -> Result<AuthLoginOutcome, CommonError> {
+        // Unknown emails and wrong passwords deliberately produce the same error
+        // after the same amount of hashing, so the endpoint can't be used to find
+        // out which accounts exist.
+
+        // No password this long can have been set, and hashing it would only
+        // waste time.
+        if credentials.password.chars().count() > self.password_policy.max_length() {
+            return Err(self.invalid_credentials_error());
+        }
+        let user = match self.user_repository.get_by_email(&normalize_email(&credentials.email)).await {
+            Ok(user) => Some(user),
+            Err(err) if matches!(err.kind, RepositoryErrorKind::NotFound) => None,
+            Err(err) => return Err(CommonError::from(err)),
+        };
+        let verification = match &user {
+            Some(user) => self.hash_service.verify_password(&credentials.password, &user.password_hash).await,
+            None => self.verify_dummy_password(&credentials.password).await,
+        };
+
+        match (user, verification) {
+            (Some(user), Ok(true)) => {
+                if self.hash_service.needs_rehash(&user.password_hash) {
+                    self.rehash_password(user.id, &credentials.password).await;
+                }
+                // Only checked once the password is known to be right, so it
+                // doesn't tell strangers whether an account exists.
+                self.email_verification.ensure_verified(&user, VerifiedAction::Login)?;
+                if !self.mfa_service.is_enabled(&user) {
+                    return self.issue_tokens(user.id, client, false).await.map(AuthLoginOutcome::Authenticated);
+                }
+
+                let challenge = self.token_service.generate_mfa_challenge_token(user.id)
+                    .map_err(CommonError::from)?;
+                Ok(AuthLoginOutcome::MfaRequired(AuthMfaChallenge {
+                    challenge_token: challenge.token,
+                    expires_at: timestamp_to_naive(challenge.claims.exp),
+                }))
+            },
+            // Shedding load says nothing about the password, so the client is
+            // told to retry rather than that the credentials are wrong.
+            (_, Err(err)) if err.identifier == constants::SEC_ERR_HASHING_OVERLOADED => Err(CommonError::from(err)),
+            _ => Err(self.invalid_credentials_error()),
+        }
+    }
+
+    async fn register(&self, registration: AuthRegister, client: SessionClient) -> Result<Option<AuthSuccessfulResponse>, CommonError> {
+        // Self-service accounts are always plain users; elevated roles can only be
+        // granted through the user management endpoints.
+        let new_user = CreateUserPlainText {
+            role: Some(Role::User),
+            name: registration.name,
+            email: registration.email,
+            password: registration.password,
+            confirm_password: registration.confirm_password,
+            reset_token: None,
+            reset_token_expiry: None,
+        };
+
+        let user = self.user_service.create(new_user, None).await?;
+        if self.email_verification.is_required_for(VerifiedAction::Login) {
+            return Ok(None);
+        }
+        self.issue_tokens(user.id, client, false).await.map(Some)
+    }
+
+    async fn verify_mfa(&self, verification: AuthMfaVerify, client: SessionClient) -> Result<AuthSuccessfulResponse, CommonError> {
+        let claims = self.token_service.validate_mfa_challenge_token(&verification.challenge_token)
+            .map_err(|_| self.invalid_challenge_error())?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| self.invalid_challenge_error())?;
+        self.ensure_not_revoked(&claims, constants::ERR_CONTEXT_MFA)
+            .await
+            .map_err(|_| self.invalid_challenge_error())?;
+
+        // Every attempt uses the challenge up, so guessing codes means getting
+        // the password past the hasher again for each guess.
+        self.revoke(user_id, &claims.jti, timestamp_to_naive(claims.exp)).await?;
+
+        let user = self.user_repository.get(user_id)
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_challenge_error()))?;
+        self.mfa_service.verify_code(&user, &verification.code).await?;
+        self.issue_tokens(user.id, client, true).await
+    }
+
+    async fn refresh(&self, refresh: AuthRefresh, client: SessionClient) -> Result<AuthSuccessfulResponse, CommonError> {
+        let claims = self.token_service.validate_refresh_token(&refresh.refresh_token)
+            .map_err(|err| self.invalid_token_error(err, constants::ERR_CONTEXT_REFRESH))?;
+        self.ensure_not_revoked(&claims, constants::ERR_CONTEXT_REFRESH).await?;
+
+        // A signed refresh token is only honoured while its session exists, which
+        // lets sessions be ended server side before the token expires.
+        let session = self.session_repository.get_by_token(&digest::sha256_hex(&refresh.refresh_token))
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_session_error()))?;
+        if session.user_id.to_string() != claims.sub || session.expiry <= Utc::now().naive_utc() {
+            return Err(self.invalid_session_error());
+        }
+
+        // Refresh tokens are single use: the old session is replaced by a new one.
+        // Only the request that actually deletes it may go on, so two refreshing
+        // with the same token at once don't both get new tokens.
+        let is_deleted = self.session_repository.delete(session.id)
+            .await
+            .map_err(CommonError::from)?;
+        if !is_deleted {
+            return Err(self.invalid_session_error());
+        }
+        // The access token issued with the old session would otherwise live on
+        // until it expires, out of reach of logging out everywhere.
+        let access_token_expiry = timestamp_to_naive(claims.iat + self.token_service.access_token_lifetime_secs());
+        self.revoke(session.user_id, &session.id.to_string(), access_token_expiry).await?;
+
+        // The account may have been removed since the refresh token was issued.
+        let user = self.user_repository.get(session.user_id)
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_session_error()))?;
+        self.email_verification.ensure_verified(&user, VerifiedAction::Login)?;
+        self.issue_tokens(user.id, client, claims.mfa).await
+    }
+
+    async fn validate_access_token(&self, access_token: &str) -> Result<TokenClaims, CommonError> {
+        let claims = self.token_service.validate_access_token(access_token)
+            .map_err(|err| self.invalid_token_error(err, constants::ERR_CONTEXT_AUTHENTICATION))?;
+        self.ensure_not_revoked(&claims, constants::ERR_CONTEXT_AUTHENTICATION).await?;
+        Ok(claims)
+    }
+
+    async fn authenticate(&self, access_token: &str) -> Result<AuthenticatedUser, CommonError> {
+        let claims = self.validate_access_token(access_token).await?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| self.revoked_token_error(constants::ERR_CONTEXT_AUTHENTICATION))?;
+
+        // Tokens of deleted accounts stay signed until they expire, so the user
+        // has to be looked up rather than trusted from the claims.
+        let user = self.user_repository.get(user_id)
+            .await
+            .map_err(|err| not_found_as(err, self.revoked_token_error(constants::ERR_CONTEXT_AUTHENTICATION)))?;
+        // Also covers tokens issued before the policy required a verified email.
+        self.email_verification.ensure_verified(&user, VerifiedAction::Login)?;
+        Ok(AuthenticatedUser { user, claims })
+    }
+
+    async fn logout(&self, claims: &TokenClaims) -> Result<(), CommonError> {
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| self.revoked_token_error(constants::ERR_CONTEXT_AUTHENTICATION))?;
+
+        // The refresh token dies with its session, so the blacklist entry only has
+        // to outlive the access token.
+        self.revoke(user_id, &claims.jti, timestamp_to_naive(claims.exp)).await?;
+        if let Ok(session_id) = Uuid::parse_str(&claims.jti) {
+            self.session_repository.delete(session_id)
+                .await
+                .map_err(CommonError::from)?;
+        }
+        Ok(())
+    }
+
+    async fn logout_all(&self, claims: &TokenClaims) -> Result<(), CommonError> {
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| self.revoked_token_error(constants::ERR_CONTEXT_AUTHENTICATION))?;
+
+        self.revoke(user_id, &claims.jti, timestamp_to_naive(claims.exp)).await?;
+        self.end_all_sessions(user_id).await
+    }
+
+    async fn forgot_password(&self, request: AuthForgotPassword) -> Result<(), CommonError> {
+        // Like login, the response must not reveal whether the account exists.
+        let user = match self.user_repository.get_by_email(&normalize_email(&request.email)).await {
+            Ok(user) => user,
+            Err(err) if matches!(err.kind, RepositoryErrorKind::NotFound) => {
+                info!("Password reset requested for an unknown email");
+                return Ok(());
+            }
+            Err(err) => return Err(CommonError::from(err)),
+        };
+
+        // Only the digest is stored, so a leaked database can't be used to take
+        // over accounts with pending resets. Issuing a new token replaces the old one.
+        let token = random::random_token(constants::SEC_PASSWORD_RESET_TOKEN_BYTES);
+        let expiry = timestamp_to_naive(Utc::now().timestamp() + self.password_reset_token_lifetime_secs);
+        let update = UpdateUserHashed {
+            role: None,
+            name: None,
+            email: None,
+            password_hash: None,
+            reset_token: Some(digest::sha256_hex(&token)),
+            reset_token_expiry: Some(expiry),
+            email_verified_at: None,
+        };
+        self.user_repository.update(user.id, &update)
+            .await
+            .map_err(CommonError::from)?;
+
+        let message = MailMessage {
+            to: user.email,
+            subject: "Reset your password".to_string(),
+            body: std::format!(
+                "Hello {},\n\nUse the following token to choose a new password. It expires at {} UTC.\n\n{}\n\nIf you didn't ask for a password reset, you can ignore this message.",
+                user.name,
+                expiry.format("%Y-%m-%d %H:%M"),
+                token
+            ),
+        };
+        self.mailer_service.send(&message).await.map_err(CommonError::from)
+    }
+
+    async fn reset_password(&self, reset: AuthResetPassword) -> Result<(), CommonError> {
+        let token_digest = digest::sha256_hex(&reset.token);
+        let user = self.user_repository.get_by_reset_token(&token_digest)
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_reset_token_error()))?;
+        if user.reset_token_expiry.is_none_or(|expiry| expiry <= Utc::now().naive_utc()) {
+            return Err(self.invalid_reset_token_error());
+        }
+
+        self.password_policy.check(&reset.password, &reset.confirm_password, &PasswordOwner {
+            name: &user.name,
+            email: &user.email,
+        })?;
+        let password_hash = self.hash_service.hash_password(&reset.password)
+            .await
+            .map_err(CommonError::from)?;
+
+        self.user_repository.reset_password(user.id, &token_digest, &password_hash)
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_reset_token_error()))?;
+
+        // Whoever asked for the reset may be locking out someone who knows the
+        // old password, so every existing session is ended.
+        self.end_all_sessions(user.id).await
+    }
+
+    async fn prune_expired_tokens(&self) -> Result<usize, CommonError> {
+        let blacklist_entries = self.token_blacklist_repository.delete_expired()
+            .await
+            .map_err(CommonError::from)?;
+        let sessions = self.session_repository.delete_expired()
+            .await
+            .map_err(CommonError::from)?;
+        Ok(blacklist_entries + sessions)
+    }
+}
+
+fn timestamp_to_naive(timestamp: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_else(Utc::now)
+        .naive_utc()
+}
+
+/// Replaces a "not found" repository error with the given error, so lookups of
+/// unknown users or tokens surface as auth failures while outages still do not.
+pub(crate) fn not_found_as(error: RepositoryError, replacement: CommonError) -> CommonError {
+    match error.kind {
+        RepositoryErrorKind::NotFound => replacement,
+        _ => CommonError::from(error),
+    }
+}
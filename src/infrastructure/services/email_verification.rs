@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::domain::error::{CommonError, RepositoryErrorKind, UnknownEnumValue};
+use crate::domain::models::auth::{AuthResendVerification, AuthVerifyEmail, VerifiedAction};
+use crate::domain::models::common::NamedEnum;
+use crate::domain::models::user::User;
+use crate::domain::repositories::user::UserRepository;
+use crate::domain::services::email_verification::EmailVerificationService;
+use crate::infrastructure::services::auth::not_found_as;
+use crate::services::constants;
+use crate::services::error::SecurityError;
+use crate::services::traits::mailer::{MailMessage, MailerService};
+use crate::services::traits::token::TokenService;
+use crate::services::utils::email::normalize_email;
+use crate::services::utils::envutil::{get_env_var_as_str, get_env_var_as_type_or_default};
+
+/// Which actions need a verified email, and how often a user may ask for the
+/// verification email again.
+#[derive(Clone, Debug)]
+pub struct EmailVerificationPolicy {
+    pub required_for: Vec<VerifiedAction>,
+    pub resend_cooldown_secs: i64,
+}
+
+impl EmailVerificationPolicy {
+    /// Nothing requires a verified email unless `EMAIL_VERIFICATION_REQUIRED_FOR`
+    /// lists it, e.g. `login,admin`.
+    pub fn from_env() -> Self {
+        let required_for = get_env_var_as_str(constants::SEC_EMAIL_VERIFICATION_ENV_REQUIRED_FOR)
+            .map(|value| Self::parse_required_for(&value).unwrap_or_else(|err| {
+                panic!("Invalid {}: {}", constants::SEC_EMAIL_VERIFICATION_ENV_REQUIRED_FOR, err)
+            }))
+            .unwrap_or_default();
+
+        Self {
+            required_for,
+            resend_cooldown_secs: get_env_var_as_type_or_default(
+                constants::SEC_EMAIL_VERIFICATION_ENV_RESEND_COOLDOWN_SECS,
+                &constants::SEC_EMAIL_VERIFICATION_RESEND_COOLDOWN_SECS_DEFAULT
+            ),
+        }
+    }
+
+    /// Parses a comma separated list of action names.
+    pub fn parse_required_for(value: &str) -> Result<Vec<VerifiedAction>, UnknownEnumValue> {
+        value.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(VerifiedAction::from_name)
+            .collect()
+    }
+}
+
+pub struct EmailVerificationServiceImpl {
+    user_repository: Arc<dyn UserRepository>,
+    token_service: Arc<dyn TokenService>,
+    mailer_service: Arc<dyn MailerService>,
+    policy: EmailVerificationPolicy,
+}
+
+impl EmailVerificationServiceImpl {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        token_service: Arc<dyn TokenService>,
+        mailer_service: Arc<dyn MailerService>,
+        policy: EmailVerificationPolicy
+    ) -> Self {
+        Self {
+            user_repository,
+            token_service,
+            mailer_service,
+            policy,
+        }
+    }
+
+    fn invalid_token_error(&self) -> CommonError {
+        CommonError::from(SecurityError {
+            identifier: constants::SEC_ERR_VERIFICATION_TOKEN_INVALID.to_string(),
+            message: "Verification token is invalid or has expired".to_string(),
+            context: constants::ERR_CONTEXT_EMAIL_VERIFICATION.to_string(),
+        })
+    }
+
+    /// Sends the email unless another one went out after `sent_before`.
+    async fn send(&self, user: &User, sent_before: NaiveDateTime) -> Result<(), CommonError> {
+        let is_due = self.user_repository.mark_verification_sent(user.id, sent_before)
+            .await
+            .map_err(CommonError::from)?;
+        if !is_due {
+            info!("Verification email for user {} is already verified or was sent recently", user.id);
+            return Ok(());
+        }
+
+        let result = self.deliver(user).await;
+        if result.is_err() {
+            // Otherwise the cooldown would keep the user from asking for the
+            // email they never got.
+            if let Err(err) = self.user_repository.restore_verification_sent(user.id, user.email_verification_sent_at).await {
+                warn!("Could not reset verification email cooldown of user {}: {}", user.id, err);
+            }
+        }
+        result
+    }
+
+    async fn deliver(&self, user: &User) -> Result<(), CommonError> {
+        let token = self.token_service.generate_email_verification_token(user.id, &user.email)
+            .map_err(CommonError::from)?;
+        let expiry = chrono::DateTime::from_timestamp(token.claims.exp, 0).unwrap_or_else(Utc::now);
+        let message = MailMessage {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: std::format!(
+                "Hello {},\n\nUse the following token to verify your email address. It expires at {} UTC.\n\n{}\n\nIf you didn't create an account, you can ignore this message.",
+                user.name,
+                expiry.format("%Y-%m-%d %H:%M"),
+                token.token
+            ),
+        };
+        self.mailer_service.send(&message).await.map_err(CommonError::from)
+    }
+}
+
+#[async_trait]
+impl EmailVerificationService for EmailVerificationServiceImpl {
+    async fn send_verification(&self, user: &User) -> Result<(), CommonError> {
+        // Asked for by the system rather than the user, so no cooldown applies.
+        self.send(user, Utc::now().naive_utc()).await
+    }
+
+    async fn resend_verification(&self, request: AuthResendVerification) -> Result<(), CommonError> {
+        let user = match self.user_repository.get_by_email(&normalize_email(&request.email)).await {
+            Ok(user) => user,
+            Err(err) if matches!(err.kind, RepositoryErrorKind::NotFound) => {
+                info!("Verification email requested for an unknown email");
+                return Ok(());
+            }
+            Err(err) => return Err(CommonError::from(err)),
+        };
+
+        let sent_before = Utc::now().naive_utc() - Duration::try_seconds(self.policy.resend_cooldown_secs).unwrap_or_default();
+        self.send(&user, sent_before).await
+    }
+
+    async fn verify_email(&self, verification: AuthVerifyEmail) -> Result<User, CommonError> {
+        let claims = self.token_service.validate_email_verification_token(&verification.token)
+            .map_err(|_| self.invalid_token_error())?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| self.invalid_token_error())?;
+        let email = claims.email.ok_or_else(|| self.invalid_token_error())?;
+
+        let user = self.user_repository.get(user_id)
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_token_error()))?;
+        // Verifying twice, e.g. by following the link again, isn't an error.
+        if user.email_verified_at.is_some() && user.email.to_lowercase() == email.to_lowercase() {
+            return Ok(user);
+        }
+
+        // Fails if the email changed since the token was sent, as the token says
+        // nothing about the new address.
+        self.user_repository.mark_email_verified(user.id, &email)
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_token_error()))
+    }
+
+    fn is_required_for(&self, action: VerifiedAction) -> bool {
+        self.policy.required_for.contains(&action)
+    }
+
+    fn ensure_verified(&self, user: &User, action: VerifiedAction) -> Result<(), CommonError> {
+        if user.email_verified_at.is_some() || !self.is_required_for(action) {
+            return Ok(());
+        }
+        Err(CommonError::from(SecurityError {
+            identifier: constants::SEC_ERR_EMAIL_NOT_VERIFIED.to_string(),
+            message: "Email address has to be verified first".to_string(),
+            context: constants::ERR_CONTEXT_EMAIL_VERIFICATION.to_string(),
+        }))
+    }
+}
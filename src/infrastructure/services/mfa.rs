@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tracing::info;
+
+use crate::domain::error::{CommonError, UnknownEnumValue};
+use crate::domain::models::auth::{AuthMfaCode, AuthenticatedUser};
+use crate::domain::models::common::NamedEnum;
+use crate::domain::models::mfa::{MfaEnrollment, MfaRecoveryCodes};
+use crate::domain::models::user::{Role, User};
+use crate::domain::repositories::mfa_recovery_code::MfaRecoveryCodeRepository;
+use crate::domain::repositories::user::UserRepository;
+use crate::domain::services::mfa::MfaService;
+use crate::infrastructure::services::auth::not_found_as;
+use crate::services::concrete::secret_cipher::SecretCipher;
+use crate::services::constants;
+use crate::services::error::SecurityError;
+use crate::services::utils::{digest, random, totp};
+use crate::services::utils::envutil::{get_env_var_as_str, get_env_var_as_type_or_default};
+
+/// Which roles have to use two-factor authentication, and how codes are
+/// checked.
+#[derive(Clone, Debug)]
+pub struct MfaPolicy {
+    pub required_roles: Vec<Role>,
+    /// Shown by authenticator apps next to the account.
+    pub issuer: String,
+    /// How many time steps a code may be off either way, for clocks that drift.
+    pub skew_steps: i64,
+}
+
+impl MfaPolicy {
+    /// No role requires two-factor authentication unless `MFA_REQUIRED_ROLES`
+    /// lists it, e.g. `admin,super_admin`.
+    pub fn from_env() -> Self {
+        let required_roles = get_env_var_as_str(constants::SEC_MFA_ENV_REQUIRED_ROLES)
+            .map(|value| Self::parse_required_roles(&value).unwrap_or_else(|err| {
+                panic!("Invalid {}: {}", constants::SEC_MFA_ENV_REQUIRED_ROLES, err)
+            }))
+            .unwrap_or_default();
+
+        Self {
+            required_roles,
+            issuer: get_env_var_as_str(constants::SEC_MFA_ENV_ISSUER)
+                .unwrap_or_else(|_| constants::SEC_MFA_ISSUER_DEFAULT.to_string()),
+            skew_steps: get_env_var_as_type_or_default::<u32>(
+                constants::SEC_MFA_ENV_SKEW_STEPS,
+                &constants::SEC_MFA_SKEW_STEPS_DEFAULT
+            ) as i64,
+        }
+    }
+
+    /// Parses a comma separated list of role names.
+    pub fn parse_required_roles(value: &str) -> Result<Vec<Role>, UnknownEnumValue> {
+        value.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(Role::from_name)
+            .collect()
+    }
+}
+
+pub struct MfaServiceImpl {
+    user_repository: Arc<dyn UserRepository>,
+    recovery_code_repository: Arc<dyn MfaRecoveryCodeRepository>,
+    cipher: SecretCipher,
+    policy: MfaPolicy,
+}
+
+impl MfaServiceImpl {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        recovery_code_repository: Arc<dyn MfaRecoveryCodeRepository>,
+        cipher: SecretCipher,
+        policy: MfaPolicy
+    ) -> Self {
+        Self {
+            user_repository,
+            recovery_code_repository,
+            cipher,
+            policy,
+        }
+    }
+
+    fn error(&self, error_identifier: &str, message: &str) -> CommonError {
+        CommonError::from(SecurityError {
+            identifier: error_identifier.to_string(),
+            message: message.to_string(),
+            context: constants::ERR_CONTEXT_MFA.to_string(),
+        })
+    }
+
+    fn invalid_code_error(&self) -> CommonError {
+        self.error(constants::SEC_ERR_MFA_CODE_INVALID, "Code is invalid or was already used")
+    }
+
+    fn already_enabled_error(&self) -> CommonError {
+        self.error(constants::SEC_ERR_MFA_ALREADY_ENABLED, "Two-factor authentication is already enabled")
+    }
+
+    fn not_enabled_error(&self) -> CommonError {
+        self.error(constants::SEC_ERR_MFA_NOT_ENABLED, "Two-factor authentication is not enabled")
+    }
+
+    /// Decrypts the user's secret. It is sealed with the user id, so a secret
+    /// copied to another row doesn't decrypt.
+    fn secret(&self, user: &User) -> Result<Vec<u8>, CommonError> {
+        let sealed = user.totp_secret.as_deref().ok_or_else(|| self.not_enabled_error())?;
+        self.cipher.open(sealed, user.id.as_bytes()).map_err(CommonError::from)
+    }
+
+    /// The time step within the skew window that `code` belongs to, if any.
+    fn matching_step(&self, secret: &[u8], code: &str) -> Option<i64> {
+        let current = totp::time_step(Utc::now().timestamp());
+        (-self.policy.skew_steps..=self.policy.skew_steps)
+            .map(|offset| current + offset)
+            .find(|step| constant_time_eq(totp::code_at(secret, *step).as_bytes(), code.as_bytes()))
+    }
+
+    async fn verify_totp(&self, user: &User, code: &str) -> Result<bool, CommonError> {
+        let Some(step) = self.matching_step(&self.secret(user)?, code) else {
+            return Ok(false);
+        };
+        self.user_repository.mark_totp_used(user.id, step)
+            .await
+            .map_err(CommonError::from)
+    }
+
+    async fn verify_recovery_code(&self, user: &User, code: &str) -> Result<bool, CommonError> {
+        let is_consumed = self.recovery_code_repository.consume(user.id, &digest::sha256_hex(&normalize_recovery_code(code)))
+            .await
+            .map_err(CommonError::from)?;
+        if is_consumed {
+            info!("User {} signed in with a recovery code", user.id);
+        }
+        Ok(is_consumed)
+    }
+
+    /// Codes are random enough that, like reset tokens, a plain digest keeps
+    /// them safe if the table leaks.
+    async fn generate_recovery_codes(&self, user: &User) -> Result<MfaRecoveryCodes, CommonError> {
+        let codes: Vec<String> = (0..constants::SEC_MFA_RECOVERY_CODE_COUNT)
+            .map(|_| format_recovery_code(&totp::base32_encode(&random::random_bytes(constants::SEC_MFA_RECOVERY_CODE_BYTES))))
+            .collect();
+        let digests: Vec<String> = codes.iter()
+            .map(|code| digest::sha256_hex(&normalize_recovery_code(code)))
+            .collect();
+
+        self.recovery_code_repository.replace(user.id, &digests)
+            .await
+            .map_err(CommonError::from)?;
+        Ok(MfaRecoveryCodes { codes })
+    }
+}
+
+#[async_trait]
+impl MfaService for MfaServiceImpl {
+    async fn enroll(&self, user: &User) -> Result<MfaEnrollment, CommonError> {
+        if self.is_enabled(user) {
+            return Err(self.already_enabled_error());
+        }
+
+        let secret = random::random_bytes(constants::SEC_TOTP_SECRET_BYTES);
+        let sealed = self.cipher.seal(&secret, user.id.as_bytes()).map_err(CommonError::from)?;
+        self.user_repository.start_totp_enrollment(user.id, &sealed)
+            .await
+            .map_err(|err| not_found_as(err, self.already_enabled_error()))?;
+
+        let secret = totp::base32_encode(&secret);
+        Ok(MfaEnrollment {
+            otpauth_uri: totp::otpauth_uri(&self.policy.issuer, &user.email, &secret),
+            secret,
+        })
+    }
+
+    async fn confirm_enrollment(&self, user: &User, confirmation: AuthMfaCode) -> Result<MfaRecoveryCodes, CommonError> {
+        if self.is_enabled(user) {
+            return Err(self.already_enabled_error());
+        }
+        let Some(sealed) = user.totp_secret.as_deref() else {
+            return Err(self.not_enabled_error());
+        };
+
+        let step = self.matching_step(&self.secret(user)?, confirmation.code.trim())
+            .ok_or_else(|| self.invalid_code_error())?;
+        // Fails if the enrollment was started over since, as the code was made
+        // with the replaced secret.
+        self.user_repository.enable_totp(user.id, sealed, step)
+            .await
+            .map_err(|err| not_found_as(err, self.invalid_code_error()))?;
+        info!("Enabled two-factor authentication for user {}", user.id);
+
+        self.generate_recovery_codes(user).await
+    }
+
+    async fn disable(&self, user: &User, confirmation: AuthMfaCode) -> Result<(), CommonError> {
+        self.verify_code(user, &confirmation.code).await?;
+
+        self.user_repository.disable_totp(user.id)
+            .await
+            .map_err(CommonError::from)?;
+        self.recovery_code_repository.delete_by_user(user.id)
+            .await
+            .map_err(CommonError::from)?;
+        info!("Disabled two-factor authentication for user {}", user.id);
+        Ok(())
+    }
+
+    async fn regenerate_recovery_codes(&self, user: &User, confirmation: AuthMfaCode) -> Result<MfaRecoveryCodes, CommonError> {
+        self.verify_code(user, &confirmation.code).await?;
+        self.generate_recovery_codes(user).await
+    }
+
+    async fn verify_code(&self, user: &User, code: &str) -> Result<(), CommonError> {
+        if !self.is_enabled(user) {
+            return Err(self.not_enabled_error());
+        }
+
+        let code = code.trim();
+        let is_totp = code.len() == constants::SEC_TOTP_DIGITS as usize && code.bytes().all(|byte| byte.is_ascii_digit());
+        let is_valid = match is_totp {
+            true => self.verify_totp(user, code).await?,
+            false => self.verify_recovery_code(user, code).await?,
+        };
+        match is_valid {
+            true => Ok(()),
+            false => Err(self.invalid_code_error()),
+        }
+    }
+
+    fn is_enabled(&self, user: &User) -> bool {
+        user.totp_enabled_at.is_some()
+    }
+
+    fn is_required_for(&self, user: &User) -> bool {
+        self.policy.required_roles.contains(&user.role)
+    }
+
+    fn ensure_satisfied(&self, authenticated_user: &AuthenticatedUser) -> Result<(), CommonError> {
+        if !self.is_required_for(&authenticated_user.user) {
+            return Ok(());
+        }
+        self.ensure_used(authenticated_user)
+    }
+
+    fn ensure_used(&self, authenticated_user: &AuthenticatedUser) -> Result<(), CommonError> {
+        // Tokens from before TOTP was enabled don't count, nor do ones that
+        // outlive it being disabled.
+        if authenticated_user.claims.mfa && self.is_enabled(&authenticated_user.user) {
+            return Ok(());
+        }
+        Err(self.error(constants::SEC_ERR_MFA_REQUIRED, "Two-factor authentication has to be enabled and used to sign in"))
+    }
+}
+
+/// Recovery codes are compared without the dashes they are shown with and
+/// regardless of case, as people type them in by hand.
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Splits a code into groups of four, e.g. `ABCD-EFGH-IJKL-MNOP`.
+fn format_recovery_code(code: &str) -> String {
+    code.as_bytes()
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Takes the same time wherever the inputs differ, so response times don't
+/// reveal how much of a code was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
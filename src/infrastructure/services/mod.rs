@@ -1,2 +1,6 @@
 pub mod admin_permission;
+pub mod auth;
+pub mod email_verification;
+pub mod mfa;
+pub mod session;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::domain::error::CommonError;
+use crate::domain::models::session::Session;
+use crate::domain::repositories::repository::ResultPaging;
+use crate::domain::repositories::session::{SessionQueryParams, SessionRepository};
+use crate::domain::services::session::SessionService;
+
+#[derive(Clone)]
+pub struct SessionServiceImpl {
+    pub repository: Arc<dyn SessionRepository>,
+}
+
+impl SessionServiceImpl {
+    pub fn new(repository: Arc<dyn SessionRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl SessionService for SessionServiceImpl {
+    async fn list_by_user(&self, user_id: Uuid, params: SessionQueryParams) -> Result<ResultPaging<Session>, CommonError> {
+        self.repository.list_by_user(user_id, params)
+            .await
+            .map_err(CommonError::from)
+    }
+}
\ No newline at end of file
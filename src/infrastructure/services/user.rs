@@ -1,10 +1,14 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use tracing::warn;
 use uuid::Uuid;
 
-use crate::domain::error::CommonError;
+use crate::domain::constants as domain_constants;
+use crate::domain::error::{CommonError, RepositoryError};
+use crate::domain::models::common::{ErrorItem, ErrorResponseType};
 use crate::domain::models::user::{
+    Role,
     User,
     CreateUserPlainText,
     CreateUserHashed,
@@ -13,23 +17,34 @@ use crate::domain::models::user::{
 };
 use crate::domain::repositories::repository::ResultPaging;
 use crate::domain::repositories::user::{UserQueryParams, UserRepository};
+use crate::domain::services::email_verification::EmailVerificationService;
 use crate::domain::services::user::UserService;
 use crate::services::constants;
-use crate::services::error::SecurityError;
+use crate::services::error::PermissionError;
 use crate::services::traits::password_hash::PasswordHashService;
-use crate::services::utils::format;
+use crate::services::traits::password_policy::{PasswordOwner, PasswordPolicyService};
+use crate::services::utils::email::normalize_email;
 
 #[derive(Clone)]
 pub struct UserServiceImpl<'a> {
     pub repository: Arc<dyn UserRepository>,
-    hash_service: Arc<dyn PasswordHashService + 'a>
+    hash_service: Arc<dyn PasswordHashService + 'a>,
+    password_policy: Arc<dyn PasswordPolicyService + 'a>,
+    email_verification: Arc<dyn EmailVerificationService + 'a>
 }
 
 impl<'a> UserServiceImpl<'a> {
-    pub fn new(repository: Arc<dyn UserRepository>, hash_service: Arc<dyn PasswordHashService + 'a>) -> Self {
+    pub fn new(
+        repository: Arc<dyn UserRepository>,
+        hash_service: Arc<dyn PasswordHashService + 'a>,
+        password_policy: Arc<dyn PasswordPolicyService + 'a>,
+        email_verification: Arc<dyn EmailVerificationService + 'a>
+    ) -> Self {
         Self {
             repository,
-            hash_service
+            hash_service,
+            password_policy,
+            email_verification
         }
     }
 
@@ -37,18 +52,64 @@ impl<'a> UserServiceImpl<'a> {
         password.is_some() && confirm_password.is_some()
     }
 
-    fn check_if_passwords_match(&self, password: &str, confirm_password: &str) -> Result<(), CommonError> {
-        if password != confirm_password {
-            return Err(CommonError::from(SecurityError {
-                message: format::format_error_string(constants::SEC_ERR_PASS_NOT_MATCH, "`password` and `confirm_password` fields do not match"),
-                context: constants::ERR_CONTEXT_LOGIN.to_string(),
-            }));
+    fn permission_error(&self, message: &str) -> CommonError {
+        CommonError::from(PermissionError {
+            identifier: constants::SEC_ERR_PERMISSION_DENIED.to_string(),
+            message: message.to_string(),
+            context: constants::ERR_CONTEXT_USER_SERV.to_string(),
+        })
+    }
+
+    fn is_super_admin(actor: Option<&User>) -> bool {
+        actor.is_some_and(|actor| matches!(actor.role, Role::SuperAdmin))
+    }
+
+    /// Holding `CanManageUsers` isn't enough to hand out elevated roles, or
+    /// admins could promote themselves.
+    fn ensure_can_assign(&self, role: Option<Role>, actor: Option<&User>) -> Result<(), CommonError> {
+        match role {
+            None | Some(Role::User) => Ok(()),
+            Some(_) if Self::is_super_admin(actor) => Ok(()),
+            Some(_) => Err(self.permission_error("Only super admins can assign elevated roles")),
+        }
+    }
+
+    /// Taking over a super admin's account would be a way around
+    /// `ensure_can_assign`.
+    async fn ensure_can_change(&self, user_id: Uuid, actor: &User) -> Result<Option<User>, CommonError> {
+        if Self::is_super_admin(Some(actor)) {
+            return Ok(None);
+        }
+        let user = self.repository.get(user_id).await.map_err(CommonError::from)?;
+        if matches!(user.role, Role::SuperAdmin) {
+            return Err(self.permission_error("Only super admins can change the accounts of super admins"));
         }
-        Ok(())
+        Ok(Some(user))
     }
 
-    fn hash_password(&self, password: &str) -> Result<String, CommonError> {
-        match self.hash_service.hash_password(password) {
+    /// Turns storage errors the caller can act on into meaningful messages.
+    fn repository_error(&self, error: RepositoryError) -> CommonError {
+        if error.kind.is_unique_violation_of(domain_constants::DB_CONSTRAINT_USERS_EMAIL) {
+            return CommonError::new(
+                constants::USER_ERR_EMAIL_TAKEN,
+                "Email is already registered".to_string(),
+                constants::ERR_CONTEXT_USER_SERV.to_string(),
+                ErrorResponseType::Conflict
+            );
+        }
+        CommonError::from(error)
+    }
+
+    /// The account works without the email, and the user can ask for it again,
+    /// so failing to send it doesn't fail the change that triggered it.
+    async fn send_verification(&self, user: &User) {
+        if let Err(err) = self.email_verification.send_verification(user).await {
+            warn!("Could not send verification email to user {}: {}", user.id, err);
+        }
+    }
+
+    async fn hash_password(&self, password: &str) -> Result<String, CommonError> {
+        match self.hash_service.hash_password(password).await {
             Ok(hash) => Ok(hash),
             Err(err) => Err(CommonError::from(err)),
         }
@@ -57,22 +118,56 @@ impl<'a> UserServiceImpl<'a> {
 
 #[async_trait]
 impl<'a> UserService for UserServiceImpl<'a> {
-    async fn create(&self, new_user: CreateUserPlainText) -> Result<User, CommonError> {
-        self.check_if_passwords_match(&new_user.password, &new_user.confirm_password)
-            .map_err(CommonError::from)?;
+    async fn create(&self, new_user: CreateUserPlainText, actor: Option<&User>) -> Result<User, CommonError> {
+        self.ensure_can_assign(new_user.role, actor)?;
+        let email = normalize_email(&new_user.email);
+        self.password_policy.check(&new_user.password, &new_user.confirm_password, &PasswordOwner {
+            name: &new_user.name,
+            email: &email,
+        })?;
 
         let hashed = CreateUserHashed {
             role: new_user.role,
             name: new_user.name,
-            email: new_user.email,
-            password_hash: self.hash_password(&new_user.password)?,
+            email,
+            password_hash: self.hash_password(&new_user.password).await?,
             reset_token: new_user.reset_token,
             reset_token_expiry: new_user.reset_token_expiry
         };
 
-        self.repository.create(&hashed)
+        let user = self.repository.create(&hashed)
             .await
-            .map_err(CommonError::from)
+            .map_err(|err| self.repository_error(err))?;
+        self.send_verification(&user).await;
+        Ok(user)
+    }
+
+    async fn import(&self, mut new_users: Vec<CreateUserHashed>, actor: &User) -> Result<Vec<User>, CommonError> {
+        for new_user in &new_users {
+            self.ensure_can_assign(new_user.role, Some(actor))?;
+        }
+
+        // Legacy formats are accepted as they are and replaced with the current
+        // one when each user first signs in.
+        let errors: Vec<ErrorItem> = new_users.iter()
+            .enumerate()
+            .filter(|(_, new_user)| !self.hash_service.is_supported(&new_user.password_hash))
+            .map(|(index, _)| ErrorItem::new(
+                &std::format!("users[{}].password_hash", index),
+                constants::VAL_ERR_UNSUPPORTED_PASSWORD_HASH,
+                "Password hash is not in a supported format".to_string()
+            ))
+            .collect();
+        if !errors.is_empty() {
+            return Err(CommonError::validation(errors));
+        }
+        for new_user in &mut new_users {
+            new_user.email = normalize_email(&new_user.email);
+        }
+
+        self.repository.create_many(&new_users)
+            .await
+            .map_err(|err| self.repository_error(err))
     }
 
     async fn list(&self, params: UserQueryParams) -> Result<ResultPaging<User>, CommonError> {
@@ -87,29 +182,63 @@ impl<'a> UserService for UserServiceImpl<'a> {
             .map_err(CommonError::from)
     }
 
-    async fn update(&self, user_id: Uuid, update_user: UpdateUserPlainText) -> Result<User, CommonError> {
+    async fn update(&self, user_id: Uuid, update_user: UpdateUserPlainText, actor: &User) -> Result<User, CommonError> {
+        if update_user.role.is_some() && !Self::is_super_admin(Some(actor)) {
+            return Err(self.permission_error("Only super admins can change roles"));
+        }
+        let target = self.ensure_can_change(user_id, actor).await?;
+
         let mut hashed = UpdateUserHashed {
             role: update_user.role,
             name: update_user.name,
-            email: update_user.email,
+            email: update_user.email.as_deref().map(normalize_email),
             password_hash: None,
             reset_token: update_user.reset_token,
-            reset_token_expiry: update_user.reset_token_expiry
+            reset_token_expiry: update_user.reset_token_expiry,
+            email_verified_at: None,
         };
-
-        if self.password_fields_are_both_specified(&update_user.password, &update_user.confirm_password) {
-            self.check_if_passwords_match(&update_user.password.clone().unwrap(), &update_user.confirm_password.unwrap())
-                .map_err(CommonError::from)?;
-
-            hashed.password_hash = Some(self.hash_password(&update_user.password.unwrap())?);
+        let changes_password = self.password_fields_are_both_specified(&update_user.password, &update_user.confirm_password);
+
+        // Unless the permission check already loaded it, the current user is only
+        // needed to check a new password against and to tell whether the email
+        // really changes.
+        let current = match (target, changes_password || hashed.email.is_some()) {
+            (Some(target), _) => Some(target),
+            (None, true) => Some(self.repository.get(user_id).await.map_err(CommonError::from)?),
+            (None, false) => None,
+        };
+        let mut email_changed = false;
+        if let Some(current) = &current {
+            if changes_password {
+                let password = update_user.password.unwrap();
+                // The password is checked against the name and email the user will
+                // have after this update.
+                self.password_policy.check(&password, &update_user.confirm_password.unwrap(), &PasswordOwner {
+                    name: hashed.name.as_deref().unwrap_or(&current.name),
+                    email: hashed.email.as_deref().unwrap_or(&current.email),
+                })?;
+
+                hashed.password_hash = Some(self.hash_password(&password).await?);
+            }
+
+            // A new address has to be verified again.
+            email_changed = hashed.email.as_ref().is_some_and(|email| email.to_lowercase() != current.email.to_lowercase());
+            if email_changed {
+                hashed.email_verified_at = Some(None);
+            }
         }
 
-        self.repository.update(user_id, &hashed)
+        let user = self.repository.update(user_id, &hashed)
             .await
-            .map_err(CommonError::from)
+            .map_err(|err| self.repository_error(err))?;
+        if email_changed {
+            self.send_verification(&user).await;
+        }
+        Ok(user)
     }
 
-    async fn delete(&self, user_id: Uuid) -> Result<bool, CommonError> {
+    async fn delete(&self, user_id: Uuid, actor: &User) -> Result<bool, CommonError> {
+        self.ensure_can_change(user_id, actor).await?;
         self.repository.delete(user_id)
             .await
             .map_err(CommonError::from)
@@ -1,13 +1,19 @@
+use std::time::Duration;
+
 use actix_web::HttpServer;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, EnvFilter};
 
-use tonguetrek_backend::{
+use iron_cms_api::{
     create_app,
     constants,
+    container::Container,
     env_check::check_env_variables,
+    infrastructure::jobs::spawn_expired_token_pruner,
+    services::constants as services_constants,
     services::utils::envutil::get_env_var_as_str,
-    services::utils::envutil::get_env_var_as_type
+    services::utils::envutil::get_env_var_as_type,
+    services::utils::envutil::get_env_var_as_type_or_default
 };
 
 #[actix_web::main]
@@ -21,9 +27,16 @@ async fn main() -> std::io::Result<()> {
 
     let domain = get_env_var_as_str(constants::ENV_SERVER_DOMAIN).unwrap();
     let port = get_env_var_as_type::<u16>(constants::ENV_SERVER_PORT).unwrap();
+    let prune_interval_secs = get_env_var_as_type_or_default(
+        services_constants::SEC_TOKEN_PRUNE_ENV_INTERVAL_SECS,
+        &services_constants::SEC_TOKEN_PRUNE_INTERVAL_SECS_DEFAULT
+    );
+
+    let container = Container::new().await;
+    spawn_expired_token_pruner(container.auth_service.clone(), Duration::from_secs(prune_interval_secs));
 
     HttpServer::new(move || {
-        create_app::create_app()
+        create_app::create_app(&container)
     })
         .bind((domain, port))?
         .run()
@@ -1,7 +1,8 @@
-use std::time::{Instant, Duration};
+use std::sync::Arc;
 
 use argon2::{
     password_hash::{
+        self,
         rand_core::OsRng,
         PasswordHash,
         PasswordHasher,
@@ -10,47 +11,73 @@ use argon2::{
     },
     Argon2,
     Algorithm,
+    KeyId,
     Params,
+    ParamsBuilder,
     Version
 };
-use tracing::info;
+use async_trait::async_trait;
 
 use crate::services::{
+    concrete::hashing_pool::HashingPool,
+    concrete::pepper::PepperRing,
     error::SecurityError, 
-    utils::{
-        envutil::get_env_var_as_str,
-        envutil::get_env_var_as_type_or_default,
-        format::format_error_string
-    }
+    utils::envutil::get_env_var_as_type_or_default
 };
 use crate::services::traits::password_hash::PasswordHashService;
-use crate::services::utils::format;
 use crate::services::constants;
 
-pub struct Argon2IdHashService<'a> {
-    argon2: Argon2<'a>,
-    time_start: Instant
+/// Argon2id hashing, run on the shared `HashingPool` so it never blocks the
+/// async workers. When peppers are configured the current one is passed to
+/// Argon2 as its secret and its version is recorded as the hash's `keyid`, so
+/// hashes made with an older pepper can still be verified.
+pub struct Argon2IdHashService {
+    params: Params,
+    pepper: Option<Arc<[u8]>>,
+    peppers: PepperRing,
+    pool: Arc<HashingPool>
 }
 
-impl<'a> Argon2IdHashService<'a> {
-    pub fn new() -> Self {
-        let argon2 = Argon2::new(
-            Algorithm::Argon2id,
-            Version::V0x13,
-            Params::new(
-                Self::retrieve_memory_size_from_env(),
-                Self::retrieve_num_iterations_from_env(),
-                Self::retrieve_num_threads_from_env(),
-                Some(Self::retrieve_output_len_from_env())
-            ).unwrap()
-        );
+impl Argon2IdHashService {
+    /// Uses the parameters and peppers configured in the environment.
+    pub fn new(pool: Arc<HashingPool>) -> Self {
+        Self::with_peppers(Self::params_from_env(), PepperRing::from_env(), pool)
+    }
+
+    /// Hashes without a pepper.
+    pub fn with_params(params: Params, pool: Arc<HashingPool>) -> Self {
+        Self::with_peppers(params, PepperRing::default(), pool)
+    }
+
+    pub fn with_peppers(params: Params, peppers: PepperRing, pool: Arc<HashingPool>) -> Self {
+        let current = peppers.current();
+        let mut builder = ParamsBuilder::new();
+        builder
+            .m_cost(params.m_cost())
+            .t_cost(params.t_cost())
+            .p_cost(params.p_cost())
+            .output_len(params.output_len().unwrap_or(Params::DEFAULT_OUTPUT_LEN));
+        if let Some((version, _)) = &current {
+            builder.keyid(Self::key_id(*version));
+        }
 
         Self {
-            argon2,
-            time_start: Instant::now()
+            params: builder.build().unwrap(),
+            pepper: current.map(|(_, pepper)| pepper),
+            peppers,
+            pool
         }
     }
 
+    fn params_from_env() -> Params {
+        Params::new(
+            Self::retrieve_memory_size_from_env(),
+            Self::retrieve_num_iterations_from_env(),
+            Self::retrieve_num_threads_from_env(),
+            Some(Self::retrieve_output_len_from_env())
+        ).unwrap()
+    }
+
     fn retrieve_memory_size_from_env() -> u32 {
         get_env_var_as_type_or_default(constants::SEC_ARGON2ID_ENV_MEMORY_SIZE_MB, &constants::SEC_ARGON2ID_MEMORY_SIZE_MB_DEFAULT)
     }
@@ -67,82 +94,127 @@ impl<'a> Argon2IdHashService<'a> {
         get_env_var_as_type_or_default(constants::SEC_ARGON2ID_ENV_OUTPUT_LEN, &constants::SEC_ARGON2ID_OUTPUT_LEN_DEFAULT)
     }
 
-    fn is_log_debug_or_trace() -> bool {
-        let debug = get_env_var_as_str("RUST_LOG");
-        debug.is_ok() && (debug.as_ref().unwrap() == "debug" || debug.as_ref().unwrap() == "trace")
+    fn key_id(pepper_version: u32) -> KeyId {
+        KeyId::new(&pepper_version.to_be_bytes()).unwrap()
     }
-}
 
-impl<'a> Default for Argon2IdHashService<'a> {
-    fn default() -> Self {
-        Self::new()
+    fn argon2(pepper: Option<&[u8]>, params: Params) -> password_hash::Result<Argon2<'_>> {
+        match pepper {
+            Some(pepper) => Ok(Argon2::new_with_secret(pepper, Algorithm::Argon2id, Version::V0x13, params)?),
+            None => Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params)),
+        }
     }
-}
 
-impl<'a> PasswordHashService for Argon2IdHashService<'a> {
-    fn hash_password(&self, password: &str) -> Result<String, SecurityError> {
-        let salt = SaltString::generate(&mut OsRng);
+    /// Whether a stored hash costs no more to verify than the ceiling, or than
+    /// the configured parameters if those are higher. Imported hashes come with
+    /// whatever cost the other system used, and an absurd one would allocate
+    /// gigabytes and tie up a hashing thread on every login attempt.
+    fn is_within_ceiling(&self, password_hash: &PasswordHash) -> bool {
+        Params::try_from(password_hash).is_ok_and(|stored| {
+            stored.m_cost() <= constants::SEC_ARGON2_MAX_M_COST.max(self.params.m_cost())
+                && stored.t_cost() <= constants::SEC_ARGON2_MAX_T_COST.max(self.params.t_cost())
+                && stored.p_cost() <= constants::SEC_ARGON2_MAX_P_COST.max(self.params.p_cost())
+        })
+    }
 
-        if Self::is_log_debug_or_trace() {
-            info!("Generated salt: {}", salt.as_str());
+    /// Looks up the pepper a stored hash was made with. Hashes without a `keyid`
+    /// predate peppering and are verified without one.
+    fn pepper_of(&self, password_hash: &PasswordHash) -> Result<Option<Arc<[u8]>>, SecurityError> {
+        let error = |identifier: &str, message: String| SecurityError {
+            identifier: identifier.to_string(),
+            message,
+            context: constants::ERR_CONTEXT_ARGON2ID_SERV.to_string(),
+        };
+        let params = Params::try_from(password_hash)
+            .map_err(|err| error(constants::SEC_ERR_HASH_PARSE_FAIL, err.to_string()))?;
+        if params.keyid().is_empty() {
+            return Ok(None);
         }
 
-        let mut duration_1: Option<Duration> = None;
-        if Self::is_log_debug_or_trace() {
-            duration_1 = Some(self.time_start.elapsed());
-        }
+        let version = <[u8; 4]>::try_from(params.keyid())
+            .map(u32::from_be_bytes)
+            .map_err(|_| error(constants::SEC_ERR_HASH_PARSE_FAIL, "Malformed pepper version".to_string()))?;
+        self.peppers.get(version)
+            .map(Some)
+            .ok_or_else(|| error(constants::SEC_ERR_PEPPER_UNKNOWN, std::format!("Pepper version {} is not configured", version)))
+    }
+}
 
-        let password_hash = self.argon2.hash_password(password.as_bytes(), &salt);
+#[async_trait]
+impl PasswordHashService for Argon2IdHashService {
+    async fn hash_password(&self, password: &str) -> Result<String, SecurityError> {
+        let params = self.params.clone();
+        let pepper = self.pepper.clone();
+        let password = password.to_string();
+
+        self.pool.run(move || {
+            let salt = SaltString::generate(&mut OsRng);
+            Self::argon2(pepper.as_deref(), params)?
+                .hash_password(password.as_bytes(), &salt)
+                .map(|hash| hash.to_string())
+        }).await?.map_err(|err| SecurityError {
+            identifier: constants::SEC_ERR_HASH_FAILED.to_string(),
+            message: err.to_string(),
+            context: constants::ERR_CONTEXT_ARGON2ID_SERV.to_string(),
+        })
+    }
 
-        if Self::is_log_debug_or_trace() {
-            let duration_2 = self.time_start.elapsed();
-            let elapsed = duration_2 - duration_1.unwrap();
-            info!("Hashing took {} ms", elapsed.as_millis());
-        }
+    fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(password_hash) = PasswordHash::new(hash) else {
+            return false;
+        };
+        let Ok(stored) = Params::try_from(&password_hash) else {
+            return true;
+        };
+        let current = &self.params;
+
+        password_hash.algorithm != Algorithm::Argon2id.ident()
+            || password_hash.version != Some(Version::V0x13.into())
+            || stored.m_cost() != current.m_cost()
+            || stored.t_cost() != current.t_cost()
+            || stored.p_cost() != current.p_cost()
+            || stored.output_len().unwrap_or(Params::DEFAULT_OUTPUT_LEN) != current.output_len().unwrap_or(Params::DEFAULT_OUTPUT_LEN)
+            || stored.keyid() != current.keyid()
+    }
 
-        match password_hash {
-            Ok(hash) => Ok(hash.to_string()),
-            Err(err) => Err(SecurityError {
-                message: format::format_error_string(constants::SEC_ERR_HASH_FAILED, err.to_string().as_str()),
-                context: constants::ERR_CONTEXT_ARGON2ID_SERV.to_string(),
-            })
-        }
+    fn is_supported(&self, hash: &str) -> bool {
+        // Every Argon2 variant can be verified, whichever one is being written.
+        PasswordHash::new(hash).is_ok_and(|password_hash| {
+            [Algorithm::Argon2d, Algorithm::Argon2i, Algorithm::Argon2id]
+                .iter()
+                .any(|algorithm| password_hash.algorithm == algorithm.ident())
+                && self.is_within_ceiling(&password_hash)
+        })
     }
 
-    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, SecurityError> {
-        let password_hash = match PasswordHash::new(hash) {
-            Ok(hash) => hash,
+    async fn verify_password(&self, password: &str, hash: &str) -> Result<bool, SecurityError> {
+        // Parsing is cheap, so a malformed hash is rejected without queueing.
+        let pepper = match PasswordHash::new(hash) {
+            Ok(password_hash) if !self.is_within_ceiling(&password_hash) => return Err(SecurityError {
+                identifier: constants::SEC_ERR_HASH_PARSE_FAIL.to_string(),
+                message: "Malformed or too costly Argon2 password hash".to_string(),
+                context: constants::ERR_CONTEXT_ARGON2ID_SERV.to_string(),
+            }),
+            Ok(password_hash) => self.pepper_of(&password_hash)?,
             Err(err) => return Err(SecurityError {
-                message: format::format_error_string(constants::SEC_ERR_HASH_PARSE_FAIL, err.to_string().as_str()),
+                identifier: constants::SEC_ERR_HASH_PARSE_FAIL.to_string(),
+                message: err.to_string(),
                 context: constants::ERR_CONTEXT_ARGON2ID_SERV.to_string(),
-            })
+            }),
         };
-        
-        let mut duration_1: Option<Duration> = None;
-        if Self::is_log_debug_or_trace() {
-            duration_1 = Some(self.time_start.elapsed());
-        }
 
-        match self.argon2.verify_password(password.as_bytes(), &password_hash) {
-            Ok(_) => {
-                if Self::is_log_debug_or_trace() {
-                    let duration_2 = self.time_start.elapsed();
-                    let elapsed = duration_2 - duration_1.unwrap();
-                    info!("Verifying took {} ms", elapsed.as_millis());
-                }
-                Ok(true)
-            },
-            Err(err) => {
-                if Self::is_log_debug_or_trace() {
-                    let duration_2 = self.time_start.elapsed();
-                    let elapsed = duration_2 - duration_1.unwrap();
-                    info!("Verifying took {} ms", elapsed.as_millis());
-                }
-                Err(SecurityError {
-                    message: format_error_string(constants::SEC_ERR_PASS_VERIFY, err.to_string().as_str()),
-                    context: constants::ERR_CONTEXT_ARGON2ID_SERV.to_string(),
-                })
-            }
-        }
+        let params = self.params.clone();
+        let password = password.to_string();
+        let hash = hash.to_string();
+
+        self.pool.run(move || {
+            let password_hash = PasswordHash::new(&hash)?;
+            // The algorithm and parameters are taken from the stored hash.
+            Self::argon2(pepper.as_deref(), params)?.verify_password(password.as_bytes(), &password_hash)
+        }).await?.map(|_| true).map_err(|err| SecurityError {
+            identifier: constants::SEC_ERR_PASS_VERIFY.to_string(),
+            message: err.to_string(),
+            context: constants::ERR_CONTEXT_ARGON2ID_SERV.to_string(),
+        })
     }
 }
\ No newline at end of file
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use argon2::password_hash::{PasswordHash, PasswordVerifier};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine};
+use pbkdf2::Pbkdf2;
+use sha2::Sha256;
+
+use crate::services::concrete::hashing_pool::HashingPool;
+use crate::services::constants;
+use crate::services::error::SecurityError;
+use crate::services::traits::password_hash::PasswordHashService;
+
+/// Hash formats written by other systems, which can be verified but are never
+/// written.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LegacyFormat {
+    /// Modular crypt bcrypt, `$2b$<cost>$<salt and hash>`, and its `2a`/`2x`/`2y` variants.
+    Bcrypt,
+    /// PHC PBKDF2-SHA256, `$pbkdf2-sha256$i=<rounds>,l=<len>$<salt>$<hash>`.
+    Pbkdf2Phc,
+    /// Passlib's modular crypt PBKDF2-SHA256, `$pbkdf2-sha256$<rounds>$<salt>$<hash>`
+    /// with both parts in its `.` for `+` base64 alphabet.
+    Pbkdf2Passlib,
+}
+
+impl LegacyFormat {
+    fn detect(hash: &str) -> Option<Self> {
+        if ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix)) {
+            return Some(LegacyFormat::Bcrypt);
+        }
+        let rest = hash.strip_prefix(constants::SEC_PBKDF2_SHA256_PREFIX)?;
+        match rest.split('$').next() {
+            Some(params) if params.contains('=') => Some(LegacyFormat::Pbkdf2Phc),
+            Some(rounds) if rounds.parse::<u32>().is_ok() => Some(LegacyFormat::Pbkdf2Passlib),
+            _ => None,
+        }
+    }
+
+    /// Whether a hash in this format is well formed and within the cost
+    /// ceiling. Imported hashes come with whatever cost the other system used,
+    /// and verifying an absurd one would tie up a hashing thread for minutes.
+    fn is_acceptable(self, hash: &str) -> bool {
+        match self {
+            LegacyFormat::Bcrypt => hash.parse::<bcrypt::HashParts>()
+                .is_ok_and(|parts| parts.get_cost() <= constants::SEC_BCRYPT_MAX_COST),
+            LegacyFormat::Pbkdf2Phc => PasswordHash::new(hash)
+                .ok()
+                .and_then(|password_hash| pbkdf2::Params::try_from(&password_hash).ok())
+                .is_some_and(|params| params.rounds <= constants::SEC_PBKDF2_MAX_ROUNDS),
+            LegacyFormat::Pbkdf2Passlib => PasslibPbkdf2::parse(hash).is_some(),
+        }
+    }
+}
+
+/// A Passlib PBKDF2 hash split into its parts.
+struct PasslibPbkdf2 {
+    rounds: u32,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+impl PasslibPbkdf2 {
+    fn parse(hash: &str) -> Option<Self> {
+        let mut parts = hash.strip_prefix(constants::SEC_PBKDF2_SHA256_PREFIX)?.split('$');
+        let rounds = parts.next()?.parse().ok().filter(|rounds| (1..=constants::SEC_PBKDF2_MAX_ROUNDS).contains(rounds))?;
+        let salt = Self::decode(parts.next()?)?;
+        let hash = Self::decode(parts.next()?).filter(|hash| !hash.is_empty())?;
+        parts.next().is_none().then_some(PasslibPbkdf2 { rounds, salt, hash })
+    }
+
+    fn decode(value: &str) -> Option<Vec<u8>> {
+        STANDARD_NO_PAD.decode(value.replace('.', "+")).ok()
+    }
+
+    fn matches(&self, password: &str) -> bool {
+        let mut derived = vec![0u8; self.hash.len()];
+        pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &self.salt, self.rounds, &mut derived);
+        // Compares every byte so the time taken doesn't depend on where they differ.
+        derived.iter().zip(&self.hash).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+/// Verifies every supported format but only ever writes through `current`.
+/// Hashes in a legacy format always report that they need a rehash, so they
+/// are replaced the first time their owner signs in.
+pub struct CompositeHashService {
+    current: Arc<dyn PasswordHashService>,
+    pool: Arc<HashingPool>,
+}
+
+impl CompositeHashService {
+    pub fn new(current: Arc<dyn PasswordHashService>, pool: Arc<HashingPool>) -> Self {
+        Self { current, pool }
+    }
+
+    fn error(error_identifier: &str, message: String) -> SecurityError {
+        SecurityError {
+            identifier: error_identifier.to_string(),
+            message,
+            context: constants::ERR_CONTEXT_COMPOSITE_HASH_SERV.to_string(),
+        }
+    }
+
+    fn parse_error(format: LegacyFormat) -> SecurityError {
+        Self::error(constants::SEC_ERR_HASH_PARSE_FAIL, std::format!("Malformed or too costly {:?} password hash", format))
+    }
+
+    async fn verify_legacy(&self, format: LegacyFormat, password: &str, hash: &str) -> Result<bool, SecurityError> {
+        if !format.is_acceptable(hash) {
+            return Err(Self::parse_error(format));
+        }
+
+        let password = password.to_string();
+        let matches = match format {
+            LegacyFormat::Bcrypt => {
+                let hash = hash.to_string();
+                self.pool.run(move || bcrypt::verify(password, &hash)).await?
+                    .map_err(|err| Self::error(constants::SEC_ERR_HASH_PARSE_FAIL, err.to_string()))?
+            },
+            LegacyFormat::Pbkdf2Phc => {
+                let hash = hash.to_string();
+                self.pool.run(move || {
+                    let password_hash = PasswordHash::new(&hash)?;
+                    Pbkdf2.verify_password(password.as_bytes(), &password_hash)
+                }).await?.is_ok()
+            },
+            LegacyFormat::Pbkdf2Passlib => {
+                let parsed = PasslibPbkdf2::parse(hash).ok_or_else(|| Self::parse_error(format))?;
+                self.pool.run(move || parsed.matches(&password)).await?
+            },
+        };
+
+        if !matches {
+            return Err(Self::error(constants::SEC_ERR_PASS_VERIFY, "Password does not match".to_string()));
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl PasswordHashService for CompositeHashService {
+    async fn hash_password(&self, password: &str) -> Result<String, SecurityError> {
+        self.current.hash_password(password).await
+    }
+
+    async fn verify_password(&self, password: &str, hash: &str) -> Result<bool, SecurityError> {
+        match LegacyFormat::detect(hash) {
+            Some(format) => self.verify_legacy(format, password, hash).await,
+            None => self.current.verify_password(password, hash).await,
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        LegacyFormat::detect(hash).is_some() || self.current.needs_rehash(hash)
+    }
+
+    fn is_supported(&self, hash: &str) -> bool {
+        match LegacyFormat::detect(hash) {
+            Some(format) => format.is_acceptable(hash),
+            None => self.current.is_supported(hash),
+        }
+    }
+}
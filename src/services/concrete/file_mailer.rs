@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::services::constants;
+use crate::services::error::MailerError;
+use crate::services::traits::mailer::{MailMessage, MailerService};
+
+/// Stand-in mailer that writes every outgoing message to its own `.eml` file
+/// in a directory, where tests and local setups can pick up what was sent.
+pub struct FileMailerService {
+    directory: PathBuf,
+}
+
+impl FileMailerService {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self { directory: directory.into() }
+    }
+
+    fn error(message: String) -> MailerError {
+        MailerError {
+            identifier: constants::MAIL_ERR_DELIVERY_FAILED.to_string(),
+            message,
+            context: constants::ERR_CONTEXT_FILE_MAILER.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl MailerService for FileMailerService {
+    async fn send(&self, message: &MailMessage) -> Result<(), MailerError> {
+        fs::create_dir_all(&self.directory)
+            .await
+            .map_err(|err| Self::error(std::format!("Could not create {}: {}", self.directory.display(), err)))?;
+
+        // Timestamp first, so the files list in the order they were sent.
+        let path = self.directory.join(std::format!("{}-{}.eml", Utc::now().format("%Y%m%dT%H%M%S%.6f"), Uuid::new_v4()));
+        let contents = std::format!("To: {}\nSubject: {}\n\n{}\n", message.to, message.subject, message.body);
+        fs::write(&path, contents)
+            .await
+            .map_err(|err| Self::error(std::format!("Could not write {}: {}", path.display(), err)))
+    }
+}
@@ -0,0 +1,176 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::services::constants;
+use crate::services::error::SecurityError;
+use crate::services::utils::envutil::get_env_var_as_type_or_default;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Point-in-time view of the hashing pool, for monitoring.
+#[derive(Clone, Debug)]
+pub struct HashingPoolMetrics {
+    pub workers: usize,
+    pub queue_capacity: usize,
+    pub queue_depth: usize,
+    pub in_flight: usize,
+    pub completed: u64,
+    pub rejected: u64,
+    pub avg_wait_ms: f64,
+    pub avg_hash_ms: f64,
+    pub max_hash_ms: f64,
+}
+
+#[derive(Default)]
+struct Counters {
+    queue_depth: AtomicUsize,
+    in_flight: AtomicUsize,
+    completed: AtomicU64,
+    rejected: AtomicU64,
+    total_wait_micros: AtomicU64,
+    total_hash_micros: AtomicU64,
+    max_hash_micros: AtomicU64,
+}
+
+impl Counters {
+    fn record(&self, wait: Duration, hash: Duration) {
+        let hash_micros = hash.as_micros() as u64;
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_micros.fetch_add(wait.as_micros() as u64, Ordering::Relaxed);
+        self.total_hash_micros.fetch_add(hash_micros, Ordering::Relaxed);
+        self.max_hash_micros.fetch_max(hash_micros, Ordering::Relaxed);
+    }
+}
+
+/// Dedicated threads for password hashing. Argon2 is deliberately slow and
+/// memory hungry, so running it on the async workers would stall every other
+/// request they serve. The number of threads bounds how many hashes, and so
+/// how much hashing memory, can be in use at once. Jobs beyond that wait in a
+/// bounded queue; once it is full new jobs are rejected straight away, which
+/// surfaces as a 503 rather than letting the backlog grow without limit.
+pub struct HashingPool {
+    sender: SyncSender<Job>,
+    workers: usize,
+    queue_capacity: usize,
+    counters: Arc<Counters>,
+}
+
+impl HashingPool {
+    pub fn new(workers: usize, queue_capacity: usize) -> Self {
+        let (sender, receiver) = sync_channel::<Job>(queue_capacity);
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for index in 0..workers {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("password-hashing-{}", index))
+                .spawn(move || Self::work(receiver))
+                .expect("Failed to spawn password hashing thread");
+        }
+
+        Self {
+            sender,
+            workers,
+            queue_capacity,
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Sizes the pool from the environment, defaulting to one worker per CPU.
+    pub fn from_env() -> Self {
+        let default_workers = thread::available_parallelism().map_or(1, |count| count.get());
+        Self::new(
+            get_env_var_as_type_or_default(constants::SEC_HASHING_POOL_ENV_WORKERS, &default_workers),
+            get_env_var_as_type_or_default(constants::SEC_HASHING_POOL_ENV_QUEUE_SIZE, &constants::SEC_HASHING_POOL_QUEUE_SIZE_DEFAULT)
+        )
+    }
+
+    fn work(receiver: Arc<Mutex<Receiver<Job>>>) {
+        loop {
+            // The lock is only held while waiting for the next job, never while
+            // running one.
+            let job = match receiver.lock() {
+                Ok(receiver) => receiver.recv(),
+                Err(_) => return,
+            };
+            match job {
+                Ok(job) => job(),
+                Err(_) => return,
+            }
+        }
+    }
+
+    fn error(error_identifier: &str, message: &str) -> SecurityError {
+        SecurityError {
+            identifier: error_identifier.to_string(),
+            message: message.to_string(),
+            context: constants::ERR_CONTEXT_HASHING_POOL.to_string(),
+        }
+    }
+
+    /// Runs `job` on one of the hashing threads and waits for its result.
+    pub async fn run<T, F>(&self, job: F) -> Result<T, SecurityError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (result_sender, result_receiver) = oneshot::channel();
+        let counters = self.counters.clone();
+        let queued_at = Instant::now();
+
+        let task: Job = Box::new(move || {
+            counters.queue_depth.fetch_sub(1, Ordering::Relaxed);
+            counters.in_flight.fetch_add(1, Ordering::Relaxed);
+            let started_at = Instant::now();
+            let result = job();
+            let (wait, hash) = (started_at - queued_at, started_at.elapsed());
+            counters.in_flight.fetch_sub(1, Ordering::Relaxed);
+            counters.record(wait, hash);
+            debug!(wait_ms = wait.as_millis() as u64, hash_ms = hash.as_millis() as u64, "Password hashing job finished");
+            // The caller may have gone away in the meantime, which is fine.
+            let _ = result_sender.send(result);
+        });
+
+        self.counters.queue_depth.fetch_add(1, Ordering::Relaxed);
+        if let Err(err) = self.sender.try_send(task) {
+            self.counters.queue_depth.fetch_sub(1, Ordering::Relaxed);
+            return Err(match err {
+                TrySendError::Full(_) => {
+                    self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+                    Self::error(constants::SEC_ERR_HASHING_OVERLOADED, "Password hashing queue is full")
+                },
+                TrySendError::Disconnected(_) => Self::error(constants::SEC_ERR_HASH_FAILED, "Password hashing threads have stopped"),
+            });
+        }
+
+        result_receiver
+            .await
+            .map_err(|_| Self::error(constants::SEC_ERR_HASH_FAILED, "Password hashing job was dropped"))
+    }
+
+    pub fn metrics(&self) -> HashingPoolMetrics {
+        let counters = &self.counters;
+        let completed = counters.completed.load(Ordering::Relaxed);
+        let average_ms = |total_micros: u64| {
+            if completed == 0 { 0.0 } else { total_micros as f64 / completed as f64 / 1000.0 }
+        };
+
+        HashingPoolMetrics {
+            workers: self.workers,
+            queue_capacity: self.queue_capacity,
+            queue_depth: counters.queue_depth.load(Ordering::Relaxed),
+            in_flight: counters.in_flight.load(Ordering::Relaxed),
+            completed,
+            rejected: counters.rejected.load(Ordering::Relaxed),
+            avg_wait_ms: average_ms(counters.total_wait_micros.load(Ordering::Relaxed)),
+            avg_hash_ms: average_ms(counters.total_hash_micros.load(Ordering::Relaxed)),
+            max_hash_ms: counters.max_hash_micros.load(Ordering::Relaxed) as f64 / 1000.0,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use uuid::Uuid;
+
+use crate::domain::models::auth::{IssuedToken, TokenClaims};
+use crate::domain::models::common::{EncryptedTokenPayload, TokenType};
+use crate::services::{
+    concrete::secret_cipher::SecretCipher,
+    error::SecurityError,
+    utils::{
+        envutil::get_env_var_as_str,
+        envutil::get_env_var_as_type_or_default
+    }
+};
+use crate::services::traits::token::TokenService;
+use crate::services::constants;
+
+pub struct JwtTokenService {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    validation: Validation,
+    cipher: SecretCipher,
+    access_token_lifetime_secs: i64,
+    refresh_token_lifetime_secs: i64,
+    email_verification_token_lifetime_secs: i64,
+    mfa_challenge_token_lifetime_secs: i64,
+}
+
+impl JwtTokenService {
+    pub fn new() -> Self {
+        let secret = get_env_var_as_str(constants::SEC_JWT_ENV_SECRET).unwrap_or_else(|_| {
+            panic!("{} must be set in .env file", constants::SEC_JWT_ENV_SECRET);
+        });
+        let encryption_key = get_env_var_as_str(constants::SEC_JWT_ENV_ENCRYPTION_KEY)
+            .ok()
+            .and_then(|key| Self::decode_encryption_key(&key))
+            .unwrap_or_else(|| {
+                panic!("{} must be set to a base64 encoded {} byte key", constants::SEC_JWT_ENV_ENCRYPTION_KEY, constants::SEC_JWT_ENCRYPTION_KEY_LEN);
+            });
+
+        // Expiry lives inside the encrypted claims, so the outer JWT carries no
+        // registered claims and `exp` is checked once the payload is decrypted.
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.required_spec_claims.clear();
+        validation.validate_exp = false;
+
+        Self {
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            validation,
+            cipher: SecretCipher::new(&encryption_key),
+            access_token_lifetime_secs: Self::retrieve_access_token_lifetime_from_env(),
+            refresh_token_lifetime_secs: Self::retrieve_refresh_token_lifetime_from_env(),
+            email_verification_token_lifetime_secs: get_env_var_as_type_or_default(
+                constants::SEC_EMAIL_VERIFICATION_ENV_TOKEN_LIFETIME_SECS,
+                &constants::SEC_EMAIL_VERIFICATION_TOKEN_LIFETIME_SECS_DEFAULT
+            ),
+            mfa_challenge_token_lifetime_secs: get_env_var_as_type_or_default(
+                constants::SEC_MFA_ENV_CHALLENGE_TOKEN_LIFETIME_SECS,
+                &constants::SEC_MFA_CHALLENGE_TOKEN_LIFETIME_SECS_DEFAULT
+            ),
+        }
+    }
+
+    /// Decodes the base64 encoded payload encryption key. Returns `None` if the
+    /// value isn't valid base64 or doesn't have the length the cipher expects.
+    pub fn decode_encryption_key(value: &str) -> Option<Vec<u8>> {
+        STANDARD.decode(value.trim())
+            .ok()
+            .filter(|key| key.len() == constants::SEC_JWT_ENCRYPTION_KEY_LEN)
+    }
+
+    fn retrieve_access_token_lifetime_from_env() -> i64 {
+        get_env_var_as_type_or_default(constants::SEC_JWT_ENV_ACCESS_TOKEN_LIFETIME_SECS, &constants::SEC_JWT_ACCESS_TOKEN_LIFETIME_SECS_DEFAULT)
+    }
+
+    fn retrieve_refresh_token_lifetime_from_env() -> i64 {
+        get_env_var_as_type_or_default(constants::SEC_JWT_ENV_REFRESH_TOKEN_LIFETIME_SECS, &constants::SEC_JWT_REFRESH_TOKEN_LIFETIME_SECS_DEFAULT)
+    }
+
+    fn error(error_identifier: &str, message: &str) -> SecurityError {
+        SecurityError {
+            identifier: error_identifier.to_string(),
+            message: message.to_string(),
+            context: constants::ERR_CONTEXT_JWT_SERV.to_string(),
+        }
+    }
+
+    /// The token type is bound to the ciphertext as associated data, so a payload
+    /// can't be lifted from a refresh token and replayed as an access token.
+    fn associated_data(token_type: &TokenType) -> &'static [u8] {
+        match token_type {
+            TokenType::AccessToken => b"access_token",
+            TokenType::RefreshToken => b"refresh_token",
+            TokenType::EmailVerificationToken => b"email_verification_token",
+            TokenType::MfaChallengeToken => b"mfa_challenge_token",
+        }
+    }
+
+    fn encrypt_claims(&self, claims: &TokenClaims) -> Result<String, SecurityError> {
+        let plaintext = serde_json::to_vec(claims)
+            .map_err(|err| Self::error(constants::SEC_ERR_TOKEN_ENCRYPT, err.to_string().as_str()))?;
+        self.cipher.seal(&plaintext, Self::associated_data(&claims.token_type))
+            .map_err(|err| Self::error(constants::SEC_ERR_TOKEN_ENCRYPT, err.message.as_str()))
+    }
+
+    fn decrypt_claims(&self, payload: &EncryptedTokenPayload) -> Result<TokenClaims, SecurityError> {
+        let invalid = || Self::error(constants::SEC_ERR_TOKEN_INVALID, "Token payload could not be decrypted");
+
+        let plaintext = self.cipher.open(&payload.payload, Self::associated_data(&payload.token_type))
+            .map_err(|_| invalid())?;
+        serde_json::from_slice(&plaintext).map_err(|_| invalid())
+    }
+
+    fn generate_token(&self, user_id: Uuid, token_id: Uuid, token_type: TokenType, lifetime_secs: i64, mfa: bool) -> Result<IssuedToken, SecurityError> {
+        let issued_at = Utc::now().timestamp();
+        self.sign(TokenClaims {
+            sub: user_id.to_string(),
+            jti: token_id.to_string(),
+            token_type,
+            iat: issued_at,
+            exp: issued_at + lifetime_secs,
+            email: None,
+            mfa,
+        })
+    }
+
+    fn sign(&self, claims: TokenClaims) -> Result<IssuedToken, SecurityError> {
+        let payload = EncryptedTokenPayload {
+            token_type: claims.token_type.clone(),
+            payload: self.encrypt_claims(&claims)?,
+        };
+
+        let token = encode(&Header::new(Algorithm::HS256), &payload, &self.encoding_key)
+            .map_err(|err| Self::error(constants::SEC_ERR_TOKEN_ENCODE, err.to_string().as_str()))?;
+        Ok(IssuedToken { token, claims })
+    }
+
+    fn validate_token(&self, token: &str, expected_type: TokenType) -> Result<TokenClaims, SecurityError> {
+        let payload = decode::<EncryptedTokenPayload>(token, &self.decoding_key, &self.validation)
+            .map_err(|err| Self::error(constants::SEC_ERR_TOKEN_INVALID, err.to_string().as_str()))?
+            .claims;
+
+        if payload.token_type != expected_type {
+            return Err(Self::error(constants::SEC_ERR_TOKEN_TYPE_MISMATCH, "Token was not issued for this purpose"));
+        }
+
+        let claims = self.decrypt_claims(&payload)?;
+        if claims.token_type != expected_type {
+            return Err(Self::error(constants::SEC_ERR_TOKEN_TYPE_MISMATCH, "Token was not issued for this purpose"));
+        }
+        if claims.exp <= Utc::now().timestamp() {
+            return Err(Self::error(constants::SEC_ERR_TOKEN_EXPIRED, "Token has expired"));
+        }
+
+        Ok(claims)
+    }
+}
+
+impl Default for JwtTokenService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenService for JwtTokenService {
+    fn generate_access_token(&self, user_id: Uuid, token_id: Uuid, mfa: bool) -> Result<IssuedToken, SecurityError> {
+        self.generate_token(user_id, token_id, TokenType::AccessToken, self.access_token_lifetime_secs, mfa)
+    }
+
+    fn generate_refresh_token(&self, user_id: Uuid, token_id: Uuid, mfa: bool) -> Result<IssuedToken, SecurityError> {
+        self.generate_token(user_id, token_id, TokenType::RefreshToken, self.refresh_token_lifetime_secs, mfa)
+    }
+
+    fn validate_access_token(&self, token: &str) -> Result<TokenClaims, SecurityError> {
+        self.validate_token(token, TokenType::AccessToken)
+    }
+
+    fn access_token_lifetime_secs(&self) -> i64 {
+        self.access_token_lifetime_secs
+    }
+
+    fn validate_refresh_token(&self, token: &str) -> Result<TokenClaims, SecurityError> {
+        self.validate_token(token, TokenType::RefreshToken)
+    }
+
+    fn generate_email_verification_token(&self, user_id: Uuid, email: &str) -> Result<IssuedToken, SecurityError> {
+        let issued_at = Utc::now().timestamp();
+        self.sign(TokenClaims {
+            sub: user_id.to_string(),
+            jti: Uuid::new_v4().to_string(),
+            token_type: TokenType::EmailVerificationToken,
+            iat: issued_at,
+            exp: issued_at + self.email_verification_token_lifetime_secs,
+            email: Some(email.to_string()),
+            mfa: false,
+        })
+    }
+
+    fn validate_email_verification_token(&self, token: &str) -> Result<TokenClaims, SecurityError> {
+        self.validate_token(token, TokenType::EmailVerificationToken)
+    }
+
+    fn generate_mfa_challenge_token(&self, user_id: Uuid) -> Result<IssuedToken, SecurityError> {
+        self.generate_token(user_id, Uuid::new_v4(), TokenType::MfaChallengeToken, self.mfa_challenge_token_lifetime_secs, false)
+    }
+
+    fn validate_mfa_challenge_token(&self, token: &str) -> Result<TokenClaims, SecurityError> {
+        self.validate_token(token, TokenType::MfaChallengeToken)
+    }
+}
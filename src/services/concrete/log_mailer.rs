@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::services::error::MailerError;
+use crate::services::traits::mailer::{MailMessage, MailerService};
+
+/// Stand-in mailer that writes outgoing messages to the log instead of
+/// delivering them. Meant for development until a real transport is wired in.
+#[derive(Default)]
+pub struct LogMailerService;
+
+impl LogMailerService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl MailerService for LogMailerService {
+    async fn send(&self, message: &MailMessage) -> Result<(), MailerError> {
+        info!(to = %message.to, subject = %message.subject, "Outgoing mail:\n{}", message.body);
+        Ok(())
+    }
+}
@@ -1 +1,9 @@
-pub mod argon2id_hash;
\ No newline at end of file
+pub mod argon2id_hash;
+pub mod composite_hash;
+pub mod file_mailer;
+pub mod hashing_pool;
+pub mod jwt_token;
+pub mod log_mailer;
+pub mod password_policy;
+pub mod pepper;
+pub mod secret_cipher;
\ No newline at end of file
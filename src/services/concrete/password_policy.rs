@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use crate::domain::models::common::ErrorItem;
+use crate::services::constants;
+use crate::services::traits::password_policy::{PasswordOwner, PasswordPolicyService};
+use crate::services::utils::envutil::{get_env_var_as_str, get_env_var_as_type_or_default};
+
+/// Common and breached passwords shipped with the server.
+const BUNDLED_BLOCKLIST: &str = include_str!("../../../assets/common_passwords.txt");
+
+/// Shortest name or email part that counts as personal information. Shorter
+/// parts would reject too many unrelated passwords.
+const MIN_PERSONAL_PART_LEN: usize = 3;
+
+/// Password rules configured through the `PASSWORD_*` variables: length
+/// bounds, a blocklist of common and breached passwords, and no passwords made
+/// from the owner's name or email.
+pub struct ConfigurablePasswordPolicy {
+    min_length: usize,
+    max_length: usize,
+    blocklist: HashSet<String>,
+}
+
+impl ConfigurablePasswordPolicy {
+    pub fn new(min_length: usize, max_length: usize, blocklist: HashSet<String>) -> Self {
+        Self { min_length, max_length, blocklist }
+    }
+
+    /// Uses the bundled blocklist, extended with the one in
+    /// `PASSWORD_BLOCKLIST_FILE` if it's set.
+    pub fn from_env() -> Self {
+        let mut blocklist = Self::parse_blocklist(BUNDLED_BLOCKLIST);
+        if let Ok(path) = get_env_var_as_str(constants::SEC_PASSWORD_ENV_BLOCKLIST_FILE) {
+            let contents = std::fs::read_to_string(&path)
+                .unwrap_or_else(|err| panic!("Could not read {}: {}", path, err));
+            blocklist.extend(Self::parse_blocklist(&contents));
+        }
+
+        Self::new(
+            get_env_var_as_type_or_default(constants::SEC_PASSWORD_ENV_MIN_LENGTH, &constants::SEC_PASSWORD_MIN_LENGTH_DEFAULT),
+            get_env_var_as_type_or_default(constants::SEC_PASSWORD_ENV_MAX_LENGTH, &constants::SEC_PASSWORD_MAX_LENGTH_DEFAULT),
+            blocklist
+        )
+    }
+
+    /// One password per line; blank lines and `#` comments are skipped.
+    pub fn parse_blocklist(contents: &str) -> HashSet<String> {
+        contents.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    /// Lowercased parts of the name and email a password must not contain.
+    fn personal_parts(owner: &PasswordOwner) -> Vec<String> {
+        let email = owner.email.trim().to_lowercase();
+        let local_part = email.split('@').next().unwrap_or_default().to_string();
+        let name = owner.name.trim().to_lowercase();
+
+        let mut parts = vec![email.clone(), local_part.clone(), name.split_whitespace().collect()];
+        parts.extend(local_part.split(|c: char| !c.is_alphanumeric()).map(str::to_string));
+        parts.extend(name.split(|c: char| !c.is_alphanumeric()).map(str::to_string));
+        parts.retain(|part| part.chars().count() >= MIN_PERSONAL_PART_LEN);
+        parts
+    }
+}
+
+impl PasswordPolicyService for ConfigurablePasswordPolicy {
+    fn violations(&self, password: &str, owner: &PasswordOwner) -> Vec<ErrorItem> {
+        let mut errors = Vec::new();
+        let mut add = |identifier: &str, message: String| errors.push(ErrorItem::new("password", identifier, message));
+        let length = password.chars().count();
+        let lowercase = password.to_lowercase();
+
+        if length < self.min_length {
+            add(constants::VAL_ERR_PASSWORD_TOO_SHORT, std::format!("`password` must be at least {} characters long", self.min_length));
+        }
+        if length > self.max_length {
+            add(constants::VAL_ERR_PASSWORD_TOO_LONG, std::format!("`password` must be at most {} characters long", self.max_length));
+        }
+        if self.blocklist.contains(lowercase.trim()) {
+            add(constants::VAL_ERR_PASSWORD_TOO_COMMON, "`password` is too common or has appeared in a data breach".to_string());
+        }
+        if Self::personal_parts(owner).iter().any(|part| lowercase.contains(part.as_str())) {
+            add(constants::VAL_ERR_PASSWORD_PERSONAL, "`password` must not contain your name or email".to_string());
+        }
+        errors
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
\ No newline at end of file
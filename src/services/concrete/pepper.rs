@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::services::constants;
+use crate::services::utils::envutil::get_env_var_as_str;
+
+/// Server-side secrets mixed into password hashes, by version. A database dump
+/// is useless for offline cracking without them.
+///
+/// Peppers come from `PASSWORD_PEPPER` or from the file named by
+/// `PASSWORD_PEPPER_FILE`, as entries of `<version>:<pepper>` separated by
+/// commas or newlines; a lone pepper without a version is version 1. New hashes
+/// use the highest version and record it, so a pepper is rotated by adding a
+/// higher version while keeping the old one until every user has signed in
+/// again and been rehashed.
+#[derive(Clone, Default)]
+pub struct PepperRing {
+    peppers: BTreeMap<u32, Arc<[u8]>>,
+}
+
+impl PepperRing {
+    /// Reads the peppers from the environment. No pepper configured gives an
+    /// empty ring, in which case hashes are made without a secret.
+    pub fn from_env() -> Self {
+        let source = match Self::source_from_env() {
+            Ok(source) => source,
+            Err(message) => panic!("{}", message),
+        };
+        source.map_or_else(PepperRing::default, |source| {
+            Self::parse(&source).unwrap_or_else(|message| panic!("Invalid password pepper: {}", message))
+        })
+    }
+
+    /// The configured peppers in their textual form, if any.
+    pub fn source_from_env() -> Result<Option<String>, String> {
+        let inline = get_env_var_as_str(constants::SEC_PEPPER_ENV_VALUE).ok();
+        let file = get_env_var_as_str(constants::SEC_PEPPER_ENV_FILE).ok();
+
+        match (inline, file) {
+            (Some(_), Some(_)) => Err(std::format!(
+                "Only one of {} and {} may be set", constants::SEC_PEPPER_ENV_VALUE, constants::SEC_PEPPER_ENV_FILE
+            )),
+            (Some(inline), None) => Ok(Some(inline)),
+            (None, Some(path)) => std::fs::read_to_string(&path)
+                .map(Some)
+                .map_err(|err| std::format!("Could not read {}: {}", path, err)),
+            (None, None) => Ok(None),
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut peppers = BTreeMap::new();
+        let entries = source.split([',', '\n'])
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty() && !entry.starts_with('#'));
+
+        for entry in entries {
+            let (version, pepper) = match entry.split_once(':') {
+                Some((version, pepper)) => match version.parse::<u32>() {
+                    Ok(version) if version > 0 => (version, pepper),
+                    _ => return Err(std::format!("`{}` is not a valid pepper version", version)),
+                },
+                None => (1, entry),
+            };
+            if pepper.len() < constants::SEC_PEPPER_MIN_LEN {
+                return Err(std::format!(
+                    "pepper version {} must be at least {} bytes long", version, constants::SEC_PEPPER_MIN_LEN
+                ));
+            }
+            if peppers.insert(version, Arc::from(pepper.as_bytes())).is_some() {
+                return Err(std::format!("pepper version {} is defined more than once", version));
+            }
+        }
+
+        Ok(PepperRing { peppers })
+    }
+
+    /// The version and pepper new hashes are made with.
+    pub fn current(&self) -> Option<(u32, Arc<[u8]>)> {
+        self.peppers.last_key_value().map(|(version, pepper)| (*version, pepper.clone()))
+    }
+
+    pub fn get(&self, version: u32) -> Option<Arc<[u8]>> {
+        self.peppers.get(&version).cloned()
+    }
+
+    pub fn contains(&self, pepper: &[u8]) -> bool {
+        self.peppers.values().any(|value| value.as_ref() == pepper)
+    }
+}
+
+/// Lists only the versions, so that logging a ring never prints a pepper.
+impl fmt::Debug for PepperRing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PepperRing").field("versions", &self.peppers.keys().collect::<Vec<_>>()).finish()
+    }
+}
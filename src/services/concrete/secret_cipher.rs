@@ -0,0 +1,81 @@
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    ChaCha20Poly1305,
+    Nonce
+};
+
+use crate::services::constants;
+use crate::services::error::SecurityError;
+use crate::services::utils::envutil::get_env_var_as_str;
+
+const NONCE_LEN: usize = 12;
+
+/// Encrypts secrets the server has to read back, such as TOTP secrets, which
+/// unlike passwords can't be stored as a hash, and the claims inside tokens.
+/// Sealed values are the random nonce followed by the ChaCha20-Poly1305
+/// ciphertext, base64url encoded. The associated data binds a sealed value to
+/// what it belongs to, so it can't be copied to another row or token type.
+pub struct SecretCipher {
+    cipher: ChaCha20Poly1305,
+}
+
+impl SecretCipher {
+    /// Panics unless `key` is 32 bytes long.
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new_from_slice(key).expect("Secret cipher key has the wrong length"),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let key = get_env_var_as_str(constants::SEC_MFA_ENV_ENCRYPTION_KEY)
+            .ok()
+            .and_then(|key| Self::decode_key(&key))
+            .unwrap_or_else(|| {
+                panic!("{} must be set to a base64 encoded {} byte key", constants::SEC_MFA_ENV_ENCRYPTION_KEY, constants::SEC_MFA_ENCRYPTION_KEY_LEN);
+            });
+        Self::new(&key)
+    }
+
+    /// Decodes a base64 encoded key. Returns `None` if the value isn't valid
+    /// base64 or doesn't have the length the cipher expects.
+    pub fn decode_key(value: &str) -> Option<Vec<u8>> {
+        STANDARD.decode(value.trim())
+            .ok()
+            .filter(|key| key.len() == constants::SEC_MFA_ENCRYPTION_KEY_LEN)
+    }
+
+    fn error(error_identifier: &str, message: &str) -> SecurityError {
+        SecurityError {
+            identifier: error_identifier.to_string(),
+            message: message.to_string(),
+            context: constants::ERR_CONTEXT_SECRET_CIPHER.to_string(),
+        }
+    }
+
+    pub fn seal(&self, plaintext: &[u8], associated_data: &[u8]) -> Result<String, SecurityError> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad: associated_data })
+            .map_err(|err| Self::error(constants::SEC_ERR_SECRET_ENCRYPT, err.to_string().as_str()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(URL_SAFE_NO_PAD.encode(sealed))
+    }
+
+    pub fn open(&self, sealed: &str, associated_data: &[u8]) -> Result<Vec<u8>, SecurityError> {
+        let invalid = || Self::error(constants::SEC_ERR_SECRET_DECRYPT, "Secret could not be decrypted");
+
+        let sealed = URL_SAFE_NO_PAD.decode(sealed).map_err(|_| invalid())?;
+        if sealed.len() <= NONCE_LEN {
+            return Err(invalid());
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: associated_data })
+            .map_err(|_| invalid())
+    }
+}
@@ -7,16 +7,138 @@ pub const SEC_ARGON2ID_MEMORY_SIZE_MB_DEFAULT: u32 = 65536;
 pub const SEC_ARGON2ID_NUM_ITERATIONS_DEFAULT: u32 = 3;
 pub const SEC_ARGON2ID_NUM_THREADS_DEFAULT: u32 = 4;
 pub const SEC_ARGON2ID_OUTPUT_LEN_DEFAULT: usize = 32;
+pub const SEC_ARGON2_MAX_M_COST: u32 = 262_144;
+pub const SEC_ARGON2_MAX_T_COST: u32 = 16;
+pub const SEC_ARGON2_MAX_P_COST: u32 = 16;
+
+pub const SEC_HASHING_POOL_ENV_WORKERS: &str = "HASHING_POOL_WORKERS";
+pub const SEC_HASHING_POOL_ENV_QUEUE_SIZE: &str = "HASHING_POOL_QUEUE_SIZE";
+
+pub const SEC_HASHING_POOL_QUEUE_SIZE_DEFAULT: usize = 32;
+
+pub const SEC_JWT_ENV_SECRET: &str = "JWT_SECRET";
+pub const SEC_JWT_ENV_ENCRYPTION_KEY: &str = "JWT_ENCRYPTION_KEY";
+pub const SEC_JWT_ENV_ACCESS_TOKEN_LIFETIME_SECS: &str = "JWT_ACCESS_TOKEN_LIFETIME_SECS";
+pub const SEC_JWT_ENV_REFRESH_TOKEN_LIFETIME_SECS: &str = "JWT_REFRESH_TOKEN_LIFETIME_SECS";
+
+pub const SEC_JWT_ACCESS_TOKEN_LIFETIME_SECS_DEFAULT: i64 = 900;
+pub const SEC_JWT_REFRESH_TOKEN_LIFETIME_SECS_DEFAULT: i64 = 1_209_600;
+pub const SEC_TOKEN_PRUNE_ENV_INTERVAL_SECS: &str = "TOKEN_PRUNE_INTERVAL_SECS";
+pub const SEC_TOKEN_PRUNE_INTERVAL_SECS_DEFAULT: u64 = 3600;
+pub const SEC_PASSWORD_RESET_ENV_TOKEN_LIFETIME_SECS: &str = "PASSWORD_RESET_TOKEN_LIFETIME_SECS";
+pub const SEC_PASSWORD_RESET_TOKEN_LIFETIME_SECS_DEFAULT: i64 = 3600;
+pub const SEC_PASSWORD_RESET_TOKEN_BYTES: usize = 32;
+pub const SEC_EMAIL_VERIFICATION_ENV_TOKEN_LIFETIME_SECS: &str = "EMAIL_VERIFICATION_TOKEN_LIFETIME_SECS";
+pub const SEC_EMAIL_VERIFICATION_ENV_RESEND_COOLDOWN_SECS: &str = "EMAIL_VERIFICATION_RESEND_COOLDOWN_SECS";
+pub const SEC_EMAIL_VERIFICATION_ENV_REQUIRED_FOR: &str = "EMAIL_VERIFICATION_REQUIRED_FOR";
+pub const SEC_EMAIL_VERIFICATION_TOKEN_LIFETIME_SECS_DEFAULT: i64 = 86_400;
+pub const SEC_EMAIL_VERIFICATION_RESEND_COOLDOWN_SECS_DEFAULT: i64 = 120;
+
+pub const SEC_MFA_ENV_ENCRYPTION_KEY: &str = "MFA_ENCRYPTION_KEY";
+pub const SEC_MFA_ENV_REQUIRED_ROLES: &str = "MFA_REQUIRED_ROLES";
+pub const SEC_MFA_ENV_ISSUER: &str = "MFA_ISSUER";
+pub const SEC_MFA_ENV_SKEW_STEPS: &str = "MFA_SKEW_STEPS";
+pub const SEC_MFA_ENV_CHALLENGE_TOKEN_LIFETIME_SECS: &str = "MFA_CHALLENGE_TOKEN_LIFETIME_SECS";
+pub const SEC_MFA_ISSUER_DEFAULT: &str = "Iron CMS";
+pub const SEC_MFA_SKEW_STEPS_DEFAULT: u32 = 1;
+pub const SEC_MFA_CHALLENGE_TOKEN_LIFETIME_SECS_DEFAULT: i64 = 300;
+pub const SEC_MFA_ENCRYPTION_KEY_LEN: usize = 32;
+pub const SEC_MFA_RECOVERY_CODE_COUNT: usize = 10;
+pub const SEC_MFA_RECOVERY_CODE_BYTES: usize = 10;
+
+pub const SEC_TOTP_SECRET_BYTES: usize = 20;
+pub const SEC_TOTP_DIGITS: u32 = 6;
+pub const SEC_TOTP_PERIOD_SECS: i64 = 30;
+
+pub const SEC_PEPPER_ENV_VALUE: &str = "PASSWORD_PEPPER";
+pub const SEC_PEPPER_ENV_FILE: &str = "PASSWORD_PEPPER_FILE";
+pub const SEC_PEPPER_MIN_LEN: usize = 32;
+
+pub const SEC_PASSWORD_ENV_MIN_LENGTH: &str = "PASSWORD_MIN_LENGTH";
+pub const SEC_PASSWORD_ENV_MAX_LENGTH: &str = "PASSWORD_MAX_LENGTH";
+pub const SEC_PASSWORD_ENV_BLOCKLIST_FILE: &str = "PASSWORD_BLOCKLIST_FILE";
+
+pub const SEC_PASSWORD_MIN_LENGTH_DEFAULT: usize = 8;
+pub const SEC_PASSWORD_MAX_LENGTH_DEFAULT: usize = 128;
+
+pub const SEC_PBKDF2_SHA256_PREFIX: &str = "$pbkdf2-sha256$";
+pub const SEC_BCRYPT_MAX_COST: u32 = 14;
+pub const SEC_PBKDF2_MAX_ROUNDS: u32 = 1_000_000;
+
+pub const SEC_JWT_SECRET_MIN_LEN: usize = 32;
+pub const SEC_JWT_ENCRYPTION_KEY_LEN: usize = 32;
 
 pub const SEC_ERR_HASH_PARSE_FAIL: &str = "password_hashing_error";
 pub const SEC_ERR_PASS_VERIFY: &str = "password_verification_error";
 pub const SEC_ERR_HASH_FAILED: &str = "password_hashing_failed";
 pub const SEC_ERR_PASS_NOT_MATCH: &str = "passwords_do_not_match";
 pub const SEC_ERR_AUTHENTICATION: &str = "authentication_error";
+pub const SEC_ERR_INVALID_CREDENTIALS: &str = "invalid_credentials";
+pub const SEC_ERR_TOKEN_ENCODE: &str = "token_encoding_failed";
+pub const SEC_ERR_TOKEN_ENCRYPT: &str = "token_encryption_failed";
+pub const SEC_ERR_TOKEN_INVALID: &str = "invalid_token";
+pub const SEC_ERR_TOKEN_EXPIRED: &str = "token_expired";
+pub const SEC_ERR_TOKEN_TYPE_MISMATCH: &str = "token_type_mismatch";
+pub const SEC_ERR_SESSION_INVALID: &str = "invalid_session";
+pub const SEC_ERR_TOKEN_REVOKED: &str = "token_revoked";
+pub const SEC_ERR_TOKEN_MISSING: &str = "missing_token";
+pub const SEC_ERR_PERMISSION_DENIED: &str = "permission_denied";
+pub const SEC_ERR_RESET_TOKEN_INVALID: &str = "invalid_reset_token";
+pub const SEC_ERR_HASHING_OVERLOADED: &str = "hashing_overloaded";
+pub const SEC_ERR_PEPPER_UNKNOWN: &str = "password_pepper_unknown";
+pub const SEC_ERR_EMAIL_NOT_VERIFIED: &str = "email_not_verified";
+pub const SEC_ERR_VERIFICATION_TOKEN_INVALID: &str = "invalid_verification_token";
+pub const SEC_ERR_MFA_CODE_INVALID: &str = "invalid_mfa_code";
+pub const SEC_ERR_MFA_CHALLENGE_INVALID: &str = "invalid_mfa_challenge";
+pub const SEC_ERR_MFA_REQUIRED: &str = "mfa_required";
+pub const SEC_ERR_MFA_ALREADY_ENABLED: &str = "mfa_already_enabled";
+pub const SEC_ERR_MFA_NOT_ENABLED: &str = "mfa_not_enabled";
+pub const SEC_ERR_SECRET_ENCRYPT: &str = "secret_encryption_failed";
+pub const SEC_ERR_SECRET_DECRYPT: &str = "secret_decryption_failed";
+
+pub const USER_ERR_EMAIL_TAKEN: &str = "email_already_registered";
+pub const USER_IMPORT_MAX_USERS: usize = 1000;
+pub const ADMIN_PERMISSION_ERR_USER_NOT_FOUND: &str = "permission_user_not_found";
 
+pub const VAL_ERR_VALIDATION_FAILED: &str = "validation_failed";
+pub const VAL_ERR_FIELD_REQUIRED: &str = "field_required";
+pub const VAL_ERR_FIELD_TOO_LONG: &str = "field_too_long";
+pub const VAL_ERR_INVALID_EMAIL: &str = "invalid_email";
+pub const VAL_ERR_INVALID_UUID: &str = "invalid_uuid";
+pub const VAL_ERR_INVALID_ENUM_VALUE: &str = "invalid_enum_value";
+pub const VAL_ERR_INVALID_JSON: &str = "invalid_json";
+pub const VAL_ERR_INVALID_PATH: &str = "invalid_path";
+pub const VAL_ERR_INVALID_QUERY: &str = "invalid_query";
+pub const VAL_ERR_UNSUPPORTED_PASSWORD_HASH: &str = "unsupported_password_hash";
+pub const VAL_ERR_TOO_MANY_ITEMS: &str = "too_many_items";
+pub const VAL_ERR_PASSWORD_TOO_SHORT: &str = "password_too_short";
+pub const VAL_ERR_PASSWORD_TOO_LONG: &str = "password_too_long";
+pub const VAL_ERR_PASSWORD_TOO_COMMON: &str = "password_too_common";
+pub const VAL_ERR_PASSWORD_PERSONAL: &str = "password_contains_personal_info";
+
+pub const MAIL_ENV_TRANSPORT: &str = "MAIL_TRANSPORT";
+pub const MAIL_ENV_FILE_DIR: &str = "MAIL_FILE_DIR";
+pub const MAIL_TRANSPORT_LOG: &str = "log";
+pub const MAIL_TRANSPORT_FILE: &str = "file";
+
+pub const MAIL_ERR_DELIVERY_FAILED: &str = "mail_delivery_failed";
+
+pub const ENV_ERR_VARIABLE_MISSING: &str = "env_variable_missing";
 pub const ENV_ERR_PARSE_FAIL: &str = "env_parse_error";
 
 pub const ERR_CONTEXT_ARGON2ID_SERV: &str = "argon2id_hash_service";
+pub const ERR_CONTEXT_HASHING_POOL: &str = "hashing_pool";
+pub const ERR_CONTEXT_COMPOSITE_HASH_SERV: &str = "composite_hash_service";
 pub const ERR_CONTEXT_LOGIN: &str = "login";
+pub const ERR_CONTEXT_REFRESH: &str = "refresh";
+pub const ERR_CONTEXT_AUTHENTICATION: &str = "authentication";
+pub const ERR_CONTEXT_AUTHORIZATION: &str = "authorization";
+pub const ERR_CONTEXT_PASSWORD_RESET: &str = "password_reset";
+pub const ERR_CONTEXT_EMAIL_VERIFICATION: &str = "email_verification";
+pub const ERR_CONTEXT_FILE_MAILER: &str = "file_mailer";
+pub const ERR_CONTEXT_MFA: &str = "mfa";
+pub const ERR_CONTEXT_SECRET_CIPHER: &str = "secret_cipher";
+pub const ERR_CONTEXT_USER_SERV: &str = "user_service";
+pub const ERR_CONTEXT_ADMIN_PERMISSION_SERV: &str = "admin_permission_service";
+pub const ERR_CONTEXT_JWT_SERV: &str = "jwt_token_service";
 pub const ERR_CONTEXT_ENV: &str = "environment";
-
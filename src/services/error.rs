@@ -1,20 +1,18 @@
 use serde::Serialize;
 
 use crate::domain::error::CommonError;
-use crate::error_codes::{
-    ERR_CODE_SECURITY,
-    ERR_CODE_ENV_VARIABLE
-};
+use crate::domain::models::common::ErrorResponseType;
 
 #[derive(Debug, Serialize)]
 pub struct SecurityError {
+    pub identifier: String,
     pub message: String,
     pub context: String,
 }
 
 impl std::fmt::Display for SecurityError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "SecurityError: {}, Context: {}", self.message, self.context)
+        write!(f, "SecurityError: {} - {}, Context: {}", self.identifier, self.message, self.context)
     }
 }
 
@@ -22,22 +20,83 @@ impl std::error::Error for SecurityError { }
 
 impl From<SecurityError> for CommonError {
     fn from(val: SecurityError) -> Self {
-        CommonError {
-            message: std::format!("{}", val),
-            code: ERR_CODE_SECURITY,
-        }
+        CommonError::new(&val.identifier, val.message, val.context, ErrorResponseType::General)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuthError {
+    pub identifier: String,
+    pub message: String,
+    pub context: String,
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AuthError: {} - {}, Context: {}", self.identifier, self.message, self.context)
+    }
+}
+
+impl std::error::Error for AuthError { }
+
+impl From<AuthError> for CommonError {
+    fn from(val: AuthError) -> Self {
+        CommonError::new(&val.identifier, val.message, val.context, ErrorResponseType::Unauthorized)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PermissionError {
+    pub identifier: String,
+    pub message: String,
+    pub context: String,
+}
+
+impl std::fmt::Display for PermissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PermissionError: {} - {}, Context: {}", self.identifier, self.message, self.context)
+    }
+}
+
+impl std::error::Error for PermissionError { }
+
+impl From<PermissionError> for CommonError {
+    fn from(val: PermissionError) -> Self {
+        CommonError::new(&val.identifier, val.message, val.context, ErrorResponseType::Forbidden)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MailerError {
+    pub identifier: String,
+    pub message: String,
+    pub context: String,
+}
+
+impl std::fmt::Display for MailerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MailerError: {} - {}, Context: {}", self.identifier, self.message, self.context)
+    }
+}
+
+impl std::error::Error for MailerError { }
+
+impl From<MailerError> for CommonError {
+    fn from(val: MailerError) -> Self {
+        CommonError::new(&val.identifier, val.message, val.context, ErrorResponseType::InternalServerError)
     }
 }
 
 #[derive(Debug, Serialize)]
 pub struct EnvVariableError {
+    pub identifier: String,
     pub message: String,
     pub variable_name: String,
 }
 
 impl std::fmt::Display for EnvVariableError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "EnvVariableError: {}, Variable: {}", self.message, self.variable_name)
+        write!(f, "EnvVariableError: {} - {}, Variable: {}", self.identifier, self.message, self.variable_name)
     }
 }
 
@@ -45,9 +104,6 @@ impl std::error::Error for EnvVariableError { }
 
 impl From<EnvVariableError> for CommonError {
     fn from(val: EnvVariableError) -> Self {
-        CommonError {
-            message: std::format!("{}", val),
-            code: ERR_CODE_ENV_VARIABLE,
-        }
+        CommonError::new(&val.identifier, val.message, val.variable_name, ErrorResponseType::InternalServerError)
     }
 }
\ No newline at end of file
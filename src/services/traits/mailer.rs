@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+
+use crate::services::error::MailerError;
+
+#[derive(Clone, Debug)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait MailerService: Send + Sync {
+    async fn send(&self, message: &MailMessage) -> Result<(), MailerError>;
+}
@@ -1 +1,4 @@
-pub mod password_hash;
\ No newline at end of file
+pub mod mailer;
+pub mod password_hash;
+pub mod password_policy;
+pub mod token;
\ No newline at end of file
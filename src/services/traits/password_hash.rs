@@ -1,6 +1,14 @@
+use async_trait::async_trait;
+
 use crate::services::error::SecurityError;
 
+#[async_trait]
 pub trait PasswordHashService: Send + Sync {
-    fn hash_password(&self, password: &str) -> Result<String, SecurityError>;
-    fn verify_password(&self, password: &str, hash: &str) -> Result<bool, SecurityError>;
+    async fn hash_password(&self, password: &str) -> Result<String, SecurityError>;
+    async fn verify_password(&self, password: &str, hash: &str) -> Result<bool, SecurityError>;
+    /// Whether a stored hash was made with another algorithm or other parameters
+    /// than the ones currently configured, and should be replaced.
+    fn needs_rehash(&self, hash: &str) -> bool;
+    /// Whether a hash is in a format this service can verify.
+    fn is_supported(&self, hash: &str) -> bool;
 }
\ No newline at end of file
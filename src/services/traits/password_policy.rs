@@ -0,0 +1,36 @@
+use crate::domain::error::CommonError;
+use crate::domain::models::common::ErrorItem;
+use crate::services::constants;
+
+/// Details of the account a password is chosen for, so the policy can reject
+/// passwords that are easy to guess from them.
+pub struct PasswordOwner<'a> {
+    pub name: &'a str,
+    pub email: &'a str,
+}
+
+pub trait PasswordPolicyService: Send + Sync {
+    /// Every rule the password breaks, reported against the `password` field.
+    fn violations(&self, password: &str, owner: &PasswordOwner) -> Vec<ErrorItem>;
+
+    /// Longest password accepted, in characters. Anything longer is refused
+    /// without being hashed.
+    fn max_length(&self) -> usize;
+
+    /// Checks a newly chosen password and its confirmation, reporting every
+    /// problem at once.
+    fn check(&self, password: &str, confirm_password: &str, owner: &PasswordOwner) -> Result<(), CommonError> {
+        let mut errors = self.violations(password, owner);
+        if password != confirm_password {
+            errors.push(ErrorItem::new(
+                "confirm_password",
+                constants::SEC_ERR_PASS_NOT_MATCH,
+                "`password` and `confirm_password` fields do not match".to_string()
+            ));
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(CommonError::validation(errors))
+    }
+}
\ No newline at end of file
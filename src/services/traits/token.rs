@@ -0,0 +1,19 @@
+use uuid::Uuid;
+
+use crate::domain::models::auth::{IssuedToken, TokenClaims};
+use crate::services::error::SecurityError;
+
+pub trait TokenService: Send + Sync {
+    /// `mfa` tells whether the user passed a second factor, see `TokenClaims`.
+    fn generate_access_token(&self, user_id: Uuid, token_id: Uuid, mfa: bool) -> Result<IssuedToken, SecurityError>;
+    fn generate_refresh_token(&self, user_id: Uuid, token_id: Uuid, mfa: bool) -> Result<IssuedToken, SecurityError>;
+    fn validate_access_token(&self, token: &str) -> Result<TokenClaims, SecurityError>;
+    /// How long an access token stays valid after it was issued.
+    fn access_token_lifetime_secs(&self) -> i64;
+    fn validate_refresh_token(&self, token: &str) -> Result<TokenClaims, SecurityError>;
+    fn generate_email_verification_token(&self, user_id: Uuid, email: &str) -> Result<IssuedToken, SecurityError>;
+    fn validate_email_verification_token(&self, token: &str) -> Result<TokenClaims, SecurityError>;
+    /// Proves the password was right while the second factor is still asked for.
+    fn generate_mfa_challenge_token(&self, user_id: Uuid) -> Result<IssuedToken, SecurityError>;
+    fn validate_mfa_challenge_token(&self, token: &str) -> Result<TokenClaims, SecurityError>;
+}
@@ -0,0 +1,7 @@
+use sha2::{Digest, Sha256};
+
+/// Hex encoded SHA-256 digest, used to store tokens without keeping them in a
+/// form that could be presented back to the API.
+pub fn sha256_hex(value: &str) -> String {
+    format!("{:x}", Sha256::digest(value.as_bytes()))
+}
\ No newline at end of file
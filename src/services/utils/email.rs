@@ -0,0 +1,16 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// The form emails are stored and looked up in. Surrounding whitespace is
+/// dropped, both parts are put in Unicode NFC and the domain is lowercased.
+/// The local part keeps its case, since mail servers may treat it as
+/// significant; uniqueness ignores case instead. Domains stay in the form they
+/// were given, `xn--` labels included. The migration to case-insensitive emails
+/// put the existing rows in this same form.
+pub fn normalize_email(email: &str) -> String {
+    let email = email.trim();
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return email.nfc().collect();
+    };
+
+    format!("{}@{}", local.nfc().collect::<String>(), domain.nfc().collect::<String>().to_lowercase())
+}
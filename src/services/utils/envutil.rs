@@ -2,12 +2,13 @@ use std::env;
 use std::str;
 
 use crate::services::error::EnvVariableError;
-use crate::services::constants::ENV_ERR_PARSE_FAIL;
+use crate::services::constants::{ENV_ERR_PARSE_FAIL, ENV_ERR_VARIABLE_MISSING};
 
 pub fn get_env_var_as_str(var_name: &str) -> Result<String, EnvVariableError> {
     match env::var(var_name) {
         Ok(val) => Ok(val),
         Err(err) => Err(EnvVariableError {
+            identifier: ENV_ERR_VARIABLE_MISSING.to_string(),
             message: err.to_string(),
             variable_name: var_name.to_string(),
         }),
@@ -19,7 +20,8 @@ pub fn get_env_var_as_type<T: std::str::FromStr + Default>(var_name: &str) -> Re
         Ok(val) => match val.parse::<T>() {
             Ok(val) => Ok(val),
             Err(_) => Err(EnvVariableError {
-                message: ENV_ERR_PARSE_FAIL.to_string(),
+                identifier: ENV_ERR_PARSE_FAIL.to_string(),
+                message: "Variable could not be parsed".to_string(),
                 variable_name: var_name.to_string(),
             }),
         },
@@ -1,2 +1,5 @@
+pub mod digest;
+pub mod email;
 pub mod envutil;
-pub mod format;
\ No newline at end of file
+pub mod random;
+pub mod totp;
@@ -0,0 +1,14 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand_core::{OsRng, RngCore};
+
+/// `num_bytes` bytes of OS randomness.
+pub fn random_bytes(num_bytes: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; num_bytes];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+/// URL safe random token built from `num_bytes` bytes of OS randomness.
+pub fn random_token(num_bytes: usize) -> String {
+    URL_SAFE_NO_PAD.encode(random_bytes(num_bytes))
+}
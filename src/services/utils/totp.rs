@@ -0,0 +1,72 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+use crate::services::constants;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The RFC 6238 time step `timestamp` falls into.
+pub fn time_step(timestamp: i64) -> i64 {
+    timestamp.div_euclid(constants::SEC_TOTP_PERIOD_SECS)
+}
+
+/// RFC 4226 HOTP code for `counter`, zero padded to `digits`. HMAC-SHA1 is what
+/// authenticator apps assume when the URI doesn't name an algorithm.
+pub fn hotp(secret: &[u8], counter: u64, digits: u32) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    // Dynamic truncation: the last nibble picks the four bytes to use.
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+    format!("{:0width$}", binary % 10u32.pow(digits), width = digits as usize)
+}
+
+/// The TOTP code of time step `step`.
+pub fn code_at(secret: &[u8], step: i64) -> String {
+    hotp(secret, step as u64, constants::SEC_TOTP_DIGITS)
+}
+
+/// RFC 4648 base32 without padding, the form authenticator apps take secrets in.
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = ((buffer << 8) | byte as u32) & 0xffff;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+/// Key URI understood by authenticator apps, usually shown as a QR code.
+/// `secret` is the base32 encoded secret.
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        constants::SEC_TOTP_DIGITS,
+        constants::SEC_TOTP_PERIOD_SECS
+    )
+}
+
+/// Percent encodes everything but the RFC 3986 unreserved characters.
+fn percent_encode(value: &str) -> String {
+    value.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
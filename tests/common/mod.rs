@@ -0,0 +1,629 @@
+//! In-memory repositories and the services wired up with them, shared by the
+//! integration tests so none of them needs a database.
+
+#![allow(dead_code)]
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use argon2::Params;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use uuid::Uuid;
+
+use iron_cms_api::domain::constants as domain_constants;
+use iron_cms_api::domain::error::{RepositoryError, RepositoryErrorKind};
+use iron_cms_api::domain::models::admin_permission::{AdminPermission, AdminPermissions, CreateAdminPermission, UpdateAdminPermission};
+use iron_cms_api::domain::models::session::{CreateSession, CreateTokenBlacklist, Session};
+use iron_cms_api::domain::models::user::{CreateUserHashed, Role, UpdateUserHashed, User};
+use iron_cms_api::domain::repositories::admin_permission::{AdminPermissionQueryParams, AdminPermissionRepository};
+use iron_cms_api::domain::repositories::mfa_recovery_code::MfaRecoveryCodeRepository;
+use iron_cms_api::domain::repositories::repository::{QueryParams, RepositoryResult, ResultPaging};
+use iron_cms_api::domain::repositories::session::{SessionQueryParams, SessionRepository};
+use iron_cms_api::domain::repositories::token_blacklist::TokenBlacklistRepository;
+use iron_cms_api::domain::repositories::user::{UserQueryParams, UserRepository};
+use iron_cms_api::infrastructure::services::admin_permission::AdminPermissionServiceImpl;
+use iron_cms_api::infrastructure::services::auth::AuthServiceImpl;
+use iron_cms_api::infrastructure::services::email_verification::{EmailVerificationPolicy, EmailVerificationServiceImpl};
+use iron_cms_api::infrastructure::services::mfa::{MfaPolicy, MfaServiceImpl};
+use iron_cms_api::infrastructure::services::user::UserServiceImpl;
+use iron_cms_api::services::concrete::argon2id_hash::Argon2IdHashService;
+use iron_cms_api::services::concrete::hashing_pool::HashingPool;
+use iron_cms_api::services::concrete::jwt_token::JwtTokenService;
+use iron_cms_api::services::concrete::password_policy::ConfigurablePasswordPolicy;
+use iron_cms_api::services::concrete::secret_cipher::SecretCipher;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::error::MailerError;
+use iron_cms_api::services::traits::mailer::{MailMessage, MailerService};
+use iron_cms_api::services::traits::password_hash::PasswordHashService;
+
+pub const PASSWORD: &str = "correct horse battery staple";
+pub const MFA_KEY: [u8; 32] = [7; 32];
+
+pub fn not_found() -> RepositoryError {
+    RepositoryError { message: "not found".to_string(), kind: RepositoryErrorKind::NotFound }
+}
+
+fn page<T: Clone>(items: Vec<T>, params: &impl QueryParams) -> ResultPaging<T> {
+    ResultPaging {
+        total: items.len() as i64,
+        limit: params.limit(),
+        offset: params.offset(),
+        items: items.into_iter().skip(params.offset() as usize).take(params.limit() as usize).collect(),
+    }
+}
+
+/// The token service reads its keys from the environment.
+pub fn set_token_env() {
+    std::env::set_var(constants::SEC_JWT_ENV_SECRET, "0123456789abcdef0123456789abcdef");
+    std::env::set_var(constants::SEC_JWT_ENV_ENCRYPTION_KEY, "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=");
+}
+
+/// Small costs keep the tests fast.
+pub fn fast_hasher() -> Argon2IdHashService {
+    Argon2IdHashService::with_params(Params::new(1024, 1, 1, None).unwrap(), Arc::new(HashingPool::new(2, 8)))
+}
+
+/// A user whose email is already verified, as most tests don't care about it.
+pub fn new_user(email: &str, role: Role, password_hash: &str) -> User {
+    User {
+        id: Uuid::new_v4(),
+        role,
+        name: "Test User".to_string(),
+        email: email.to_string(),
+        password_hash: password_hash.to_string(),
+        reset_token: None,
+        reset_token_expiry: None,
+        created_at: Utc::now().naive_utc(),
+        updated_at: None,
+        email_verified_at: Some(Utc::now().naive_utc()),
+        email_verification_sent_at: None,
+        totp_secret: None,
+        totp_enabled_at: None,
+        totp_last_used_step: None,
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    users: Mutex<HashMap<Uuid, User>>,
+    updates: AtomicUsize,
+    offline: AtomicBool,
+}
+
+impl InMemoryUserRepository {
+    pub fn insert(&self, user: User) -> User {
+        self.users.lock().unwrap().insert(user.id, user.clone());
+        user
+    }
+
+    pub fn user(&self, user_id: Uuid) -> User {
+        self.users.lock().unwrap()[&user_id].clone()
+    }
+
+    /// How often `update` was called.
+    pub fn updates(&self) -> usize {
+        self.updates.load(Ordering::SeqCst)
+    }
+
+    /// Makes looking users up by email fail as if the database were down.
+    pub fn go_offline(&self) {
+        self.offline.store(true, Ordering::SeqCst);
+    }
+
+    fn email_taken(users: &HashMap<Uuid, User>, email: &str, except: Option<Uuid>) -> bool {
+        users.values().any(|user| Some(user.id) != except && user.email.to_lowercase() == email.to_lowercase())
+    }
+
+    fn email_taken_error() -> RepositoryError {
+        RepositoryError {
+            message: "duplicate email".to_string(),
+            kind: RepositoryErrorKind::UniqueViolation { constraint: Some(domain_constants::DB_CONSTRAINT_USERS_EMAIL.to_string()) },
+        }
+    }
+
+    fn from_new_user(new_user: &CreateUserHashed) -> User {
+        User {
+            id: Uuid::new_v4(),
+            role: new_user.role.unwrap_or(Role::User),
+            name: new_user.name.clone(),
+            email: new_user.email.clone(),
+            password_hash: new_user.password_hash.clone(),
+            reset_token: new_user.reset_token.clone(),
+            reset_token_expiry: new_user.reset_token_expiry,
+            created_at: Utc::now().naive_utc(),
+            updated_at: None,
+            email_verified_at: None,
+            email_verification_sent_at: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_used_step: None,
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn create(&self, new_user: &CreateUserHashed) -> RepositoryResult<User> {
+        let mut users = self.users.lock().unwrap();
+        if Self::email_taken(&users, &new_user.email, None) {
+            return Err(Self::email_taken_error());
+        }
+        let user = Self::from_new_user(new_user);
+        users.insert(user.id, user.clone());
+        Ok(user)
+    }
+
+    async fn create_many(&self, new_users: &[CreateUserHashed]) -> RepositoryResult<Vec<User>> {
+        let mut users = self.users.lock().unwrap();
+        let mut created: Vec<User> = Vec::new();
+        for new_user in new_users {
+            let taken_by_batch = created.iter().any(|user| user.email.to_lowercase() == new_user.email.to_lowercase());
+            if taken_by_batch || Self::email_taken(&users, &new_user.email, None) {
+                return Err(Self::email_taken_error());
+            }
+            created.push(Self::from_new_user(new_user));
+        }
+        users.extend(created.iter().map(|user| (user.id, user.clone())));
+        Ok(created)
+    }
+
+    async fn list(&self, params: UserQueryParams) -> RepositoryResult<ResultPaging<User>> {
+        let mut users: Vec<User> = self.users.lock().unwrap().values().cloned().collect();
+        users.sort_by_key(|user| user.created_at);
+        Ok(page(users, &params))
+    }
+
+    async fn get(&self, user_id: Uuid) -> RepositoryResult<User> {
+        self.users.lock().unwrap().get(&user_id).cloned().ok_or_else(not_found)
+    }
+
+    async fn get_by_email(&self, user_email: &str) -> RepositoryResult<User> {
+        if self.offline.load(Ordering::SeqCst) {
+            return Err(RepositoryError { message: "connection refused".to_string(), kind: RepositoryErrorKind::Connection });
+        }
+        self.users.lock().unwrap()
+            .values()
+            .find(|user| user.email.to_lowercase() == user_email.to_lowercase())
+            .cloned()
+            .ok_or_else(not_found)
+    }
+
+    async fn get_by_reset_token(&self, reset_token: &str) -> RepositoryResult<User> {
+        self.users.lock().unwrap()
+            .values()
+            .find(|user| user.reset_token.as_deref() == Some(reset_token))
+            .cloned()
+            .ok_or_else(not_found)
+    }
+
+    async fn update(&self, user_id: Uuid, updated_user: &UpdateUserHashed) -> RepositoryResult<User> {
+        self.updates.fetch_add(1, Ordering::SeqCst);
+        let mut users = self.users.lock().unwrap();
+        if updated_user.email.as_ref().is_some_and(|email| Self::email_taken(&users, email, Some(user_id))) {
+            return Err(Self::email_taken_error());
+        }
+        let user = users.get_mut(&user_id).ok_or_else(not_found)?;
+        if let Some(role) = updated_user.role {
+            user.role = role;
+        }
+        if let Some(name) = &updated_user.name {
+            user.name = name.clone();
+        }
+        if let Some(email) = &updated_user.email {
+            user.email = email.clone();
+        }
+        if let Some(password_hash) = &updated_user.password_hash {
+            user.password_hash = password_hash.clone();
+        }
+        if let Some(reset_token) = &updated_user.reset_token {
+            user.reset_token = Some(reset_token.clone());
+        }
+        if let Some(reset_token_expiry) = updated_user.reset_token_expiry {
+            user.reset_token_expiry = Some(reset_token_expiry);
+        }
+        if let Some(email_verified_at) = updated_user.email_verified_at {
+            user.email_verified_at = email_verified_at;
+        }
+        user.updated_at = Some(Utc::now().naive_utc());
+        Ok(user.clone())
+    }
+
+    async fn reset_password(&self, user_id: Uuid, reset_token: &str, password_hash: &str) -> RepositoryResult<User> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.get_mut(&user_id)
+            .filter(|user| user.reset_token.as_deref() == Some(reset_token))
+            .ok_or_else(not_found)?;
+        user.password_hash = password_hash.to_string();
+        user.reset_token = None;
+        user.reset_token_expiry = None;
+        Ok(user.clone())
+    }
+
+    async fn mark_verification_sent(&self, user_id: Uuid, sent_before: NaiveDateTime) -> RepositoryResult<bool> {
+        let mut users = self.users.lock().unwrap();
+        let Some(user) = users.get_mut(&user_id) else {
+            return Ok(false);
+        };
+        if user.email_verified_at.is_some() || user.email_verification_sent_at.is_some_and(|sent_at| sent_at > sent_before) {
+            return Ok(false);
+        }
+        user.email_verification_sent_at = Some(Utc::now().naive_utc());
+        Ok(true)
+    }
+
+    async fn restore_verification_sent(&self, user_id: Uuid, sent_at: Option<NaiveDateTime>) -> RepositoryResult<bool> {
+        let mut users = self.users.lock().unwrap();
+        let Some(user) = users.get_mut(&user_id) else {
+            return Ok(false);
+        };
+        user.email_verification_sent_at = sent_at;
+        Ok(true)
+    }
+
+    async fn mark_email_verified(&self, user_id: Uuid, email: &str) -> RepositoryResult<User> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.get_mut(&user_id)
+            .filter(|user| user.email.to_lowercase() == email.to_lowercase())
+            .ok_or_else(not_found)?;
+        user.email_verified_at = Some(Utc::now().naive_utc());
+        Ok(user.clone())
+    }
+
+    async fn start_totp_enrollment(&self, user_id: Uuid, totp_secret: &str) -> RepositoryResult<User> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.get_mut(&user_id).filter(|user| user.totp_enabled_at.is_none()).ok_or_else(not_found)?;
+        user.totp_secret = Some(totp_secret.to_string());
+        Ok(user.clone())
+    }
+
+    async fn enable_totp(&self, user_id: Uuid, totp_secret: &str, step: i64) -> RepositoryResult<User> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.get_mut(&user_id)
+            .filter(|user| user.totp_enabled_at.is_none() && user.totp_secret.as_deref() == Some(totp_secret))
+            .ok_or_else(not_found)?;
+        user.totp_enabled_at = Some(Utc::now().naive_utc());
+        user.totp_last_used_step = Some(step);
+        Ok(user.clone())
+    }
+
+    async fn disable_totp(&self, user_id: Uuid) -> RepositoryResult<User> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.get_mut(&user_id).ok_or_else(not_found)?;
+        user.totp_secret = None;
+        user.totp_enabled_at = None;
+        user.totp_last_used_step = None;
+        Ok(user.clone())
+    }
+
+    async fn mark_totp_used(&self, user_id: Uuid, step: i64) -> RepositoryResult<bool> {
+        let mut users = self.users.lock().unwrap();
+        let user = users.get_mut(&user_id).ok_or_else(not_found)?;
+        if user.totp_last_used_step.is_some_and(|last_step| last_step >= step) {
+            return Ok(false);
+        }
+        user.totp_last_used_step = Some(step);
+        Ok(true)
+    }
+
+    async fn delete(&self, user_id: Uuid) -> RepositoryResult<bool> {
+        Ok(self.users.lock().unwrap().remove(&user_id).is_some())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemorySessionRepository {
+    sessions: Mutex<HashMap<Uuid, Session>>,
+    lose_race: AtomicBool,
+}
+
+impl InMemorySessionRepository {
+    pub fn count(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    /// Makes the next `get_by_token` behave as if another request ended the
+    /// session right after it was read, as two refreshes racing each other do.
+    pub fn lose_next_race(&self) {
+        self.lose_race.store(true, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl SessionRepository for InMemorySessionRepository {
+    async fn create(&self, new_session: &CreateSession) -> RepositoryResult<Session> {
+        let session = Session {
+            id: new_session.id,
+            user_id: new_session.user_id,
+            token: new_session.token.clone(),
+            expiry: new_session.expiry,
+            user_agent: new_session.user_agent.clone(),
+            ip_address: new_session.ip_address.clone(),
+            created_at: Utc::now().naive_utc(),
+            updated_at: None,
+        };
+        self.sessions.lock().unwrap().insert(session.id, session.clone());
+        Ok(session)
+    }
+
+    async fn list_by_user(&self, user_id: Uuid, params: SessionQueryParams) -> RepositoryResult<ResultPaging<Session>> {
+        let mut sessions: Vec<Session> = self.sessions.lock().unwrap()
+            .values()
+            .filter(|session| session.user_id == user_id)
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|session| session.created_at);
+        Ok(page(sessions, &params))
+    }
+
+    async fn get_by_token(&self, token: &str) -> RepositoryResult<Session> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.values()
+            .find(|session| session.token == token)
+            .cloned()
+            .ok_or_else(not_found)?;
+        if self.lose_race.swap(false, Ordering::SeqCst) {
+            sessions.remove(&session.id);
+        }
+        Ok(session)
+    }
+
+    async fn delete(&self, session_id: Uuid) -> RepositoryResult<bool> {
+        Ok(self.sessions.lock().unwrap().remove(&session_id).is_some())
+    }
+
+    async fn delete_by_user(&self, user_id: Uuid) -> RepositoryResult<Vec<Session>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let ended: Vec<Session> = sessions.values().filter(|session| session.user_id == user_id).cloned().collect();
+        sessions.retain(|_, session| session.user_id != user_id);
+        Ok(ended)
+    }
+
+    async fn delete_expired(&self) -> RepositoryResult<usize> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let before = sessions.len();
+        sessions.retain(|_, session| session.expiry > Utc::now().naive_utc());
+        Ok(before - sessions.len())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryTokenBlacklistRepository {
+    entries: Mutex<HashMap<String, NaiveDateTime>>,
+}
+
+#[async_trait]
+impl TokenBlacklistRepository for InMemoryTokenBlacklistRepository {
+    async fn create(&self, new_entry: &CreateTokenBlacklist) -> RepositoryResult<()> {
+        self.entries.lock().unwrap().insert(new_entry.token.clone(), new_entry.expiry);
+        Ok(())
+    }
+
+    async fn is_blacklisted(&self, token: &str) -> RepositoryResult<bool> {
+        Ok(self.entries.lock().unwrap().contains_key(token))
+    }
+
+    async fn delete_expired(&self) -> RepositoryResult<usize> {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|_, expiry| *expiry > Utc::now().naive_utc());
+        Ok(before - entries.len())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryRecoveryCodeRepository {
+    codes: Mutex<HashMap<Uuid, HashSet<String>>>,
+}
+
+#[async_trait]
+impl MfaRecoveryCodeRepository for InMemoryRecoveryCodeRepository {
+    async fn replace(&self, user_id: Uuid, codes: &[String]) -> RepositoryResult<()> {
+        self.codes.lock().unwrap().insert(user_id, codes.iter().cloned().collect());
+        Ok(())
+    }
+
+    async fn consume(&self, user_id: Uuid, code: &str) -> RepositoryResult<bool> {
+        Ok(self.codes.lock().unwrap().get_mut(&user_id).is_some_and(|codes| codes.remove(code)))
+    }
+
+    async fn delete_by_user(&self, user_id: Uuid) -> RepositoryResult<usize> {
+        Ok(self.codes.lock().unwrap().remove(&user_id).map_or(0, |codes| codes.len()))
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryAdminPermissionRepository {
+    permissions: Mutex<HashMap<Uuid, AdminPermission>>,
+}
+
+impl InMemoryAdminPermissionRepository {
+    pub fn grant(&self, user_id: Uuid, permission: AdminPermissions) {
+        let admin_permission = AdminPermission {
+            id: Uuid::new_v4(),
+            user_id,
+            permission,
+            created_at: Utc::now().naive_utc(),
+            updated_at: None,
+        };
+        self.permissions.lock().unwrap().insert(admin_permission.id, admin_permission);
+    }
+}
+
+#[async_trait]
+impl AdminPermissionRepository for InMemoryAdminPermissionRepository {
+    async fn create(&self, new_admin_permission: &CreateAdminPermission) -> RepositoryResult<AdminPermission> {
+        let admin_permission = AdminPermission {
+            id: Uuid::new_v4(),
+            user_id: new_admin_permission.user_id,
+            permission: new_admin_permission.permission,
+            created_at: Utc::now().naive_utc(),
+            updated_at: None,
+        };
+        self.permissions.lock().unwrap().insert(admin_permission.id, admin_permission.clone());
+        Ok(admin_permission)
+    }
+
+    async fn list(&self, params: AdminPermissionQueryParams) -> RepositoryResult<ResultPaging<AdminPermission>> {
+        let mut permissions: Vec<AdminPermission> = self.permissions.lock().unwrap().values().cloned().collect();
+        permissions.sort_by_key(|admin_permission| admin_permission.created_at);
+        Ok(page(permissions, &params))
+    }
+
+    async fn get(&self, admin_permission_id: Uuid) -> RepositoryResult<AdminPermission> {
+        self.permissions.lock().unwrap().get(&admin_permission_id).cloned().ok_or_else(not_found)
+    }
+
+    async fn update(&self, admin_permission_id: Uuid, updated_admin_permission: &UpdateAdminPermission) -> RepositoryResult<AdminPermission> {
+        let mut permissions = self.permissions.lock().unwrap();
+        let admin_permission = permissions.get_mut(&admin_permission_id).ok_or_else(not_found)?;
+        admin_permission.user_id = updated_admin_permission.user_id;
+        admin_permission.permission = updated_admin_permission.permission;
+        admin_permission.updated_at = Some(Utc::now().naive_utc());
+        Ok(admin_permission.clone())
+    }
+
+    async fn delete(&self, admin_permission_id: Uuid) -> RepositoryResult<bool> {
+        Ok(self.permissions.lock().unwrap().remove(&admin_permission_id).is_some())
+    }
+
+    async fn has_permission(&self, user_id: Uuid, permission: AdminPermissions) -> RepositoryResult<bool> {
+        Ok(self.permissions.lock().unwrap()
+            .values()
+            .any(|admin_permission| admin_permission.user_id == user_id && admin_permission.permission == permission))
+    }
+}
+
+/// Keeps every message it is asked to send.
+#[derive(Default)]
+pub struct RecordingMailer {
+    sent: Mutex<Vec<MailMessage>>,
+}
+
+impl RecordingMailer {
+    pub fn sent(&self) -> Vec<MailMessage> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// The token on a line of its own in the last message.
+    pub fn last_token(&self) -> String {
+        let sent = self.sent();
+        let message = sent.last().expect("no mail was sent");
+        message.body.lines()
+            .find(|line| !line.is_empty() && !line.contains(' '))
+            .expect("mail holds no token")
+            .to_string()
+    }
+}
+
+#[async_trait]
+impl MailerService for RecordingMailer {
+    async fn send(&self, message: &MailMessage) -> Result<(), MailerError> {
+        self.sent.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+}
+
+/// What the tests may want to differ from the defaults.
+pub struct Setup {
+    pub hash_service: Option<Arc<dyn PasswordHashService>>,
+    /// Replaces the `RecordingMailer`, which then stays empty.
+    pub mailer: Option<Arc<dyn MailerService>>,
+    pub verification: EmailVerificationPolicy,
+    pub mfa: MfaPolicy,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Self {
+            hash_service: None,
+            mailer: None,
+            verification: EmailVerificationPolicy { required_for: Vec::new(), resend_cooldown_secs: 0 },
+            mfa: MfaPolicy { required_roles: Vec::new(), issuer: "Iron CMS".to_string(), skew_steps: 1 },
+        }
+    }
+}
+
+pub struct Services {
+    pub users: Arc<InMemoryUserRepository>,
+    pub sessions: Arc<InMemorySessionRepository>,
+    pub blacklist: Arc<InMemoryTokenBlacklistRepository>,
+    pub admin_permissions: Arc<InMemoryAdminPermissionRepository>,
+    pub mail: Arc<RecordingMailer>,
+    pub hash_service: Arc<dyn PasswordHashService>,
+    pub token_service: Arc<JwtTokenService>,
+    pub email_verification: Arc<EmailVerificationServiceImpl>,
+    pub mfa: Arc<MfaServiceImpl>,
+    pub user_service: Arc<UserServiceImpl<'static>>,
+    pub admin_permission_service: Arc<AdminPermissionServiceImpl>,
+    pub auth: Arc<AuthServiceImpl>,
+}
+
+impl Services {
+    pub fn new(setup: Setup) -> Self {
+        set_token_env();
+
+        let users = Arc::new(InMemoryUserRepository::default());
+        let sessions = Arc::new(InMemorySessionRepository::default());
+        let blacklist = Arc::new(InMemoryTokenBlacklistRepository::default());
+        let admin_permissions = Arc::new(InMemoryAdminPermissionRepository::default());
+        let mail = Arc::new(RecordingMailer::default());
+        let mailer = setup.mailer.unwrap_or_else(|| mail.clone());
+        let hash_service = setup.hash_service.unwrap_or_else(|| Arc::new(fast_hasher()));
+        let password_policy = Arc::new(ConfigurablePasswordPolicy::new(8, 128, HashSet::new()));
+        let token_service = Arc::new(JwtTokenService::new());
+
+        let email_verification = Arc::new(EmailVerificationServiceImpl::new(
+            users.clone(),
+            token_service.clone(),
+            mailer.clone(),
+            setup.verification
+        ));
+        let mfa = Arc::new(MfaServiceImpl::new(
+            users.clone(),
+            Arc::new(InMemoryRecoveryCodeRepository::default()),
+            SecretCipher::new(&MFA_KEY),
+            setup.mfa
+        ));
+        let user_service = Arc::new(UserServiceImpl::new(
+            users.clone(),
+            hash_service.clone(),
+            password_policy.clone(),
+            email_verification.clone()
+        ));
+        let auth = Arc::new(AuthServiceImpl::new(
+            users.clone(),
+            sessions.clone(),
+            blacklist.clone(),
+            user_service.clone(),
+            email_verification.clone(),
+            mfa.clone(),
+            hash_service.clone(),
+            password_policy,
+            token_service.clone(),
+            mailer
+        ));
+
+        Services {
+            users,
+            sessions,
+            blacklist,
+            admin_permission_service: Arc::new(AdminPermissionServiceImpl::new(admin_permissions.clone())),
+            admin_permissions,
+            mail,
+            hash_service,
+            token_service,
+            email_verification,
+            mfa,
+            user_service,
+            auth,
+        }
+    }
+
+    /// Adds a user with a verified email who signs in with `PASSWORD`.
+    pub async fn add_user(&self, email: &str, role: Role) -> User {
+        let password_hash = self.hash_service.hash_password(PASSWORD).await.unwrap();
+        self.users.insert(new_user(email, role, &password_hash))
+    }
+}
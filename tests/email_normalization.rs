@@ -0,0 +1,40 @@
+//! Covers the form emails are stored and looked up in.
+
+use iron_cms_api::services::utils::email::normalize_email;
+
+#[test]
+fn surrounding_whitespace_is_dropped() {
+    assert_eq!(normalize_email("  ana@example.com\n"), "ana@example.com");
+}
+
+#[test]
+fn domain_is_lowercased_but_local_part_keeps_its_case() {
+    assert_eq!(normalize_email("Ana.Silva@Example.COM"), "Ana.Silva@example.com");
+}
+
+#[test]
+fn punycode_domain_is_only_lowercased() {
+    // Existing rows were only lowercased by the migration, so decoding `xn--`
+    // labels here would stop them from being found.
+    assert_eq!(normalize_email("ana@XN--MNCHEN-3YA.de"), "ana@xn--mnchen-3ya.de");
+    assert_eq!(normalize_email("ana@MÜNCHEN.de"), "ana@münchen.de");
+}
+
+#[test]
+fn local_part_is_composed() {
+    // `e` followed by a combining acute accent.
+    assert_eq!(normalize_email("jose\u{0301}@example.com"), "josé@example.com");
+}
+
+#[test]
+fn decomposed_addresses_match_their_composed_form() {
+    // Every accented letter is a base letter followed by a combining accent.
+    let decomposed = "Jose\u{0301}.Nun\u{0303}ez@Cafe\u{0301}.COM";
+    assert_eq!(normalize_email(decomposed), "José.Nuñez@café.com");
+    assert_eq!(normalize_email(decomposed), normalize_email("José.Nuñez@CAFÉ.com"));
+}
+
+#[test]
+fn only_the_last_at_separates_the_domain() {
+    assert_eq!(normalize_email("\"a@b\"@Example.com"), "\"a@b\"@example.com");
+}
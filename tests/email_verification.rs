@@ -0,0 +1,210 @@
+//! Covers verifying the email of an account with the token mailed to it. Mail
+//! goes through the file mailer, which is where the tests read the tokens from.
+
+mod common;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use iron_cms_api::domain::error::RepositoryErrorKind;
+use iron_cms_api::domain::models::auth::{AuthResendVerification, AuthVerifyEmail, VerifiedAction};
+use iron_cms_api::domain::models::user::{Role, UpdateUserPlainText, User};
+use iron_cms_api::domain::services::email_verification::EmailVerificationService;
+use iron_cms_api::domain::services::user::UserService;
+use iron_cms_api::infrastructure::services::email_verification::{EmailVerificationPolicy, EmailVerificationServiceImpl};
+use iron_cms_api::services::concrete::file_mailer::FileMailerService;
+use iron_cms_api::services::constants;
+
+use common::{new_user, Services, Setup};
+
+const EMAIL: &str = "verify@example.com";
+
+struct Fixture {
+    services: Services,
+    verification: Arc<EmailVerificationServiceImpl>,
+    outbox: PathBuf,
+}
+
+impl Fixture {
+    fn new(resend_cooldown_secs: i64) -> Self {
+        let outbox = std::env::temp_dir().join(std::format!("iron-cms-outbox-{}", Uuid::new_v4()));
+        let services = Services::new(Setup {
+            mailer: Some(Arc::new(FileMailerService::new(&outbox))),
+            verification: EmailVerificationPolicy { required_for: vec![VerifiedAction::Login], resend_cooldown_secs },
+            ..Setup::default()
+        });
+        Fixture { verification: services.email_verification.clone(), services, outbox }
+    }
+
+    /// Adds a user whose email is yet to be verified.
+    fn insert(&self) -> User {
+        let user = User { email_verified_at: None, ..new_user(EMAIL, Role::User, "") };
+        self.services.users.insert(user)
+    }
+
+    /// Mails in the order they were sent.
+    fn sent_mails(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(&self.outbox) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries.map(|entry| entry.unwrap().path()).collect();
+        paths.sort();
+        paths.into_iter().map(|path| std::fs::read_to_string(path).unwrap()).collect()
+    }
+
+    fn last_token(&self) -> String {
+        let mails = self.sent_mails();
+        let mail = mails.last().expect("no mail was sent");
+        mail.lines().find(|line| line.starts_with("ey")).expect("mail holds no token").to_string()
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.outbox);
+    }
+}
+
+fn verification(token: String) -> AuthVerifyEmail {
+    AuthVerifyEmail { token }
+}
+
+#[actix_web::test]
+async fn mailed_token_verifies_the_email() {
+    let fixture = Fixture::new(0);
+    let user = fixture.insert();
+    fixture.verification.send_verification(&user).await.unwrap();
+
+    let mails = fixture.sent_mails();
+    assert_eq!(mails.len(), 1);
+    assert!(mails[0].starts_with(&std::format!("To: {}\n", EMAIL)));
+
+    let verified = fixture.verification.verify_email(verification(fixture.last_token())).await.unwrap();
+    assert!(verified.email_verified_at.is_some());
+    // Using the token again is harmless.
+    assert!(fixture.verification.verify_email(verification(fixture.last_token())).await.is_ok());
+}
+
+#[actix_web::test]
+async fn token_stops_working_once_the_email_changes() {
+    let fixture = Fixture::new(0);
+    let user = fixture.insert();
+    fixture.verification.send_verification(&user).await.unwrap();
+    let old_token = fixture.last_token();
+
+    let update = UpdateUserPlainText {
+        role: None,
+        name: None,
+        email: Some("Moved@Example.com".to_string()),
+        password: None,
+        confirm_password: None,
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    fixture.services.user_service.update(user.id, update, &user).await.unwrap();
+
+    let err = fixture.verification.verify_email(verification(old_token)).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_VERIFICATION_TOKEN_INVALID);
+
+    // The new address was mailed a token of its own.
+    assert!(fixture.sent_mails().last().unwrap().starts_with("To: Moved@example.com\n"));
+    let verified = fixture.verification.verify_email(verification(fixture.last_token())).await.unwrap();
+    assert_eq!(verified.email, "Moved@example.com");
+    assert!(verified.email_verified_at.is_some());
+}
+
+#[actix_web::test]
+async fn changing_the_email_resets_verification() {
+    let fixture = Fixture::new(0);
+    let user = fixture.insert();
+    fixture.verification.send_verification(&user).await.unwrap();
+    fixture.verification.verify_email(verification(fixture.last_token())).await.unwrap();
+
+    let update = UpdateUserPlainText {
+        role: None,
+        name: None,
+        email: Some("other@example.com".to_string()),
+        password: None,
+        confirm_password: None,
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    let updated = fixture.services.user_service.update(user.id, update, &user).await.unwrap();
+    assert!(updated.email_verified_at.is_none());
+}
+
+#[actix_web::test]
+async fn resend_waits_for_the_cooldown() {
+    let fixture = Fixture::new(3600);
+    let user = fixture.insert();
+    fixture.verification.send_verification(&user).await.unwrap();
+
+    let resend = AuthResendVerification { email: EMAIL.to_uppercase() };
+    fixture.verification.resend_verification(resend).await.unwrap();
+    assert_eq!(fixture.sent_mails().len(), 1);
+
+    let fixture = Fixture::new(0);
+    let user = fixture.insert();
+    fixture.verification.send_verification(&user).await.unwrap();
+    fixture.verification.resend_verification(AuthResendVerification { email: EMAIL.to_string() }).await.unwrap();
+    assert_eq!(fixture.sent_mails().len(), 2);
+}
+
+#[actix_web::test]
+async fn failed_mail_does_not_start_the_cooldown() {
+    let fixture = Fixture::new(3600);
+    let user = fixture.insert();
+
+    // A file where the outbox should be makes the mailer fail.
+    std::fs::write(&fixture.outbox, "").unwrap();
+    let err = fixture.verification.send_verification(&user).await.unwrap_err();
+    assert_eq!(err.identifier, constants::MAIL_ERR_DELIVERY_FAILED);
+    assert!(fixture.services.users.user(user.id).email_verification_sent_at.is_none());
+
+    std::fs::remove_file(&fixture.outbox).unwrap();
+    fixture.verification.resend_verification(AuthResendVerification { email: EMAIL.to_string() }).await.unwrap();
+    assert_eq!(fixture.sent_mails().len(), 1);
+}
+
+#[actix_web::test]
+async fn resend_ignores_unknown_and_verified_addresses() {
+    let fixture = Fixture::new(0);
+    fixture.verification.resend_verification(AuthResendVerification { email: "nobody@example.com".to_string() }).await.unwrap();
+    assert!(fixture.sent_mails().is_empty());
+
+    let user = fixture.insert();
+    fixture.verification.send_verification(&user).await.unwrap();
+    fixture.verification.verify_email(verification(fixture.last_token())).await.unwrap();
+    fixture.verification.resend_verification(AuthResendVerification { email: EMAIL.to_string() }).await.unwrap();
+    assert_eq!(fixture.sent_mails().len(), 1);
+}
+
+#[actix_web::test]
+async fn lookup_failures_are_not_taken_for_unknown_addresses() {
+    let fixture = Fixture::new(0);
+    fixture.insert();
+    fixture.services.users.go_offline();
+
+    let err = fixture.verification.resend_verification(AuthResendVerification { email: EMAIL.to_string() }).await.unwrap_err();
+    assert_eq!(err.identifier, RepositoryErrorKind::Connection.identifier());
+    assert!(fixture.sent_mails().is_empty());
+}
+
+#[actix_web::test]
+async fn unverified_users_are_only_kept_from_required_actions() {
+    let fixture = Fixture::new(0);
+    let user = fixture.insert();
+
+    let err = fixture.verification.ensure_verified(&user, VerifiedAction::Login).unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_EMAIL_NOT_VERIFIED);
+    assert!(fixture.verification.ensure_verified(&user, VerifiedAction::Admin).is_ok());
+}
+
+#[test]
+fn required_actions_are_parsed_by_name() {
+    let actions = EmailVerificationPolicy::parse_required_for(" login, admin ,").unwrap();
+    assert_eq!(actions, vec![VerifiedAction::Login, VerifiedAction::Admin]);
+    assert!(EmailVerificationPolicy::parse_required_for("logout").is_err());
+}
@@ -0,0 +1,64 @@
+//! Covers decoding `Role` and `AdminPermissions` from numbers and names,
+//! without falling back to some variant for values that match none.
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use iron_cms_api::domain::error::{CommonError, RepositoryErrorKind};
+use iron_cms_api::domain::models::admin_permission::{AdminPermission, AdminPermissions};
+use iron_cms_api::domain::models::common::{ErrorResponseType, NamedEnum};
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::infrastructure::models::admin_permission::AdminPermissionDiesel;
+use iron_cms_api::services::constants;
+
+#[test]
+fn variants_round_trip_through_numbers_and_names() {
+    for role in Role::VARIANTS {
+        assert_eq!(Role::from_i32(role.as_i32()).unwrap(), *role);
+        assert_eq!(Role::from_name(role.as_str()).unwrap(), *role);
+    }
+    for permission in AdminPermissions::VARIANTS {
+        assert_eq!(AdminPermissions::from_i32(permission.as_i32()).unwrap(), *permission);
+        assert_eq!(AdminPermissions::from_name(permission.as_str()).unwrap(), *permission);
+    }
+}
+
+#[test]
+fn unknown_values_are_refused() {
+    assert!(Role::try_from(3).is_err());
+    assert!(Role::try_from(-1).is_err());
+    assert!(Role::from_name("Admin").is_err());
+    assert!(AdminPermissions::try_from(99).is_err());
+
+    let err = Role::from_name("owner").unwrap_err();
+    assert_eq!(err.accepted, ["user", "admin", "super_admin"]);
+    let err = CommonError::from(err);
+    assert_eq!(err.error_type, ErrorResponseType::Validation);
+    assert_eq!(err.errors[0].identifier.as_deref(), Some(constants::VAL_ERR_INVALID_ENUM_VALUE));
+}
+
+#[test]
+fn json_takes_numbers_or_names() {
+    assert_eq!(serde_json::from_str::<Role>("2").unwrap(), Role::SuperAdmin);
+    assert_eq!(serde_json::from_str::<Role>("\"super_admin\"").unwrap(), Role::SuperAdmin);
+    assert_eq!(serde_json::to_string(&Role::SuperAdmin).unwrap(), "\"super_admin\"");
+
+    for invalid in ["7", "-1", "4294967296", "\"root\"", "null", "1.0"] {
+        assert!(serde_json::from_str::<Role>(invalid).is_err(), "{} was accepted", invalid);
+    }
+}
+
+#[test]
+fn unknown_stored_values_are_a_server_error() {
+    let stored = AdminPermissionDiesel {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        permission: 99,
+        created_at: Utc::now().naive_utc(),
+        updated_at: None,
+    };
+    let Err(err) = AdminPermission::try_from(stored) else {
+        panic!("an unknown permission was decoded");
+    };
+    assert_eq!(err.kind, RepositoryErrorKind::Other);
+}
@@ -0,0 +1,54 @@
+//! Covers the catalog of error codes published to clients.
+
+use std::collections::HashSet;
+
+use actix_web::test::{call_and_read_body_json, init_service, TestRequest};
+use actix_web::{web, App};
+
+use iron_cms_api::api::controllers::error_handler::list_error_code_handler;
+use iron_cms_api::api::dto::error::ErrorCodeDto;
+use iron_cms_api::domain::models::common::MessageWithListOfObjectsResponse;
+use iron_cms_api::error_codes::{find_error_code, ERROR_CATALOG};
+
+/// The values of the `*_ERR_*` identifier constants declared in a source file.
+/// `ERR_CONTEXT_*` constants name where an error happened, not the error.
+fn error_identifiers(source: &str) -> Vec<String> {
+    source.lines()
+        .filter_map(|line| line.strip_prefix("pub const "))
+        .filter(|line| line.contains("_ERR_") && !line.starts_with("ERR_CONTEXT_") && line.contains(": &str"))
+        .filter_map(|line| line.split('"').nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn identifiers_and_codes_are_unique() {
+    let identifiers: HashSet<&str> = ERROR_CATALOG.iter().map(|entry| entry.identifier).collect();
+    let codes: HashSet<u32> = ERROR_CATALOG.iter().map(|entry| entry.code).collect();
+    assert_eq!(identifiers.len(), ERROR_CATALOG.len());
+    assert_eq!(codes.len(), ERROR_CATALOG.len());
+    assert!(ERROR_CATALOG.iter().all(|entry| entry.code >= 1000 && !entry.message.is_empty()));
+}
+
+#[test]
+fn every_error_identifier_is_in_the_catalog() {
+    let sources = [
+        include_str!("../src/domain/constants.rs"),
+        include_str!("../src/services/constants.rs"),
+    ];
+    let identifiers: Vec<String> = sources.iter().flat_map(|source| error_identifiers(source)).collect();
+    assert!(identifiers.len() > 40);
+    for identifier in identifiers {
+        assert!(find_error_code(&identifier).is_some(), "`{}` is missing from the catalog", identifier);
+    }
+}
+
+#[actix_web::test]
+async fn catalog_is_listed_for_clients() {
+    let app = init_service(App::new().route("/api/errors", web::get().to(list_error_code_handler))).await;
+    let response: MessageWithListOfObjectsResponse<ErrorCodeDto> = call_and_read_body_json(
+        &app,
+        TestRequest::get().uri("/api/errors").to_request()
+    ).await;
+    assert_eq!(response.objects.len(), ERROR_CATALOG.len());
+}
@@ -0,0 +1,119 @@
+//! Covers how errors turn into HTTP statuses and `ErrorResponse` bodies.
+
+use actix_web::body::to_bytes;
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+
+use iron_cms_api::domain::constants as domain_constants;
+use iron_cms_api::domain::error::{status_code, ApiError, CommonError, RepositoryError, RepositoryErrorKind};
+use iron_cms_api::domain::models::common::{ErrorItem, ErrorResponse, ErrorResponseType};
+use iron_cms_api::error_codes::find_error_code;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::error::{AuthError, PermissionError};
+
+async fn respond(error: CommonError) -> (StatusCode, ErrorResponse) {
+    let response = ApiError::from(error).error_response();
+    let status = response.status();
+    let body = to_bytes(response.into_body()).await.ok().unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn repository_error(kind: RepositoryErrorKind) -> CommonError {
+    CommonError::from(RepositoryError { message: "connection refused at 10.0.0.5:5432".to_string(), kind })
+}
+
+#[test]
+fn error_types_map_to_statuses() {
+    let expected = [
+        (ErrorResponseType::General, StatusCode::BAD_REQUEST),
+        (ErrorResponseType::Validation, StatusCode::UNPROCESSABLE_ENTITY),
+        (ErrorResponseType::NotFound, StatusCode::NOT_FOUND),
+        (ErrorResponseType::Conflict, StatusCode::CONFLICT),
+        (ErrorResponseType::Forbidden, StatusCode::FORBIDDEN),
+        (ErrorResponseType::UnprocessableEntity, StatusCode::UNPROCESSABLE_ENTITY),
+        (ErrorResponseType::InternalServerError, StatusCode::INTERNAL_SERVER_ERROR),
+        (ErrorResponseType::Unauthorized, StatusCode::UNAUTHORIZED),
+        (ErrorResponseType::ServiceUnavailable, StatusCode::SERVICE_UNAVAILABLE),
+    ];
+    for (error_type, status) in expected {
+        assert_eq!(status_code(error_type), status);
+    }
+}
+
+#[actix_web::test]
+async fn repository_errors_are_classified() {
+    let (status, body) = respond(repository_error(RepositoryErrorKind::NotFound)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body.error_type, ErrorResponseType::NotFound);
+    assert_eq!(body.errors[0].identifier.as_deref(), Some(domain_constants::REPO_ERR_NOT_FOUND));
+
+    let (status, _) = respond(repository_error(RepositoryErrorKind::UniqueViolation { constraint: None })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = respond(repository_error(RepositoryErrorKind::ForeignKeyViolation { constraint: None })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn internal_errors_hide_their_details() {
+    for kind in [RepositoryErrorKind::Connection, RepositoryErrorKind::Other] {
+        let identifier = kind.identifier();
+        let (status, body) = respond(repository_error(kind)).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+        let catalog_message = find_error_code(identifier).unwrap().message;
+        assert_eq!(body.message, catalog_message);
+        assert_eq!(body.errors[0].message, catalog_message);
+        assert!(!serde_json::to_string(&body).unwrap().contains("10.0.0.5"));
+    }
+}
+
+#[actix_web::test]
+async fn auth_and_permission_errors_are_401_and_403() {
+    let (status, body) = respond(CommonError::from(AuthError {
+        identifier: constants::SEC_ERR_TOKEN_MISSING.to_string(),
+        message: "A bearer token is required".to_string(),
+        context: constants::ERR_CONTEXT_AUTHENTICATION.to_string(),
+    })).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body.errors[0].message, "A bearer token is required");
+    assert_eq!(body.errors[0].error_code, Some(find_error_code(constants::SEC_ERR_TOKEN_MISSING).unwrap().code));
+
+    let (status, body) = respond(CommonError::from(PermissionError {
+        identifier: constants::SEC_ERR_PERMISSION_DENIED.to_string(),
+        message: "Missing the permission required for this resource".to_string(),
+        context: constants::ERR_CONTEXT_AUTHORIZATION.to_string(),
+    })).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body.errors[0].context, constants::ERR_CONTEXT_AUTHORIZATION);
+}
+
+#[actix_web::test]
+async fn validation_errors_list_every_field() {
+    let (status, body) = respond(CommonError::validation(vec![
+        ErrorItem::new("email", constants::VAL_ERR_VALIDATION_FAILED, "Email is invalid".to_string()),
+        ErrorItem::new("password", constants::VAL_ERR_VALIDATION_FAILED, "Password is too short".to_string()),
+    ])).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let fields: Vec<&str> = body.errors.iter().map(|item| item.context.as_str()).collect();
+    assert_eq!(fields, ["email", "password"]);
+}
+
+#[test]
+fn overload_asks_clients_to_retry() {
+    let error = CommonError::new(
+        constants::SEC_ERR_HASHING_OVERLOADED,
+        "Too many hashes are queued".to_string(),
+        constants::ERR_CONTEXT_HASHING_POOL.to_string(),
+        ErrorResponseType::General,
+    );
+    let response = ApiError::from(error).error_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), &domain_constants::RETRY_AFTER_SECS.to_string());
+}
+
+#[test]
+fn unknown_identifiers_keep_the_fallback_type() {
+    let error = CommonError::new("not_in_the_catalog", "Something".to_string(), "test".to_string(), ErrorResponseType::Conflict);
+    assert_eq!((error.code, error.error_type), (0, ErrorResponseType::Conflict));
+}
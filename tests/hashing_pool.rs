@@ -0,0 +1,46 @@
+//! Covers the threads password hashing runs on and how much work they take.
+
+use std::sync::mpsc;
+use std::thread;
+
+use futures_util::FutureExt;
+
+use iron_cms_api::services::concrete::hashing_pool::HashingPool;
+use iron_cms_api::services::constants;
+
+#[actix_web::test]
+async fn jobs_run_off_the_async_workers() {
+    let pool = HashingPool::new(2, 4);
+    let thread_name = pool.run(|| thread::current().name().map(str::to_string)).await.unwrap();
+    assert!(thread_name.unwrap().starts_with("password-hashing-"));
+    assert_eq!(pool.metrics().completed, 1);
+}
+
+#[actix_web::test]
+async fn jobs_beyond_the_queue_are_rejected() {
+    let pool = HashingPool::new(1, 1);
+    let (started_sender, started) = mpsc::channel();
+    let (release, released) = mpsc::channel::<()>();
+
+    // Polling once hands the job to the pool.
+    let mut running = Box::pin(pool.run(move || {
+        started_sender.send(()).unwrap();
+        released.recv().unwrap();
+        1
+    }));
+    assert!((&mut running).now_or_never().is_none());
+    started.recv().unwrap();
+
+    let mut queued = Box::pin(pool.run(|| 2));
+    assert!((&mut queued).now_or_never().is_none());
+    let metrics = pool.metrics();
+    assert_eq!((metrics.in_flight, metrics.queue_depth), (1, 1));
+
+    let err = pool.run(|| 3).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_HASHING_OVERLOADED);
+
+    release.send(()).unwrap();
+    assert_eq!((running.await.unwrap(), queued.await.unwrap()), (1, 2));
+    let metrics = pool.metrics();
+    assert_eq!((metrics.completed, metrics.rejected, metrics.queue_depth, metrics.in_flight), (2, 1, 0, 0));
+}
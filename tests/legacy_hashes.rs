@@ -0,0 +1,182 @@
+//! Covers password hashes imported from other platforms, which are verified as
+//! they are and replaced on the first login when in a legacy format.
+
+mod common;
+
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::Params;
+use uuid::Uuid;
+
+use iron_cms_api::domain::models::auth::AuthLogin;
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::{CreateUserHashed, Role};
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::domain::services::user::UserService;
+use iron_cms_api::services::concrete::argon2id_hash::Argon2IdHashService;
+use iron_cms_api::services::concrete::composite_hash::CompositeHashService;
+use iron_cms_api::services::concrete::hashing_pool::HashingPool;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::traits::password_hash::PasswordHashService;
+
+use common::{new_user, Services, Setup, PASSWORD};
+
+const EMAIL: &str = "legacy@example.com";
+
+/// `"password"` hashed by Passlib's `pbkdf2_sha256`, from its documentation.
+const PASSLIB_PBKDF2_HASH: &str = "$pbkdf2-sha256$6400$0ZrzXitFSGltTQnBWOsdAw$Y11AchqV4b0sUisdZd0Xr97KWoymNE0LNNrnEgY4H9M";
+
+fn composite_hasher() -> CompositeHashService {
+    let pool = Arc::new(HashingPool::new(2, 8));
+    let current = Argon2IdHashService::with_params(Params::new(2048, 2, 1, None).unwrap(), pool.clone());
+    CompositeHashService::new(Arc::new(current), pool)
+}
+
+fn pbkdf2_phc_hash(password: &str) -> String {
+    use pbkdf2::password_hash::PasswordHasher as _;
+
+    let salt = SaltString::generate(&mut OsRng);
+    let params = pbkdf2::Params { rounds: 1000, output_length: 32 };
+    pbkdf2::Pbkdf2
+        .hash_password_customized(password.as_bytes(), Some(pbkdf2::Algorithm::Pbkdf2Sha256.ident()), None, params, &salt)
+        .unwrap()
+        .to_string()
+}
+
+/// Services whose only user signs in with `PASSWORD`, stored as `password_hash`.
+fn services(password_hash: &str, hash_service: Arc<dyn PasswordHashService>) -> (Services, Uuid) {
+    let services = Services::new(Setup { hash_service: Some(hash_service), ..Setup::default() });
+    let user_id = services.users.insert(new_user(EMAIL, Role::User, password_hash)).id;
+    (services, user_id)
+}
+
+async fn login(services: &Services) -> bool {
+    let credentials = AuthLogin { email: EMAIL.to_string(), password: PASSWORD.to_string() };
+    services.auth.login(credentials, SessionClient::default()).await.is_ok()
+}
+
+#[actix_web::test]
+async fn legacy_hashes_are_verified() {
+    let hash_service = composite_hasher();
+    let bcrypt_hash = bcrypt::hash(PASSWORD, 4).unwrap();
+    let pbkdf2_hash = pbkdf2_phc_hash(PASSWORD);
+
+    for hash in [&bcrypt_hash, &pbkdf2_hash] {
+        assert!(hash_service.is_supported(hash));
+        assert!(hash_service.needs_rehash(hash));
+        assert!(hash_service.verify_password(PASSWORD, hash).await.unwrap());
+        assert!(hash_service.verify_password("wrong password", hash).await.is_err());
+    }
+
+    assert!(hash_service.is_supported(PASSLIB_PBKDF2_HASH));
+    assert!(hash_service.verify_password("password", PASSLIB_PBKDF2_HASH).await.unwrap());
+    assert!(hash_service.verify_password("wrong password", PASSLIB_PBKDF2_HASH).await.is_err());
+}
+
+#[test]
+fn unknown_hash_formats_are_not_supported() {
+    let hash_service = composite_hasher();
+
+    for hash in ["", "plaintext", "$1$salt$md5crypt", "$pbkdf2-sha256$rounds$salt$hash", "$2b$04$short"] {
+        assert!(!hash_service.is_supported(hash), "{} should not be supported", hash);
+    }
+}
+
+#[actix_web::test]
+async fn costs_above_the_ceiling_are_not_supported() {
+    let hash_service = composite_hasher();
+    let bcrypt_hash = bcrypt::hash(PASSWORD, 4).unwrap();
+    let pbkdf2_hash = pbkdf2_phc_hash(PASSWORD);
+
+    // Only the cost is rewritten; the ceiling is checked before any hashing.
+    let at_ceiling = [
+        bcrypt_hash.replacen("$04$", "$14$", 1),
+        pbkdf2_hash.replacen("i=1000,", "i=1000000,", 1),
+        PASSLIB_PBKDF2_HASH.replacen("$6400$", "$1000000$", 1),
+    ];
+    for hash in &at_ceiling {
+        assert!(hash_service.is_supported(hash), "{} should be supported", hash);
+    }
+
+    let above_ceiling = [
+        bcrypt_hash.replacen("$04$", "$15$", 1),
+        bcrypt_hash.replacen("$04$", "$31$", 1),
+        pbkdf2_hash.replacen("i=1000,", "i=1000001,", 1),
+        PASSLIB_PBKDF2_HASH.replacen("$6400$", "$4294967295$", 1),
+    ];
+    for hash in &above_ceiling {
+        assert!(!hash_service.is_supported(hash), "{} should not be supported", hash);
+        let err = hash_service.verify_password(PASSWORD, hash).await.unwrap_err();
+        assert_eq!(err.identifier, constants::SEC_ERR_HASH_PARSE_FAIL);
+    }
+}
+
+#[actix_web::test]
+async fn imports_refuse_costs_above_the_ceiling() {
+    let (services, _) = services(&bcrypt::hash(PASSWORD, 4).unwrap(), Arc::new(composite_hasher()));
+    let super_admin = services.add_user("super@example.com", Role::SuperAdmin).await;
+
+    let imported = CreateUserHashed {
+        role: None,
+        name: "Imported".to_string(),
+        email: "imported@example.com".to_string(),
+        password_hash: bcrypt::hash(PASSWORD, 4).unwrap().replacen("$04$", "$20$", 1),
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    let err = services.user_service.import(vec![imported], &super_admin).await.unwrap_err();
+    assert_eq!(err.errors[0].identifier.as_deref(), Some(constants::VAL_ERR_UNSUPPORTED_PASSWORD_HASH));
+    assert_eq!(err.errors[0].context, "users[0].password_hash");
+}
+
+#[actix_web::test]
+async fn imports_refuse_argon2_costs_above_the_ceiling() {
+    let (services, _) = services(&bcrypt::hash(PASSWORD, 4).unwrap(), Arc::new(composite_hasher()));
+    let super_admin = services.add_user("super@example.com", Role::SuperAdmin).await;
+    let hash = services.hash_service.hash_password(PASSWORD).await.unwrap();
+    let params = hash.split('$').nth(3).unwrap().to_string();
+
+    let costly = [
+        hash.replacen(&params, "m=4194304,t=1000,p=4", 1),
+        hash.replacen(&params, "m=2048,t=17,p=1", 1),
+        hash.replacen(&params, "m=2048,t=2,p=17", 1),
+    ];
+    for hash in &costly {
+        assert!(!services.hash_service.is_supported(hash), "{} should not be supported", hash);
+        let err = services.hash_service.verify_password(PASSWORD, hash).await.unwrap_err();
+        assert_eq!(err.identifier, constants::SEC_ERR_HASH_PARSE_FAIL);
+    }
+
+    let imported = |index: usize, password_hash: &str| CreateUserHashed {
+        role: None,
+        name: "Imported".to_string(),
+        email: std::format!("imported{}@example.com", index),
+        password_hash: password_hash.to_string(),
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    let new_users = vec![imported(0, &hash), imported(1, &costly[0])];
+    let err = services.user_service.import(new_users, &super_admin).await.unwrap_err();
+    assert_eq!(err.errors.len(), 1);
+    assert_eq!(err.errors[0].identifier.as_deref(), Some(constants::VAL_ERR_UNSUPPORTED_PASSWORD_HASH));
+    assert_eq!(err.errors[0].context, "users[1].password_hash");
+}
+
+#[actix_web::test]
+async fn login_upgrades_legacy_hashes() {
+    for legacy in [bcrypt::hash(PASSWORD, 4).unwrap(), pbkdf2_phc_hash(PASSWORD)] {
+        let hash_service: Arc<dyn PasswordHashService> = Arc::new(composite_hasher());
+        let (services, user_id) = services(&legacy, hash_service.clone());
+
+        assert!(login(&services).await);
+
+        let upgraded = services.users.user(user_id).password_hash;
+        assert_eq!(services.users.updates(), 1);
+        assert!(upgraded.starts_with("$argon2id$"));
+        assert!(!hash_service.needs_rehash(&upgraded));
+        assert!(login(&services).await);
+        assert_eq!(services.users.updates(), 1);
+    }
+}
@@ -0,0 +1,84 @@
+//! Covers signing in with an email and password.
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use iron_cms_api::domain::models::auth::{AuthLogin, AuthLoginOutcome};
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::services::concrete::argon2id_hash::Argon2IdHashService;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::error::SecurityError;
+use iron_cms_api::services::traits::password_hash::PasswordHashService;
+
+use common::{fast_hasher, Services, Setup, PASSWORD};
+
+const EMAIL: &str = "login@example.com";
+
+/// Counts the verifications, which is where the time of a login goes.
+struct CountingHasher {
+    inner: Argon2IdHashService,
+    verifications: AtomicUsize,
+}
+
+#[async_trait]
+impl PasswordHashService for CountingHasher {
+    async fn hash_password(&self, password: &str) -> Result<String, SecurityError> {
+        self.inner.hash_password(password).await
+    }
+
+    async fn verify_password(&self, password: &str, hash: &str) -> Result<bool, SecurityError> {
+        self.verifications.fetch_add(1, Ordering::SeqCst);
+        self.inner.verify_password(password, hash).await
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        self.inner.needs_rehash(hash)
+    }
+
+    fn is_supported(&self, hash: &str) -> bool {
+        self.inner.is_supported(hash)
+    }
+}
+
+fn credentials(email: &str, password: &str) -> AuthLogin {
+    AuthLogin { email: email.to_string(), password: password.to_string() }
+}
+
+#[actix_web::test]
+async fn right_password_signs_in() {
+    let services = Services::new(Setup::default());
+    let user = services.add_user(EMAIL, Role::User).await;
+
+    let outcome = services.auth.login(credentials(" LOGIN@example.com ", PASSWORD), SessionClient::default()).await.unwrap();
+    let AuthLoginOutcome::Authenticated(tokens) = outcome else {
+        panic!("login asked for a second factor");
+    };
+    let claims = services.auth.validate_access_token(&tokens.token).await.unwrap();
+    assert_eq!(claims.sub, user.id.to_string());
+    assert_eq!(services.sessions.count(), 1);
+}
+
+#[actix_web::test]
+async fn unknown_email_and_wrong_password_look_the_same() {
+    let hash_service = Arc::new(CountingHasher { inner: fast_hasher(), verifications: AtomicUsize::new(0) });
+    let services = Services::new(Setup { hash_service: Some(hash_service.clone()), ..Setup::default() });
+    services.add_user(EMAIL, Role::User).await;
+
+    let wrong_password = services.auth.login(credentials(EMAIL, "wrong password"), SessionClient::default()).await.unwrap_err();
+    assert_eq!(hash_service.verifications.load(Ordering::SeqCst), 1);
+
+    let unknown_email = services.auth.login(credentials("nobody@example.com", PASSWORD), SessionClient::default()).await.unwrap_err();
+    // The unknown email is checked against a stand-in hash all the same.
+    assert_eq!(hash_service.verifications.load(Ordering::SeqCst), 2);
+
+    assert_eq!(wrong_password.identifier, constants::SEC_ERR_INVALID_CREDENTIALS);
+    assert_eq!(unknown_email.identifier, wrong_password.identifier);
+    assert_eq!(unknown_email.message, wrong_password.message);
+    assert_eq!(services.sessions.count(), 0);
+}
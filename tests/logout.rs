@@ -0,0 +1,77 @@
+//! Covers logging out of one session or all of them, and that revoked tokens
+//! stop working.
+
+mod common;
+
+use iron_cms_api::domain::models::auth::{AuthLogin, AuthLoginOutcome, AuthRefresh, AuthSuccessfulResponse};
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::services::constants;
+
+use common::{Services, Setup, PASSWORD};
+
+const EMAIL: &str = "logout@example.com";
+
+async fn login(services: &Services) -> AuthSuccessfulResponse {
+    let credentials = AuthLogin { email: EMAIL.to_string(), password: PASSWORD.to_string() };
+    match services.auth.login(credentials, SessionClient::default()).await.unwrap() {
+        AuthLoginOutcome::Authenticated(tokens) => tokens,
+        AuthLoginOutcome::MfaRequired(_) => panic!("login asked for a second factor"),
+    }
+}
+
+async fn refresh(services: &Services, tokens: &AuthSuccessfulResponse) -> Result<AuthSuccessfulResponse, String> {
+    let refresh = AuthRefresh { refresh_token: tokens.refresh_token.clone() };
+    services.auth.refresh(refresh, SessionClient::default()).await.map_err(|err| err.identifier)
+}
+
+#[actix_web::test]
+async fn logout_revokes_both_tokens_of_the_session() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    let tokens = login(&services).await;
+    let other = login(&services).await;
+
+    let claims = services.auth.validate_access_token(&tokens.token).await.unwrap();
+    services.auth.logout(&claims).await.unwrap();
+
+    let err = services.auth.authenticate(&tokens.token).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_REVOKED);
+    assert_eq!(refresh(&services, &tokens).await.unwrap_err(), constants::SEC_ERR_TOKEN_REVOKED);
+    // Other sessions are left alone.
+    assert!(services.auth.authenticate(&other.token).await.is_ok());
+}
+
+#[actix_web::test]
+async fn logout_all_revokes_every_session() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    let first = login(&services).await;
+    let second = login(&services).await;
+
+    let claims = services.auth.validate_access_token(&second.token).await.unwrap();
+    services.auth.logout_all(&claims).await.unwrap();
+
+    for tokens in [&first, &second] {
+        assert!(services.auth.authenticate(&tokens.token).await.is_err());
+        assert!(refresh(&services, tokens).await.is_err());
+    }
+    assert_eq!(services.sessions.count(), 0);
+}
+
+#[actix_web::test]
+async fn logout_all_reaches_access_tokens_of_refreshed_sessions() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    let original = login(&services).await;
+    let refreshed = refresh(&services, &original).await.unwrap();
+
+    // The access token of the replaced session is revoked right away.
+    let err = services.auth.authenticate(&original.token).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_REVOKED);
+
+    let claims = services.auth.validate_access_token(&refreshed.token).await.unwrap();
+    services.auth.logout_all(&claims).await.unwrap();
+    assert!(services.auth.authenticate(&refreshed.token).await.is_err());
+}
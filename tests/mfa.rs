@@ -0,0 +1,253 @@
+//! Covers TOTP two-factor authentication: the RFC 6238 codes themselves,
+//! enrolling, the two-step login and recovery codes. Everything runs against
+//! in-memory repositories, so no database is needed.
+
+mod common;
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use iron_cms_api::domain::models::auth::{AuthLogin, AuthLoginOutcome, AuthMfaChallenge, AuthMfaCode, AuthMfaVerify};
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::{Role, User};
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::domain::services::mfa::MfaService;
+use iron_cms_api::infrastructure::services::auth::AuthServiceImpl;
+use iron_cms_api::infrastructure::services::mfa::{MfaPolicy, MfaServiceImpl};
+use iron_cms_api::services::concrete::secret_cipher::SecretCipher;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::utils::totp;
+
+use common::{InMemoryUserRepository, Services, Setup, MFA_KEY as KEY, PASSWORD};
+
+const EMAIL: &str = "mfa@example.com";
+
+struct Fixture {
+    repository: Arc<InMemoryUserRepository>,
+    mfa: Arc<MfaServiceImpl>,
+    auth: Arc<AuthServiceImpl>,
+    user_id: Uuid,
+}
+
+impl Fixture {
+    async fn new(role: Role, required_roles: Vec<Role>) -> Self {
+        let services = Services::new(Setup {
+            mfa: MfaPolicy { required_roles, issuer: "Iron CMS".to_string(), skew_steps: 1 },
+            ..Setup::default()
+        });
+        let user_id = services.add_user(EMAIL, role).await.id;
+        Fixture { repository: services.users, mfa: services.mfa, auth: services.auth, user_id }
+    }
+
+    async fn user(&self) -> User {
+        self.repository.user(self.user_id)
+    }
+
+    /// The secret as stored, decrypted the way the service does it.
+    async fn secret(&self) -> Vec<u8> {
+        let user = self.user().await;
+        SecretCipher::new(&KEY).open(user.totp_secret.as_deref().unwrap(), user.id.as_bytes()).unwrap()
+    }
+
+    /// The code of the time step `offset` steps away from now.
+    async fn code(&self, offset: i64) -> String {
+        totp::code_at(&self.secret().await, totp::time_step(Utc::now().timestamp()) + offset)
+    }
+
+    /// Enables TOTP with the current code and returns the recovery codes.
+    async fn enable(&self) -> Vec<String> {
+        self.mfa.enroll(&self.user().await).await.unwrap();
+        let code = AuthMfaCode { code: self.code(0).await };
+        self.mfa.confirm_enrollment(&self.user().await, code).await.unwrap().codes
+    }
+
+    async fn login(&self) -> AuthLoginOutcome {
+        let credentials = AuthLogin { email: EMAIL.to_string(), password: PASSWORD.to_string() };
+        self.auth.login(credentials, SessionClient::default()).await.unwrap()
+    }
+
+    async fn challenge(&self) -> AuthMfaChallenge {
+        match self.login().await {
+            AuthLoginOutcome::MfaRequired(challenge) => challenge,
+            AuthLoginOutcome::Authenticated(_) => panic!("login didn't ask for a second factor"),
+        }
+    }
+}
+
+fn verification(challenge: &AuthMfaChallenge, code: String) -> AuthMfaVerify {
+    AuthMfaVerify { challenge_token: challenge.challenge_token.clone(), code }
+}
+
+#[test]
+fn codes_match_rfc_6238_test_vectors() {
+    let secret = b"12345678901234567890";
+    let vectors = [
+        (59, "94287082"),
+        (1_111_111_109, "07081804"),
+        (1_111_111_111, "14050471"),
+        (1_234_567_890, "89005924"),
+        (2_000_000_000, "69279037"),
+        (20_000_000_000, "65353130"),
+    ];
+    for (timestamp, code) in vectors {
+        assert_eq!(totp::hotp(secret, totp::time_step(timestamp) as u64, 8), code);
+    }
+    assert_eq!(totp::code_at(secret, totp::time_step(59)), "287082");
+}
+
+#[test]
+fn base32_matches_rfc_4648_test_vectors() {
+    let vectors = [("", ""), ("f", "MY"), ("fo", "MZXQ"), ("foo", "MZXW6"), ("foob", "MZXW6YQ"), ("fooba", "MZXW6YTB"), ("foobar", "MZXW6YTBOI")];
+    for (plain, encoded) in vectors {
+        assert_eq!(totp::base32_encode(plain.as_bytes()), encoded);
+    }
+}
+
+#[test]
+fn otpauth_uri_encodes_its_label() {
+    assert_eq!(
+        totp::otpauth_uri("Iron CMS", "a+b@example.com", "ABC"),
+        "otpauth://totp/Iron%20CMS:a%2Bb%40example.com?secret=ABC&issuer=Iron%20CMS&algorithm=SHA1&digits=6&period=30"
+    );
+}
+
+#[test]
+fn secrets_only_open_for_their_owner() {
+    let cipher = SecretCipher::new(&KEY);
+    let sealed = cipher.seal(b"secret", b"owner").unwrap();
+
+    assert_eq!(cipher.open(&sealed, b"owner").unwrap(), b"secret");
+    assert!(cipher.open(&sealed, b"someone else").is_err());
+}
+
+#[test]
+fn required_roles_are_parsed_by_name() {
+    let roles = MfaPolicy::parse_required_roles(" admin, super_admin ,").unwrap();
+    assert_eq!(roles, vec![Role::Admin, Role::SuperAdmin]);
+    assert!(MfaPolicy::parse_required_roles("root").is_err());
+}
+
+#[actix_web::test]
+async fn enrollment_takes_effect_once_confirmed() {
+    let fixture = Fixture::new(Role::User, Vec::new()).await;
+    let enrollment = fixture.mfa.enroll(&fixture.user().await).await.unwrap();
+    assert_eq!(enrollment.secret, totp::base32_encode(&fixture.secret().await));
+    assert!(enrollment.otpauth_uri.contains(&std::format!("secret={}", enrollment.secret)));
+
+    // Until confirmed, the password alone still signs in.
+    assert!(matches!(fixture.login().await, AuthLoginOutcome::Authenticated(_)));
+    let err = fixture.mfa.confirm_enrollment(&fixture.user().await, AuthMfaCode { code: "000000".to_string() }).await;
+    assert_eq!(err.unwrap_err().identifier, constants::SEC_ERR_MFA_CODE_INVALID);
+
+    let code = AuthMfaCode { code: fixture.code(0).await };
+    let recovery_codes = fixture.mfa.confirm_enrollment(&fixture.user().await, code).await.unwrap();
+    assert_eq!(recovery_codes.codes.len(), constants::SEC_MFA_RECOVERY_CODE_COUNT);
+
+    let err = fixture.mfa.enroll(&fixture.user().await).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_MFA_ALREADY_ENABLED);
+}
+
+#[actix_web::test]
+async fn login_asks_for_a_code_once_enabled() {
+    let fixture = Fixture::new(Role::User, Vec::new()).await;
+    fixture.enable().await;
+
+    let challenge = fixture.challenge().await;
+    // The confirming code was used up, so the next one is needed.
+    let tokens = fixture.auth.verify_mfa(verification(&challenge, fixture.code(1).await), SessionClient::default()).await.unwrap();
+
+    let claims = fixture.auth.validate_access_token(&tokens.token).await.unwrap();
+    assert!(claims.mfa);
+}
+
+#[actix_web::test]
+async fn challenge_is_used_up_by_a_wrong_code() {
+    let fixture = Fixture::new(Role::User, Vec::new()).await;
+    fixture.enable().await;
+
+    let challenge = fixture.challenge().await;
+    let err = fixture.auth.verify_mfa(verification(&challenge, "000000".to_string()), SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_MFA_CODE_INVALID);
+
+    let err = fixture.auth.verify_mfa(verification(&challenge, fixture.code(1).await), SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_MFA_CHALLENGE_INVALID);
+}
+
+#[actix_web::test]
+async fn codes_are_accepted_within_the_skew_window_once() {
+    let fixture = Fixture::new(Role::User, Vec::new()).await;
+    fixture.mfa.enroll(&fixture.user().await).await.unwrap();
+    let code = AuthMfaCode { code: fixture.code(-1).await };
+    fixture.mfa.confirm_enrollment(&fixture.user().await, code).await.unwrap();
+
+    let user = fixture.user().await;
+    assert!(fixture.mfa.verify_code(&user, &fixture.code(2).await).await.is_err());
+    fixture.mfa.verify_code(&user, &fixture.code(0).await).await.unwrap();
+    let err = fixture.mfa.verify_code(&user, &fixture.code(0).await).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_MFA_CODE_INVALID);
+    // Older codes stop working once a newer one was used.
+    assert!(fixture.mfa.verify_code(&user, &fixture.code(-1).await).await.is_err());
+}
+
+#[actix_web::test]
+async fn recovery_codes_work_once() {
+    let fixture = Fixture::new(Role::User, Vec::new()).await;
+    let recovery_codes = fixture.enable().await;
+    let user = fixture.user().await;
+
+    // Typed in by hand, without dashes and in lower case.
+    let typed = recovery_codes[0].replace('-', "").to_lowercase();
+    fixture.mfa.verify_code(&user, &typed).await.unwrap();
+    assert!(fixture.mfa.verify_code(&user, &recovery_codes[0]).await.is_err());
+
+    let regenerated = fixture.mfa.regenerate_recovery_codes(&user, AuthMfaCode { code: recovery_codes[1].clone() }).await.unwrap();
+    assert!(fixture.mfa.verify_code(&user, &recovery_codes[2]).await.is_err());
+    fixture.mfa.verify_code(&user, &regenerated.codes[0]).await.unwrap();
+}
+
+#[actix_web::test]
+async fn disabling_takes_a_code() {
+    let fixture = Fixture::new(Role::User, Vec::new()).await;
+    let recovery_codes = fixture.enable().await;
+
+    let err = fixture.mfa.disable(&fixture.user().await, AuthMfaCode { code: "not-a-code".to_string() }).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_MFA_CODE_INVALID);
+
+    fixture.mfa.disable(&fixture.user().await, AuthMfaCode { code: recovery_codes[0].clone() }).await.unwrap();
+    assert!(fixture.user().await.totp_secret.is_none());
+    assert!(matches!(fixture.login().await, AuthLoginOutcome::Authenticated(_)));
+}
+
+#[actix_web::test]
+async fn required_roles_need_tokens_from_a_second_factor() {
+    let fixture = Fixture::new(Role::Admin, vec![Role::Admin, Role::SuperAdmin]).await;
+
+    let AuthLoginOutcome::Authenticated(tokens) = fixture.login().await else {
+        panic!("login asked for a second factor before enrolling");
+    };
+    let password_only = fixture.auth.authenticate(&tokens.token).await.unwrap();
+    let err = fixture.mfa.ensure_satisfied(&password_only).unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_MFA_REQUIRED);
+
+    fixture.enable().await;
+    // Tokens from before enrolling still don't count.
+    let enrolled = fixture.auth.authenticate(&tokens.token).await.unwrap();
+    assert!(fixture.mfa.ensure_satisfied(&enrolled).is_err());
+
+    let challenge = fixture.challenge().await;
+    let tokens = fixture.auth.verify_mfa(verification(&challenge, fixture.code(1).await), SessionClient::default()).await.unwrap();
+    let authenticated = fixture.auth.authenticate(&tokens.token).await.unwrap();
+    assert!(fixture.mfa.ensure_satisfied(&authenticated).is_ok());
+}
+
+#[actix_web::test]
+async fn other_roles_are_not_held_to_the_policy() {
+    let fixture = Fixture::new(Role::User, vec![Role::Admin]).await;
+    let AuthLoginOutcome::Authenticated(tokens) = fixture.login().await else {
+        panic!("login asked for a second factor before enrolling");
+    };
+    let authenticated = fixture.auth.authenticate(&tokens.token).await.unwrap();
+    assert!(fixture.mfa.ensure_satisfied(&authenticated).is_ok());
+}
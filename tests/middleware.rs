@@ -0,0 +1,215 @@
+//! Covers the authentication and permission middleware in front of the routes.
+
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use actix_web::{test, web, App, Error, HttpResponse};
+use chrono::Utc;
+
+use iron_cms_api::api::middleware::{Authentication, RequirePermission};
+use iron_cms_api::domain::models::admin_permission::AdminPermissions;
+use iron_cms_api::domain::models::auth::{AuthLogin, AuthLoginOutcome, AuthMfaCode, AuthMfaVerify, AuthenticatedUser};
+use iron_cms_api::domain::models::common::ErrorResponse;
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::{Role, User};
+use iron_cms_api::domain::services::admin_permission::AdminPermissionService;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::domain::services::email_verification::EmailVerificationService;
+use iron_cms_api::domain::services::mfa::MfaService;
+use iron_cms_api::services::concrete::secret_cipher::SecretCipher;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::utils::totp;
+
+use common::{Services, Setup, MFA_KEY, PASSWORD};
+
+async fn public_handler() -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+async fn caller_handler(authenticated_user: AuthenticatedUser) -> HttpResponse {
+    HttpResponse::Ok().body(authenticated_user.user.id.to_string())
+}
+
+fn app(services: &Services) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Response = ServiceResponse<impl MessageBody>,
+        Config = (),
+        InitError = (),
+        Error = Error,
+    >,
+> {
+    App::new()
+        .app_data(web::Data::from(services.auth.clone() as Arc<dyn AuthService>))
+        .app_data(web::Data::from(services.email_verification.clone() as Arc<dyn EmailVerificationService>))
+        .app_data(web::Data::from(services.mfa.clone() as Arc<dyn MfaService>))
+        .app_data(web::Data::from(services.admin_permission_service.clone() as Arc<dyn AdminPermissionService>))
+        .service(
+            web::scope("/api").wrap(Authentication)
+                .route("/public", web::get().to(public_handler))
+                .route("/me", web::get().to(caller_handler))
+                .service(
+                    web::scope("/users")
+                        .wrap(RequirePermission::new(AdminPermissions::CanManageUsers))
+                        .route("", web::get().to(public_handler))
+                )
+                .service(
+                    web::scope("/reports")
+                        .wrap(RequirePermission::new(AdminPermissions::CanViewReports))
+                        .route("", web::get().to(public_handler))
+                )
+        )
+}
+
+async fn access_token(services: &Services, email: &str) -> String {
+    let credentials = AuthLogin { email: email.to_string(), password: PASSWORD.to_string() };
+    match services.auth.login(credentials, SessionClient::default()).await.unwrap() {
+        AuthLoginOutcome::Authenticated(tokens) => tokens.token,
+        AuthLoginOutcome::MfaRequired(_) => panic!("login asked for a second factor"),
+    }
+}
+
+/// Enables TOTP for the user and signs in with both factors.
+async fn mfa_access_token(services: &Services, user: &User) -> String {
+    services.mfa.enroll(user).await.unwrap();
+    let code = |user_id, offset| {
+        let user = services.users.user(user_id);
+        let secret = SecretCipher::new(&MFA_KEY).open(user.totp_secret.as_deref().unwrap(), user.id.as_bytes()).unwrap();
+        totp::code_at(&secret, totp::time_step(Utc::now().timestamp()) + offset)
+    };
+    let confirmation = AuthMfaCode { code: code(user.id, 0) };
+    services.mfa.confirm_enrollment(&services.users.user(user.id), confirmation).await.unwrap();
+
+    let credentials = AuthLogin { email: user.email.clone(), password: PASSWORD.to_string() };
+    let AuthLoginOutcome::MfaRequired(challenge) = services.auth.login(credentials, SessionClient::default()).await.unwrap() else {
+        panic!("login didn't ask for a second factor");
+    };
+    // The enrollment used up the current code.
+    let verification = AuthMfaVerify { challenge_token: challenge.challenge_token, code: code(user.id, 1) };
+    services.auth.verify_mfa(verification, SessionClient::default()).await.unwrap().token
+}
+
+fn get(path: &str, authorization: Option<&str>) -> TestRequest {
+    let request = TestRequest::get().uri(path);
+    match authorization {
+        Some(value) => request.insert_header((AUTHORIZATION, value)),
+        None => request,
+    }
+}
+
+/// The status of the response and the identifier of its error, if any.
+/// Middleware errors come back as `Err`, handler errors as responses.
+async fn call<S, R, B>(app: &S, request: R) -> (StatusCode, Option<String>)
+where
+    S: Service<R, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody + 'static,
+{
+    let response = match app.call(request).await {
+        Ok(response) => response.map_into_boxed_body().into_parts().1,
+        Err(err) => err.error_response(),
+    };
+    let status = response.status();
+    if status.is_success() {
+        return (status, None);
+    }
+    let body = actix_web::body::to_bytes(response.into_body()).await.ok().unwrap();
+    let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+    (status, body.errors[0].identifier.clone())
+}
+
+fn unauthorized(identifier: &str) -> (StatusCode, Option<String>) {
+    (StatusCode::UNAUTHORIZED, Some(identifier.to_string()))
+}
+
+#[actix_web::test]
+async fn public_routes_ignore_tokens_that_fail_to_authenticate() {
+    let services = Services::new(Setup::default());
+    let app = test::init_service(app(&services)).await;
+
+    for authorization in [None, Some("Bearer not-a-token"), Some("Basic dXNlcjpwYXNz")] {
+        assert_eq!(call(&app, get("/api/public", authorization).to_request()).await, (StatusCode::OK, None));
+    }
+}
+
+#[actix_web::test]
+async fn routes_needing_a_caller_report_why_there_is_none() {
+    let services = Services::new(Setup::default());
+    let user = services.add_user("caller@example.com", Role::User).await;
+    let token = access_token(&services, "caller@example.com").await;
+    let app = test::init_service(app(&services)).await;
+
+    let response = app.call(get("/api/me", Some(&format!("Bearer {token}"))).to_request()).await.unwrap();
+    assert_eq!(test::read_body(response).await, user.id.to_string());
+
+    assert_eq!(call(&app, get("/api/me", None).to_request()).await, unauthorized(constants::SEC_ERR_TOKEN_MISSING));
+    assert_eq!(call(&app, get("/api/me", Some("Bearer not-a-token")).to_request()).await.0, StatusCode::UNAUTHORIZED);
+    assert_eq!(call(&app, get("/api/me", Some("Basic dXNlcjpwYXNz")).to_request()).await, unauthorized(constants::SEC_ERR_TOKEN_INVALID));
+}
+
+#[actix_web::test]
+async fn revoked_tokens_are_refused() {
+    let services = Services::new(Setup::default());
+    let user = services.add_user("revoked@example.com", Role::User).await;
+    services.admin_permissions.grant(user.id, AdminPermissions::CanManageUsers);
+    let token = access_token(&services, "revoked@example.com").await;
+    let app = test::init_service(app(&services)).await;
+
+    let claims = services.auth.validate_access_token(&token).await.unwrap();
+    services.auth.logout(&claims).await.unwrap();
+
+    let authorization = format!("Bearer {token}");
+    assert_eq!(call(&app, get("/api/me", Some(&authorization)).to_request()).await, unauthorized(constants::SEC_ERR_TOKEN_REVOKED));
+    assert_eq!(call(&app, get("/api/users", Some(&authorization)).to_request()).await, unauthorized(constants::SEC_ERR_TOKEN_REVOKED));
+    assert_eq!(call(&app, get("/api/public", Some(&authorization)).to_request()).await, (StatusCode::OK, None));
+}
+
+#[actix_web::test]
+async fn permissions_are_required_unless_super_admin() {
+    let services = Services::new(Setup::default());
+    services.add_user("user@example.com", Role::User).await;
+    let granted = services.add_user("granted@example.com", Role::Admin).await;
+    services.admin_permissions.grant(granted.id, AdminPermissions::CanViewReports);
+    services.add_user("super@example.com", Role::SuperAdmin).await;
+    let app = test::init_service(app(&services)).await;
+
+    let token = access_token(&services, "user@example.com").await;
+    let forbidden = (StatusCode::FORBIDDEN, Some(constants::SEC_ERR_PERMISSION_DENIED.to_string()));
+    assert_eq!(call(&app, get("/api/reports", Some(&format!("Bearer {token}"))).to_request()).await, forbidden);
+
+    for email in ["granted@example.com", "super@example.com"] {
+        let token = access_token(&services, email).await;
+        assert_eq!(call(&app, get("/api/reports", Some(&format!("Bearer {token}"))).to_request()).await, (StatusCode::OK, None));
+    }
+    assert_eq!(call(&app, get("/api/reports", None).to_request()).await, unauthorized(constants::SEC_ERR_TOKEN_MISSING));
+}
+
+#[actix_web::test]
+async fn managing_users_takes_a_second_factor() {
+    // No role is held to the MFA policy, yet the permission still needs it.
+    let services = Services::new(Setup::default());
+    services.add_user("user@example.com", Role::User).await;
+    let granted = services.add_user("granted@example.com", Role::Admin).await;
+    services.admin_permissions.grant(granted.id, AdminPermissions::CanManageUsers);
+    let super_admin = services.add_user("super@example.com", Role::SuperAdmin).await;
+    let app = test::init_service(app(&services)).await;
+
+    // Callers without the permission are told so, not asked for a second factor.
+    let token = access_token(&services, "user@example.com").await;
+    let forbidden = (StatusCode::FORBIDDEN, Some(constants::SEC_ERR_PERMISSION_DENIED.to_string()));
+    assert_eq!(call(&app, get("/api/users", Some(&format!("Bearer {token}"))).to_request()).await, forbidden);
+
+    let mfa_required = (StatusCode::FORBIDDEN, Some(constants::SEC_ERR_MFA_REQUIRED.to_string()));
+    for user in [granted, super_admin] {
+        let token = access_token(&services, &user.email).await;
+        assert_eq!(call(&app, get("/api/users", Some(&format!("Bearer {token}"))).to_request()).await, mfa_required);
+
+        let token = mfa_access_token(&services, &user).await;
+        assert_eq!(call(&app, get("/api/users", Some(&format!("Bearer {token}"))).to_request()).await, (StatusCode::OK, None));
+    }
+}
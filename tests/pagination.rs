@@ -0,0 +1,72 @@
+//! Covers the paging window taken from the query string and the metadata
+//! returned with each page.
+
+mod common;
+
+use iron_cms_api::domain::models::common::PaginationParams;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::repositories::admin_permission::AdminPermissionQueryParams;
+use iron_cms_api::domain::repositories::repository::{QueryParams, QueryParamsImpl, MAX_LIMIT};
+use iron_cms_api::domain::repositories::session::SessionQueryParams;
+use iron_cms_api::domain::repositories::user::UserQueryParams;
+use iron_cms_api::domain::services::user::UserService;
+
+use common::{Services, Setup};
+
+fn window(limit: Option<i64>, offset: Option<i64>) -> Vec<(i64, i64)> {
+    let params: Vec<Box<dyn QueryParams>> = vec![
+        Box::new(QueryParamsImpl { limit, offset }),
+        Box::new(UserQueryParams { limit, offset }),
+        Box::new(SessionQueryParams { limit, offset }),
+        Box::new(AdminPermissionQueryParams { limit, offset }),
+    ];
+    params.iter().map(|params| (params.limit(), params.offset())).collect()
+}
+
+#[test]
+fn window_defaults_to_the_first_page() {
+    assert!(window(None, None).iter().all(|&window| window == (25, 0)));
+}
+
+#[test]
+fn window_is_kept_in_range() {
+    assert!(window(Some(0), Some(-1)).iter().all(|&window| window == (1, 0)));
+    assert!(window(Some(-5), Some(i64::MIN)).iter().all(|&window| window == (1, 0)));
+    assert!(window(Some(i64::MAX), Some(40)).iter().all(|&window| window == (MAX_LIMIT, 40)));
+    assert!(window(Some(10), Some(i64::MAX)).iter().all(|&window| window == (10, i64::MAX)));
+}
+
+#[test]
+fn pages_are_counted_from_the_total() {
+    let pagination = PaginationParams::new(51, 25, 25);
+    assert_eq!((pagination.total_pages, pagination.current_page, pagination.has_next), (3, 2, true));
+
+    let pagination = PaginationParams::new(50, 25, 25);
+    assert_eq!((pagination.total_pages, pagination.current_page, pagination.has_next), (2, 2, false));
+
+    let pagination = PaginationParams::new(0, 25, 0);
+    assert_eq!((pagination.total_pages, pagination.current_page, pagination.has_next), (0, 1, false));
+}
+
+#[test]
+fn extreme_windows_do_not_overflow() {
+    let pagination = PaginationParams::new(i64::MAX, MAX_LIMIT, i64::MAX);
+    assert_eq!(pagination.total_pages, i64::MAX / MAX_LIMIT);
+    assert!(!pagination.has_next);
+
+    let pagination = PaginationParams::new(10, i64::MAX, 0);
+    assert_eq!((pagination.total_pages, pagination.has_next), (1, false));
+}
+
+#[actix_web::test]
+async fn listing_reports_the_window_it_used() {
+    let services = Services::new(Setup::default());
+    for index in 0..3 {
+        services.add_user(&format!("user{index}@example.com"), Role::User).await;
+    }
+
+    let page = services.user_service.list(UserQueryParams { limit: Some(0), offset: Some(-10) }).await.unwrap();
+    assert_eq!((page.limit, page.offset, page.items.len()), (1, 0, 1));
+    let pagination = page.pagination();
+    assert_eq!((pagination.total_pages, pagination.has_next), (3, true));
+}
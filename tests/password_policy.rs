@@ -0,0 +1,108 @@
+//! Covers the rules newly chosen passwords are checked against.
+
+mod common;
+
+use std::collections::HashSet;
+
+use iron_cms_api::domain::models::user::{CreateUserPlainText, Role, UpdateUserPlainText};
+use iron_cms_api::domain::services::user::UserService;
+use iron_cms_api::services::concrete::password_policy::ConfigurablePasswordPolicy;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::traits::password_policy::{PasswordOwner, PasswordPolicyService};
+
+use common::{Services, Setup};
+
+const OWNER: PasswordOwner = PasswordOwner { name: "Ada Lovelace", email: "countess.ada@example.com" };
+
+fn policy() -> ConfigurablePasswordPolicy {
+    let blocklist = ConfigurablePasswordPolicy::parse_blocklist("# comment\nLetMeIn123\n\n  dragonfly  \n");
+    ConfigurablePasswordPolicy::new(10, 20, blocklist)
+}
+
+/// The identifiers of every rule a password breaks.
+fn violations(policy: &ConfigurablePasswordPolicy, password: &str) -> Vec<String> {
+    policy.violations(password, &OWNER).into_iter().map(|item| item.identifier.unwrap_or_default()).collect()
+}
+
+#[test]
+fn length_is_counted_in_characters() {
+    let policy = policy();
+
+    assert_eq!(violations(&policy, "tangerine"), [constants::VAL_ERR_PASSWORD_TOO_SHORT]);
+    assert!(violations(&policy, "tangerines").is_empty());
+    assert!(violations(&policy, &"é".repeat(20)).is_empty());
+    assert_eq!(violations(&policy, &"é".repeat(21)), [constants::VAL_ERR_PASSWORD_TOO_LONG]);
+    assert_eq!(policy.max_length(), 20);
+}
+
+#[test]
+fn blocklisted_passwords_are_refused() {
+    let policy = policy();
+
+    for password in ["letmein123", "LETMEIN123", "dragonfly ", " DragonFly"] {
+        assert!(violations(&policy, password).contains(&constants::VAL_ERR_PASSWORD_TOO_COMMON.to_string()), "{} was accepted", password);
+    }
+    assert!(violations(&policy, "letmein1234").is_empty());
+
+    let bundled = ConfigurablePasswordPolicy::parse_blocklist(include_str!("../assets/common_passwords.txt"));
+    assert!(bundled.contains("qwerty123"));
+    assert!(!bundled.iter().any(|password| password.starts_with('#')));
+}
+
+#[test]
+fn names_and_emails_are_refused() {
+    let policy = policy();
+
+    for password in ["lovelace-2024", "XXcountessXX", "AdaLovelace!", "countess.ada!"] {
+        assert_eq!(violations(&policy, password), [constants::VAL_ERR_PASSWORD_PERSONAL], "{} was accepted", password);
+    }
+    // The email domain is shared with others, so it isn't personal.
+    assert!(violations(&policy, "example-tangerines").is_empty());
+}
+
+#[test]
+fn every_violation_is_reported() {
+    let policy = ConfigurablePasswordPolicy::new(12, 128, HashSet::from(["lovelace".to_string()]));
+
+    assert_eq!(violations(&policy, "Lovelace"), [
+        constants::VAL_ERR_PASSWORD_TOO_SHORT,
+        constants::VAL_ERR_PASSWORD_TOO_COMMON,
+        constants::VAL_ERR_PASSWORD_PERSONAL,
+    ]);
+
+    let err = policy.check("Lovelace", "lovelace", &OWNER).unwrap_err();
+    let fields: Vec<&str> = err.errors.iter().map(|item| item.context.as_str()).collect();
+    assert_eq!(fields, ["password", "password", "password", "confirm_password"]);
+}
+
+#[actix_web::test]
+async fn creating_and_updating_users_apply_the_policy() {
+    let services = Services::new(Setup::default());
+    let admin = services.add_user("admin@example.com", Role::Admin).await;
+
+    let new_user = CreateUserPlainText {
+        role: None,
+        name: "Grace Hopper".to_string(),
+        email: "grace@example.com".to_string(),
+        password: "hopper".to_string(),
+        confirm_password: "hopper".to_string(),
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    let err = services.user_service.create(new_user, Some(&admin)).await.unwrap_err();
+    let identifiers: Vec<_> = err.errors.iter().filter_map(|item| item.identifier.as_deref()).collect();
+    assert_eq!(identifiers, [constants::VAL_ERR_PASSWORD_TOO_SHORT, constants::VAL_ERR_PASSWORD_PERSONAL]);
+
+    let update = UpdateUserPlainText {
+        role: None,
+        name: None,
+        email: None,
+        password: Some("admin-admin".to_string()),
+        confirm_password: Some("admin-admin".to_string()),
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    let err = services.user_service.update(admin.id, update, &admin).await.unwrap_err();
+    assert_eq!(err.errors[0].identifier.as_deref(), Some(constants::VAL_ERR_PASSWORD_PERSONAL));
+    assert_eq!(services.users.updates(), 0);
+}
@@ -0,0 +1,115 @@
+//! Covers upgrading stored password hashes when the Argon2 settings change.
+//! Login runs against in-memory repositories, so no database is needed.
+
+mod common;
+
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHasher, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use uuid::Uuid;
+
+use iron_cms_api::domain::models::auth::{AuthLogin, VerifiedAction};
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::infrastructure::services::email_verification::EmailVerificationPolicy;
+use iron_cms_api::services::concrete::argon2id_hash::Argon2IdHashService;
+use iron_cms_api::services::concrete::hashing_pool::HashingPool;
+use iron_cms_api::services::traits::password_hash::PasswordHashService;
+
+use common::{new_user, Services, Setup, PASSWORD};
+
+const EMAIL: &str = "rehash@example.com";
+
+/// Small costs keep the tests fast; only the difference between them matters.
+fn old_params() -> Params {
+    Params::new(1024, 1, 1, None).unwrap()
+}
+
+fn current_params() -> Params {
+    Params::new(2048, 2, 1, None).unwrap()
+}
+
+fn hasher(params: Params) -> Argon2IdHashService {
+    Argon2IdHashService::with_params(params, Arc::new(HashingPool::new(2, 8)))
+}
+
+fn hash_with(algorithm: Algorithm, params: Params, password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::new(algorithm, Version::V0x13, params)
+        .hash_password(password.as_bytes(), &salt)
+        .unwrap()
+        .to_string()
+}
+
+/// Services whose only user signs in with `PASSWORD`, stored as `password_hash`.
+fn services(password_hash: String, hash_service: Arc<dyn PasswordHashService>) -> (Services, Uuid) {
+    let services = Services::new(Setup {
+        hash_service: Some(hash_service),
+        verification: EmailVerificationPolicy { required_for: vec![VerifiedAction::Login], resend_cooldown_secs: 0 },
+        ..Setup::default()
+    });
+    let user_id = services.users.insert(new_user(EMAIL, Role::User, &password_hash)).id;
+    (services, user_id)
+}
+
+async fn login(services: &Services, password: &str) -> bool {
+    let credentials = AuthLogin { email: EMAIL.to_string(), password: password.to_string() };
+    let client = SessionClient { user_agent: None, ip_address: None };
+    services.auth.login(credentials, client).await.is_ok()
+}
+
+#[actix_web::test]
+async fn hash_with_current_params_is_kept() {
+    let hash_service = hasher(current_params());
+    let hash = hash_service.hash_password(PASSWORD).await.unwrap();
+
+    assert!(!hash_service.needs_rehash(&hash));
+}
+
+#[test]
+fn hash_with_outdated_params_needs_rehash() {
+    let hash_service = hasher(current_params());
+
+    assert!(hash_service.needs_rehash(&hash_with(Algorithm::Argon2id, old_params(), PASSWORD)));
+    assert!(hash_service.needs_rehash(&hash_with(Algorithm::Argon2id, Params::new(2048, 2, 1, Some(64)).unwrap(), PASSWORD)));
+}
+
+#[test]
+fn hash_with_other_algorithm_needs_rehash() {
+    let hash_service = hasher(current_params());
+
+    assert!(hash_service.needs_rehash(&hash_with(Algorithm::Argon2i, current_params(), PASSWORD)));
+}
+
+#[actix_web::test]
+async fn login_upgrades_outdated_hash() {
+    let hash_service: Arc<dyn PasswordHashService> = Arc::new(hasher(current_params()));
+    let (services, user_id) = services(hash_with(Algorithm::Argon2i, old_params(), PASSWORD), hash_service.clone());
+
+    assert!(login(&services, PASSWORD).await);
+
+    let upgraded = services.users.user(user_id).password_hash;
+    assert_eq!(services.users.updates(), 1);
+    assert!(upgraded.starts_with("$argon2id$"));
+    assert!(!hash_service.needs_rehash(&upgraded));
+    assert!(hash_service.verify_password(PASSWORD, &upgraded).await.unwrap());
+
+    // Once upgraded, later logins leave the hash alone.
+    assert!(login(&services, PASSWORD).await);
+    assert_eq!(services.users.updates(), 1);
+    assert_eq!(services.users.user(user_id).password_hash, upgraded);
+}
+
+#[actix_web::test]
+async fn failed_login_keeps_outdated_hash() {
+    let outdated = hash_with(Algorithm::Argon2id, old_params(), PASSWORD);
+    let (services, user_id) = services(outdated.clone(), Arc::new(hasher(current_params())));
+
+    assert!(!login(&services, "wrong password").await);
+
+    assert_eq!(services.users.updates(), 0);
+    assert_eq!(services.users.user(user_id).password_hash, outdated);
+}
@@ -0,0 +1,92 @@
+//! Covers resetting a forgotten password with a token sent by mail.
+
+mod common;
+
+use iron_cms_api::domain::error::RepositoryErrorKind;
+use iron_cms_api::domain::models::auth::{AuthForgotPassword, AuthLogin, AuthResetPassword};
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::services::constants;
+
+use common::{Services, Setup, PASSWORD};
+
+const EMAIL: &str = "reset@example.com";
+const NEW_PASSWORD: &str = "a brand new passphrase";
+
+fn forgot(email: &str) -> AuthForgotPassword {
+    AuthForgotPassword { email: email.to_string() }
+}
+
+fn reset(token: &str) -> AuthResetPassword {
+    AuthResetPassword {
+        token: token.to_string(),
+        password: NEW_PASSWORD.to_string(),
+        confirm_password: NEW_PASSWORD.to_string(),
+    }
+}
+
+async fn can_login(services: &Services, password: &str) -> bool {
+    let credentials = AuthLogin { email: EMAIL.to_string(), password: password.to_string() };
+    services.auth.login(credentials, SessionClient::default()).await.is_ok()
+}
+
+#[actix_web::test]
+async fn reset_token_is_only_redeemed_once() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    assert!(can_login(&services, PASSWORD).await);
+
+    services.auth.forgot_password(forgot(EMAIL)).await.unwrap();
+    let token = services.mail.last_token();
+    services.auth.reset_password(reset(&token)).await.unwrap();
+    assert!(can_login(&services, NEW_PASSWORD).await);
+    assert!(!can_login(&services, PASSWORD).await);
+
+    let err = services.auth.reset_password(reset(&token)).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_RESET_TOKEN_INVALID);
+}
+
+#[actix_web::test]
+async fn reset_ends_every_session() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    assert!(can_login(&services, PASSWORD).await);
+    assert_eq!(services.sessions.count(), 1);
+
+    services.auth.forgot_password(forgot(EMAIL)).await.unwrap();
+    services.auth.reset_password(reset(&services.mail.last_token())).await.unwrap();
+    assert_eq!(services.sessions.count(), 0);
+}
+
+#[actix_web::test]
+async fn new_reset_token_replaces_the_old_one() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+
+    services.auth.forgot_password(forgot(EMAIL)).await.unwrap();
+    let old_token = services.mail.last_token();
+    services.auth.forgot_password(forgot(EMAIL)).await.unwrap();
+
+    let err = services.auth.reset_password(reset(&old_token)).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_RESET_TOKEN_INVALID);
+    services.auth.reset_password(reset(&services.mail.last_token())).await.unwrap();
+}
+
+#[actix_web::test]
+async fn unknown_emails_get_no_mail() {
+    let services = Services::new(Setup::default());
+    services.auth.forgot_password(forgot("nobody@example.com")).await.unwrap();
+    assert!(services.mail.sent().is_empty());
+}
+
+#[actix_web::test]
+async fn lookup_failures_are_not_taken_for_unknown_emails() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    services.users.go_offline();
+
+    let err = services.auth.forgot_password(forgot(EMAIL)).await.unwrap_err();
+    assert_eq!(err.identifier, RepositoryErrorKind::Connection.identifier());
+    assert!(services.mail.sent().is_empty());
+}
@@ -0,0 +1,91 @@
+//! Covers the server-side peppers mixed into password hashes and rotating
+//! them on login.
+
+mod common;
+
+use std::sync::Arc;
+
+use argon2::Params;
+use uuid::Uuid;
+
+use iron_cms_api::domain::models::auth::AuthLogin;
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::services::concrete::argon2id_hash::Argon2IdHashService;
+use iron_cms_api::services::concrete::hashing_pool::HashingPool;
+use iron_cms_api::services::concrete::pepper::PepperRing;
+use iron_cms_api::services::traits::password_hash::PasswordHashService;
+
+use common::{new_user, Services, Setup, PASSWORD};
+
+const EMAIL: &str = "pepper@example.com";
+const PEPPER_V1: &str = "1:first-pepper-first-pepper-first-pepper";
+const PEPPER_V2: &str = "2:second-pepper-second-pepper-second-pepper";
+
+fn params() -> Params {
+    Params::new(2048, 2, 1, None).unwrap()
+}
+
+fn hasher() -> Argon2IdHashService {
+    Argon2IdHashService::with_params(params(), Arc::new(HashingPool::new(2, 8)))
+}
+
+fn peppered_hasher(peppers: &str) -> Argon2IdHashService {
+    let peppers = PepperRing::parse(peppers).unwrap();
+    Argon2IdHashService::with_peppers(params(), peppers, Arc::new(HashingPool::new(2, 8)))
+}
+
+/// Services whose only user signs in with `PASSWORD`, stored as `password_hash`.
+fn services(password_hash: &str, hash_service: Arc<dyn PasswordHashService>) -> (Services, Uuid) {
+    let services = Services::new(Setup { hash_service: Some(hash_service), ..Setup::default() });
+    let user_id = services.users.insert(new_user(EMAIL, Role::User, password_hash)).id;
+    (services, user_id)
+}
+
+#[test]
+fn peppers_are_validated() {
+    assert!(PepperRing::parse(&std::format!("{},{}", PEPPER_V1, PEPPER_V2)).is_ok());
+    assert!(PepperRing::parse("a-pepper-without-a-version-is-version-one").unwrap().current().is_some_and(|(version, _)| version == 1));
+    assert!(PepperRing::parse("1:too-short").is_err());
+    assert!(PepperRing::parse("0:a-pepper-with-an-invalid-version-number").is_err());
+    assert!(PepperRing::parse(&std::format!("{},{}", PEPPER_V1, PEPPER_V1)).is_err());
+}
+
+#[test]
+fn debug_output_leaves_out_the_peppers() {
+    let peppers = PepperRing::parse(&std::format!("{}\n{}", PEPPER_V1, PEPPER_V2)).unwrap();
+    let debug = std::format!("{:?}", peppers);
+
+    assert_eq!(debug, "PepperRing { versions: [1, 2] }");
+    assert!(!debug.contains("pepper-"));
+}
+
+#[actix_web::test]
+async fn pepper_is_required_to_verify() {
+    let hash = peppered_hasher(PEPPER_V1).hash_password(PASSWORD).await.unwrap();
+
+    assert!(hasher().verify_password(PASSWORD, &hash).await.is_err());
+    assert!(peppered_hasher(PEPPER_V2).verify_password(PASSWORD, &hash).await.is_err());
+    assert!(peppered_hasher(PEPPER_V1).verify_password(PASSWORD, &hash).await.unwrap());
+}
+
+#[actix_web::test]
+async fn login_moves_hashes_to_the_current_pepper() {
+    let unpeppered = hasher().hash_password(PASSWORD).await.unwrap();
+    let old_pepper = peppered_hasher(PEPPER_V1).hash_password(PASSWORD).await.unwrap();
+
+    for stored in [unpeppered, old_pepper] {
+        let hash_service: Arc<dyn PasswordHashService> = Arc::new(peppered_hasher(&std::format!("{}\n{}", PEPPER_V1, PEPPER_V2)));
+        let (services, user_id) = services(&stored, hash_service.clone());
+
+        let credentials = AuthLogin { email: EMAIL.to_string(), password: PASSWORD.to_string() };
+        assert!(services.auth.login(credentials, SessionClient::default()).await.is_ok());
+
+        let upgraded = services.users.user(user_id).password_hash;
+        assert_eq!(services.users.updates(), 1);
+        assert!(!hash_service.needs_rehash(&upgraded));
+        // Once rotated, the retired pepper is no longer needed.
+        assert!(peppered_hasher(PEPPER_V2).verify_password(PASSWORD, &upgraded).await.unwrap());
+    }
+}
@@ -0,0 +1,55 @@
+//! Covers people signing up for an account themselves.
+
+mod common;
+
+use iron_cms_api::domain::models::auth::AuthRegister;
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::services::constants;
+
+use common::{Services, Setup, PASSWORD};
+
+fn registration(email: &str, password: &str, confirm_password: &str) -> AuthRegister {
+    AuthRegister {
+        email: email.to_string(),
+        name: "New Member".to_string(),
+        password: password.to_string(),
+        confirm_password: confirm_password.to_string(),
+    }
+}
+
+#[actix_web::test]
+async fn registering_signs_in_a_plain_user() {
+    let services = Services::new(Setup::default());
+
+    let tokens = services.auth.register(registration("member@example.com", PASSWORD, PASSWORD), SessionClient::default())
+        .await
+        .unwrap()
+        .expect("no email verification is required");
+    let authenticated_user = services.auth.authenticate(&tokens.token).await.unwrap();
+    assert_eq!(authenticated_user.user.role, Role::User);
+    assert_ne!(authenticated_user.user.password_hash, PASSWORD);
+    assert!(services.hash_service.verify_password(PASSWORD, &authenticated_user.user.password_hash).await.unwrap());
+}
+
+#[actix_web::test]
+async fn registering_a_taken_email_fails() {
+    let services = Services::new(Setup::default());
+    services.add_user("member@example.com", Role::Admin).await;
+
+    let err = services.auth.register(registration("MEMBER@example.com", PASSWORD, PASSWORD), SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.identifier, constants::USER_ERR_EMAIL_TAKEN);
+    assert_eq!(services.sessions.count(), 0);
+}
+
+#[actix_web::test]
+async fn registering_checks_the_password() {
+    let services = Services::new(Setup::default());
+
+    let err = services.auth.register(registration("member@example.com", PASSWORD, "something else"), SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.identifier, constants::VAL_ERR_VALIDATION_FAILED);
+    let err = services.auth.register(registration("member@example.com", "short", "short"), SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.errors[0].identifier.as_deref(), Some(constants::VAL_ERR_PASSWORD_TOO_SHORT));
+    assert_eq!(services.sessions.count(), 0);
+}
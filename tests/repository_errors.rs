@@ -0,0 +1,93 @@
+//! Covers how database errors are classified and what callers make of them.
+
+mod common;
+
+use diesel::result::{DatabaseErrorInformation, DatabaseErrorKind, Error as DieselError};
+
+use iron_cms_api::domain::constants as domain_constants;
+use iron_cms_api::domain::error::{CommonError, RepositoryErrorKind};
+use iron_cms_api::domain::models::common::ErrorResponseType;
+use iron_cms_api::domain::models::user::{CreateUserPlainText, Role};
+use iron_cms_api::domain::services::user::UserService;
+use iron_cms_api::infrastructure::error::DieselRepositoryError;
+use iron_cms_api::services::constants;
+
+use common::{Services, Setup, PASSWORD};
+
+/// What Postgres reports along with a constraint violation.
+struct Violation(&'static str);
+
+impl DatabaseErrorInformation for Violation {
+    fn message(&self) -> &str {
+        "violates constraint"
+    }
+    fn details(&self) -> Option<&str> {
+        None
+    }
+    fn hint(&self) -> Option<&str> {
+        None
+    }
+    fn table_name(&self) -> Option<&str> {
+        None
+    }
+    fn column_name(&self) -> Option<&str> {
+        None
+    }
+    fn constraint_name(&self) -> Option<&str> {
+        Some(self.0)
+    }
+    fn statement_position(&self) -> Option<i32> {
+        None
+    }
+}
+
+fn kind_of(error: DieselError) -> RepositoryErrorKind {
+    DieselRepositoryError::from(error).into_inner().kind
+}
+
+#[test]
+fn diesel_errors_keep_their_kind() {
+    assert_eq!(kind_of(DieselError::NotFound), RepositoryErrorKind::NotFound);
+
+    let unique = DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, Box::new(Violation(domain_constants::DB_CONSTRAINT_USERS_EMAIL)));
+    assert!(kind_of(unique).is_unique_violation_of(domain_constants::DB_CONSTRAINT_USERS_EMAIL));
+
+    let foreign_key = DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, Box::new(Violation(domain_constants::DB_CONSTRAINT_ADMIN_PERMISSIONS_USER_ID)));
+    let kind = kind_of(foreign_key);
+    assert!(kind.is_foreign_key_violation_of(domain_constants::DB_CONSTRAINT_ADMIN_PERMISSIONS_USER_ID));
+    assert!(!kind.is_unique_violation_of(domain_constants::DB_CONSTRAINT_ADMIN_PERMISSIONS_USER_ID));
+
+    let closed = DieselError::DatabaseError(DatabaseErrorKind::ClosedConnection, Box::new("server closed the connection".to_string()));
+    assert_eq!(kind_of(closed), RepositoryErrorKind::Connection);
+    assert_eq!(kind_of(DieselError::RollbackTransaction), RepositoryErrorKind::Other);
+}
+
+#[test]
+fn classified_errors_carry_their_catalog_entry() {
+    let unique = DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, Box::new(Violation("some_key")));
+    let error = CommonError::from(DieselRepositoryError::from(unique).into_inner());
+    assert_eq!(error.identifier, domain_constants::REPO_ERR_UNIQUE_VIOLATION);
+    assert_eq!(error.error_type, ErrorResponseType::Conflict);
+
+    let error = CommonError::from(DieselRepositoryError::from(DieselError::NotFound).into_inner());
+    assert_eq!(error.error_type, ErrorResponseType::NotFound);
+}
+
+#[actix_web::test]
+async fn taken_emails_are_a_conflict() {
+    let services = Services::new(Setup::default());
+    services.add_user("taken@example.com", Role::User).await;
+
+    let new_user = CreateUserPlainText {
+        role: None,
+        name: "Someone Else".to_string(),
+        email: "Taken@Example.com".to_string(),
+        password: PASSWORD.to_string(),
+        confirm_password: PASSWORD.to_string(),
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    let err = services.user_service.create(new_user, None).await.unwrap_err();
+    assert_eq!(err.identifier, constants::USER_ERR_EMAIL_TAKEN);
+    assert_eq!(err.error_type, ErrorResponseType::Conflict);
+}
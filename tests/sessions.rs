@@ -0,0 +1,54 @@
+//! Covers refresh tokens and the sessions that back them.
+
+mod common;
+
+use iron_cms_api::domain::models::auth::{AuthLogin, AuthLoginOutcome, AuthRefresh, AuthSuccessfulResponse};
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::services::constants;
+
+use common::{Services, Setup, PASSWORD};
+
+const EMAIL: &str = "sessions@example.com";
+
+async fn login(services: &Services) -> AuthSuccessfulResponse {
+    let credentials = AuthLogin { email: EMAIL.to_string(), password: PASSWORD.to_string() };
+    match services.auth.login(credentials, SessionClient::default()).await.unwrap() {
+        AuthLoginOutcome::Authenticated(tokens) => tokens,
+        AuthLoginOutcome::MfaRequired(_) => panic!("login asked for a second factor"),
+    }
+}
+
+fn refresh(tokens: &AuthSuccessfulResponse) -> AuthRefresh {
+    AuthRefresh { refresh_token: tokens.refresh_token.clone() }
+}
+
+#[actix_web::test]
+async fn refresh_replaces_the_session() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    let tokens = login(&services).await;
+
+    let refreshed = services.auth.refresh(refresh(&tokens), SessionClient::default()).await.unwrap();
+    assert!(services.auth.validate_access_token(&refreshed.token).await.is_ok());
+    assert_eq!(services.sessions.count(), 1);
+
+    // Replacing the session revoked its tokens.
+    let err = services.auth.refresh(refresh(&tokens), SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_REVOKED);
+    assert!(services.auth.refresh(refresh(&refreshed), SessionClient::default()).await.is_ok());
+}
+
+#[actix_web::test]
+async fn refresh_that_loses_a_race_gets_no_tokens() {
+    let services = Services::new(Setup::default());
+    services.add_user(EMAIL, Role::User).await;
+    let tokens = login(&services).await;
+
+    // Another refresh with the same token ends the session first.
+    services.sessions.lose_next_race();
+    let err = services.auth.refresh(refresh(&tokens), SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_SESSION_INVALID);
+    assert_eq!(services.sessions.count(), 0);
+}
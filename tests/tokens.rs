@@ -0,0 +1,111 @@
+//! Covers the signed, encrypted tokens and that each is only accepted for the
+//! purpose it was issued for.
+
+mod common;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use uuid::Uuid;
+
+use iron_cms_api::domain::models::auth::AuthRefresh;
+use iron_cms_api::domain::models::common::{EncryptedTokenPayload, TokenType};
+use iron_cms_api::domain::models::session::SessionClient;
+use iron_cms_api::domain::models::user::Role;
+use iron_cms_api::domain::services::auth::AuthService;
+use iron_cms_api::services::concrete::jwt_token::JwtTokenService;
+use iron_cms_api::services::constants;
+use iron_cms_api::services::traits::token::TokenService;
+
+use common::{set_token_env, Services, Setup};
+
+const JWT_SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+fn token_service() -> JwtTokenService {
+    set_token_env();
+    JwtTokenService::new()
+}
+
+/// The outer JWT of a token, which is only signed.
+fn outer_payload(token: &str) -> EncryptedTokenPayload {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.required_spec_claims.clear();
+    validation.validate_exp = false;
+    decode::<EncryptedTokenPayload>(token, &DecodingKey::from_secret(JWT_SECRET.as_bytes()), &validation)
+        .unwrap()
+        .claims
+}
+
+fn sign(payload: &EncryptedTokenPayload) -> String {
+    encode(&Header::new(Algorithm::HS256), payload, &EncodingKey::from_secret(JWT_SECRET.as_bytes())).unwrap()
+}
+
+#[test]
+fn tokens_are_only_accepted_for_their_own_purpose() {
+    let token_service = token_service();
+    let (user_id, token_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let access_token = token_service.generate_access_token(user_id, token_id, false).unwrap().token;
+    let refresh_token = token_service.generate_refresh_token(user_id, token_id, false).unwrap().token;
+
+    assert_eq!(token_service.validate_access_token(&access_token).unwrap().sub, user_id.to_string());
+    assert_eq!(token_service.validate_refresh_token(&refresh_token).unwrap().jti, token_id.to_string());
+
+    let err = token_service.validate_refresh_token(&access_token).unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_TYPE_MISMATCH);
+    let err = token_service.validate_access_token(&refresh_token).unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_TYPE_MISMATCH);
+    assert!(token_service.validate_access_token(&token_service.generate_mfa_challenge_token(user_id).unwrap().token).is_err());
+}
+
+#[test]
+fn claims_are_not_readable_from_the_token() {
+    let token_service = token_service();
+    let user_id = Uuid::new_v4();
+    let token = token_service.generate_access_token(user_id, Uuid::new_v4(), false).unwrap().token;
+
+    let body = URL_SAFE_NO_PAD.decode(token.split('.').nth(1).unwrap()).unwrap();
+    let body = String::from_utf8(body).unwrap();
+    assert!(!body.contains(&user_id.to_string()));
+    assert!(!body.contains("jti"));
+}
+
+#[test]
+fn encrypted_claims_are_bound_to_the_token_type() {
+    let token_service = token_service();
+    let access_token = token_service.generate_access_token(Uuid::new_v4(), Uuid::new_v4(), false).unwrap().token;
+
+    // Relabelling the payload of an access token and signing it again gets past
+    // the signature, but not the decryption.
+    let mut payload = outer_payload(&access_token);
+    payload.token_type = TokenType::RefreshToken;
+    let err = token_service.validate_refresh_token(&sign(&payload)).unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_INVALID);
+}
+
+#[test]
+fn tampered_tokens_are_rejected() {
+    let token_service = token_service();
+    let access_token = token_service.generate_access_token(Uuid::new_v4(), Uuid::new_v4(), false).unwrap().token;
+
+    let mut payload = outer_payload(&access_token);
+    payload.payload.replace_range(20..24, "AAAA");
+    assert!(token_service.validate_access_token(&sign(&payload)).is_err());
+
+    let forged = encode(&Header::new(Algorithm::HS256), &outer_payload(&access_token), &EncodingKey::from_secret(b"another secret")).unwrap();
+    assert!(token_service.validate_access_token(&forged).is_err());
+}
+
+#[actix_web::test]
+async fn auth_refuses_tokens_of_the_other_type() {
+    let services = Services::new(Setup::default());
+    let user = services.add_user("tokens@example.com", Role::User).await;
+    let token_id = Uuid::new_v4();
+    let access_token = services.token_service.generate_access_token(user.id, token_id, false).unwrap().token;
+    let refresh_token = services.token_service.generate_refresh_token(user.id, token_id, false).unwrap().token;
+
+    let refresh = AuthRefresh { refresh_token: access_token };
+    let err = services.auth.refresh(refresh, SessionClient::default()).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_TYPE_MISMATCH);
+
+    let err = services.auth.authenticate(&refresh_token).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_TOKEN_TYPE_MISMATCH);
+}
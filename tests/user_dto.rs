@@ -0,0 +1,54 @@
+//! Covers what the user responses reveal about an account.
+
+mod common;
+
+use chrono::{Duration, Utc};
+
+use iron_cms_api::api::dto::user::{UserDetailsDto, UserDto};
+use iron_cms_api::domain::models::user::{Role, User};
+
+use common::new_user;
+
+const PASSWORD_HASH: &str = "$argon2id$v=19$m=1024,t=1,p=1$c2FsdHNhbHQ$aGFzaGhhc2hoYXNo";
+const RESET_TOKEN: &str = "5f0c6b2e9a1d4c3b8e7f6a5d4c3b2a19";
+const TOTP_SECRET: &str = "encrypted-totp-secret";
+
+fn user_with_secrets() -> User {
+    User {
+        reset_token: Some(RESET_TOKEN.to_string()),
+        reset_token_expiry: Some(Utc::now().naive_utc() + Duration::try_hours(1).unwrap()),
+        totp_secret: Some(TOTP_SECRET.to_string()),
+        ..new_user("dto@example.com", Role::Admin, PASSWORD_HASH)
+    }
+}
+
+fn assert_no_secrets(json: &str) {
+    for secret in [PASSWORD_HASH, RESET_TOKEN, TOTP_SECRET, "password_hash", "reset_token\"", "totp_secret"] {
+        assert!(!json.contains(secret), "{} leaks into {}", secret, json);
+    }
+}
+
+#[test]
+fn user_responses_leave_out_credentials() {
+    let json = serde_json::to_string(&UserDto::from(user_with_secrets())).unwrap();
+    assert_no_secrets(&json);
+    assert!(json.contains("\"role_name\":\"admin\""));
+
+    let json = serde_json::to_string(&UserDetailsDto::from(user_with_secrets())).unwrap();
+    assert_no_secrets(&json);
+}
+
+#[test]
+fn details_only_tell_whether_a_reset_is_pending() {
+    let details = UserDetailsDto::from(user_with_secrets());
+    assert!(details.reset_pending);
+    assert!(details.reset_token_expiry.is_some());
+
+    let expired = User {
+        reset_token_expiry: Some(Utc::now().naive_utc() - Duration::try_minutes(1).unwrap()),
+        ..user_with_secrets()
+    };
+    let details = UserDetailsDto::from(expired);
+    assert!(!details.reset_pending);
+    assert!(details.reset_token_expiry.is_none());
+}
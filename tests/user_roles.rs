@@ -0,0 +1,100 @@
+//! Covers who may hand out roles and change the accounts that hold them.
+
+mod common;
+
+use iron_cms_api::domain::models::user::{CreateUserHashed, CreateUserPlainText, Role, UpdateUserPlainText};
+use iron_cms_api::domain::services::user::UserService;
+use iron_cms_api::services::constants;
+
+use common::{Services, Setup, PASSWORD};
+
+fn new_account(email: &str, role: Option<Role>) -> CreateUserPlainText {
+    CreateUserPlainText {
+        role,
+        name: "New Account".to_string(),
+        email: email.to_string(),
+        password: PASSWORD.to_string(),
+        confirm_password: PASSWORD.to_string(),
+        reset_token: None,
+        reset_token_expiry: None,
+    }
+}
+
+fn change(role: Option<Role>, name: Option<&str>) -> UpdateUserPlainText {
+    UpdateUserPlainText {
+        role,
+        name: name.map(str::to_string),
+        email: None,
+        password: None,
+        confirm_password: None,
+        reset_token: None,
+        reset_token_expiry: None,
+    }
+}
+
+#[actix_web::test]
+async fn only_super_admins_assign_elevated_roles() {
+    let services = Services::new(Setup::default());
+    let admin = services.add_user("admin@example.com", Role::Admin).await;
+    let super_admin = services.add_user("super@example.com", Role::SuperAdmin).await;
+
+    for role in [Role::Admin, Role::SuperAdmin] {
+        let err = services.user_service.create(new_account("new@example.com", Some(role)), Some(&admin)).await.unwrap_err();
+        assert_eq!(err.identifier, constants::SEC_ERR_PERMISSION_DENIED);
+    }
+    let err = services.user_service.create(new_account("new@example.com", Some(Role::Admin)), None).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_PERMISSION_DENIED);
+
+    let imported = CreateUserHashed {
+        role: Some(Role::SuperAdmin),
+        name: "Imported".to_string(),
+        email: "imported@example.com".to_string(),
+        password_hash: admin.password_hash.clone(),
+        reset_token: None,
+        reset_token_expiry: None,
+    };
+    let err = services.user_service.import(vec![imported], &admin).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_PERMISSION_DENIED);
+
+    let user = services.user_service.create(new_account("user@example.com", None), Some(&admin)).await.unwrap();
+    assert_eq!(user.role, Role::User);
+    let promoted = services.user_service.create(new_account("promoted@example.com", Some(Role::Admin)), Some(&super_admin)).await.unwrap();
+    assert_eq!(promoted.role, Role::Admin);
+}
+
+#[actix_web::test]
+async fn admins_cannot_change_roles_including_their_own() {
+    let services = Services::new(Setup::default());
+    let admin = services.add_user("admin@example.com", Role::Admin).await;
+    let user = services.add_user("user@example.com", Role::User).await;
+
+    for user_id in [admin.id, user.id] {
+        let err = services.user_service.update(user_id, change(Some(Role::SuperAdmin), None), &admin).await.unwrap_err();
+        assert_eq!(err.identifier, constants::SEC_ERR_PERMISSION_DENIED);
+    }
+    let err = services.user_service.update(admin.id, change(Some(Role::User), None), &admin).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_PERMISSION_DENIED);
+    assert_eq!(services.users.user(admin.id).role, Role::Admin);
+    assert_eq!(services.users.updates(), 0);
+
+    // Other changes are still theirs to make.
+    let renamed = services.user_service.update(user.id, change(None, Some("Renamed")), &admin).await.unwrap();
+    assert_eq!(renamed.name, "Renamed");
+}
+
+#[actix_web::test]
+async fn accounts_of_super_admins_are_left_to_super_admins() {
+    let services = Services::new(Setup::default());
+    let admin = services.add_user("admin@example.com", Role::Admin).await;
+    let super_admin = services.add_user("super@example.com", Role::SuperAdmin).await;
+    let other = services.add_user("other@example.com", Role::SuperAdmin).await;
+
+    let err = services.user_service.update(super_admin.id, change(None, Some("Renamed")), &admin).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_PERMISSION_DENIED);
+    let err = services.user_service.delete(super_admin.id, &admin).await.unwrap_err();
+    assert_eq!(err.identifier, constants::SEC_ERR_PERMISSION_DENIED);
+
+    let demoted = services.user_service.update(other.id, change(Some(Role::Admin), None), &super_admin).await.unwrap();
+    assert_eq!(demoted.role, Role::Admin);
+    assert!(services.user_service.delete(other.id, &super_admin).await.unwrap());
+}
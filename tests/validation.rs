@@ -0,0 +1,108 @@
+//! Covers the checks request bodies go through before they reach a handler.
+
+use actix_web::http::StatusCode;
+use actix_web::test::{call_service, init_service, read_body_json, TestRequest};
+use actix_web::{web, App, HttpResponse};
+use serde_json::{json, Value};
+
+use iron_cms_api::api::dto::admin_permission::CreateAdminPermissionDto;
+use iron_cms_api::api::dto::user::{CreateUserPlainTextDto, ImportUsersDto, UpdateUserPlainTextDto};
+use iron_cms_api::api::validation::{json_error_handler, ValidatedJson};
+use iron_cms_api::domain::models::common::ErrorResponse;
+use iron_cms_api::services::constants;
+
+async fn accept<T>(_body: ValidatedJson<T>) -> HttpResponse {
+    HttpResponse::Ok().finish()
+}
+
+/// The status and the `(field, identifier)` of every error reported for a body.
+async fn post(path: &str, body: Value) -> (StatusCode, Vec<(String, String)>) {
+    let app = init_service(
+        App::new()
+            .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+            .route("/users", web::post().to(accept::<CreateUserPlainTextDto>))
+            .route("/users/update", web::post().to(accept::<UpdateUserPlainTextDto>))
+            .route("/users/import", web::post().to(accept::<ImportUsersDto>))
+            .route("/admin_permissions", web::post().to(accept::<CreateAdminPermissionDto>))
+    ).await;
+    let response = call_service(&app, TestRequest::post().uri(path).set_json(body).to_request()).await;
+    let status = response.status();
+    if status.is_success() {
+        return (status, Vec::new());
+    }
+    let body: ErrorResponse = read_body_json(response).await;
+    (status, body.errors.into_iter().map(|item| (item.context, item.identifier.unwrap_or_default())).collect())
+}
+
+fn error(field: &str, identifier: &str) -> (String, String) {
+    (field.to_string(), identifier.to_string())
+}
+
+#[actix_web::test]
+async fn valid_bodies_pass() {
+    let user = json!({ "role": "admin", "name": "Ada", "email": "ada@example.com", "password": "pass", "confirm_password": "pass" });
+    assert_eq!(post("/users", user).await, (StatusCode::OK, Vec::new()));
+    assert_eq!(post("/users/update", json!({ "role": 1 })).await, (StatusCode::OK, Vec::new()));
+}
+
+#[actix_web::test]
+async fn every_invalid_field_is_reported() {
+    let user = json!({ "role": "owner", "name": " ", "email": "not an email", "password": "", "confirm_password": "" });
+    let (status, errors) = post("/users", user).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(errors, [
+        error("role", constants::VAL_ERR_INVALID_ENUM_VALUE),
+        error("name", constants::VAL_ERR_FIELD_REQUIRED),
+        error("email", constants::VAL_ERR_INVALID_EMAIL),
+        error("password", constants::VAL_ERR_FIELD_REQUIRED),
+        error("confirm_password", constants::VAL_ERR_FIELD_REQUIRED),
+    ]);
+}
+
+#[actix_web::test]
+async fn long_values_are_refused() {
+    let user = json!({ "name": "a".repeat(256), "email": "ada@example.com", "password": "pass", "confirm_password": "pass" });
+    assert_eq!(post("/users", user).await.1, [error("name", constants::VAL_ERR_FIELD_TOO_LONG)]);
+}
+
+#[actix_web::test]
+async fn password_changes_need_both_fields() {
+    assert_eq!(post("/users/update", json!({ "password": "pass" })).await.1, [error("confirm_password", constants::VAL_ERR_FIELD_REQUIRED)]);
+    assert_eq!(post("/users/update", json!({ "confirm_password": "pass" })).await.1, [error("password", constants::VAL_ERR_FIELD_REQUIRED)]);
+}
+
+#[actix_web::test]
+async fn imports_are_checked_per_user() {
+    assert_eq!(post("/users/import", json!({ "users": [] })).await.1, [error("users", constants::VAL_ERR_FIELD_REQUIRED)]);
+
+    let users = json!({ "users": [
+        { "name": "Ada", "email": "ada@example.com", "password_hash": "$2b$10$abc" },
+        { "role": 7, "name": "Bob", "email": "bob", "password_hash": "" },
+    ] });
+    assert_eq!(post("/users/import", users).await.1, [
+        error("users[1].role", constants::VAL_ERR_INVALID_ENUM_VALUE),
+        error("users[1].email", constants::VAL_ERR_INVALID_EMAIL),
+        error("users[1].password_hash", constants::VAL_ERR_FIELD_REQUIRED),
+    ]);
+
+    let user = json!({ "name": "Ada", "email": "ada@example.com", "password_hash": "$2b$10$abc" });
+    let users = json!({ "users": vec![user; constants::USER_IMPORT_MAX_USERS + 1] });
+    assert_eq!(post("/users/import", users).await.1, [error("users", constants::VAL_ERR_TOO_MANY_ITEMS)]);
+}
+
+#[actix_web::test]
+async fn permission_grants_need_a_user_id_and_a_known_permission() {
+    let (status, errors) = post("/admin_permissions", json!({ "user_id": "42", "permission": "can_fly" })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(errors, [
+        error("user_id", constants::VAL_ERR_INVALID_UUID),
+        error("permission", constants::VAL_ERR_INVALID_ENUM_VALUE),
+    ]);
+}
+
+#[actix_web::test]
+async fn malformed_json_is_a_validation_error() {
+    let (status, errors) = post("/admin_permissions", json!({ "user_id": 42 })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(errors, [error("body", constants::VAL_ERR_INVALID_JSON)]);
+}